@@ -7,7 +7,10 @@ use image::*;
 #[derive(Debug)]
 pub struct Texture
 {
-    pub(crate) view: wgpu::TextureView
+    pub(crate) view: wgpu::TextureView,
+    /// how shaders read this texture's texels, ie. `Float`
+    /// for normalized colours sampled with filtering
+    pub(crate) component: wgpu::TextureComponentType,
 }
 
 impl crate::Bind for Texture
@@ -18,7 +21,7 @@ impl crate::Bind for Texture
         {
             multisampled: false,
             dimension: wgpu::TextureViewDimension::D2,
-            component_type: wgpu::TextureComponentType::Uint
+            component_type: self.component
         }
     }
 
@@ -30,9 +33,10 @@ impl crate::Bind for Texture
 
 impl Texture
 {
-    /// create a new texture from an image file's bytes. this
-    /// should not be called directly.
-    pub(crate) fn new(ctx: &crate::Renderer, name: &str, img: ImageResult<DynamicImage>) -> Self
+    /// create a new texture from an image file's bytes, read
+    /// by shaders as `component`. this should not be called
+    /// directly.
+    pub(crate) fn new(ctx: &crate::Renderer, name: &str, img: ImageResult<DynamicImage>, component: wgpu::TextureComponentType) -> Self
     {
         //let img = load_from_memory(bytes).unwrap();
         let img = img.unwrap();
@@ -96,7 +100,8 @@ impl Texture
 
         Self
         {
-            view: tex.create_default_view()                 // texture view
+            view: tex.create_default_view(),                // texture view
+            component,
         }
     }
 
@@ -129,7 +134,8 @@ impl Texture
 
         Self
         {
-            view: tex.create_default_view()                 // texture view
+            view: tex.create_default_view(),                // texture view
+            component: wgpu::TextureComponentType::Uint,
         }
     }
 }
\ No newline at end of file
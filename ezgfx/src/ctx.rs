@@ -154,7 +154,18 @@ impl Renderer
     /// ```
     pub fn texture_bytes(&self, name: &str, bytes: &[u8]) -> Texture
     {
-        Texture::new(self, name, image::load_from_memory(bytes))
+        Texture::new(self, name, image::load_from_memory(bytes), wgpu::TextureComponentType::Uint)
+    }
+
+    /// create a new texture from an image that's already
+    /// loaded or generated in memory, read by shaders as
+    /// `component`
+    /// ```rust
+    /// renderer.texture_image("atlas", img, wgpu::TextureComponentType::Float);
+    /// ```
+    pub fn texture_image(&self, name: &str, img: image::DynamicImage, component: wgpu::TextureComponentType) -> Texture
+    {
+        Texture::new(self, name, Ok(img), component)
     }
 
     /// create a new texture from the path of the image
@@ -162,7 +173,7 @@ impl Renderer
     {
         let path = path.as_ref();
 
-        Texture::new(self, path.to_str().unwrap(), image::open(path))
+        Texture::new(self, path.to_str().unwrap(), image::open(path), wgpu::TextureComponentType::Uint)
     }
 
     pub fn sampler(&self, desc: &SamplerDesc) -> Sampler
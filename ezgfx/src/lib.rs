@@ -20,6 +20,7 @@ pub use ctx::*;
 
 pub mod bytemuck { pub use bytemuck::*; }
 pub mod wgpu { pub use wgpu::*; }
+pub mod image { pub use image::*; }
 
 #[cfg(test)]
 mod tests;
\ No newline at end of file
@@ -25,6 +25,9 @@ struct PipelineSettings
 {
     winding: FaceWinding,
     culling: Culling,
+    blending: Blending,
+    topology: Topology,
+    depth_read_only: bool,
 }
 
 /// a temporary reference to a renderer.
@@ -108,6 +111,22 @@ impl<'a> PipelineBuilder<'a>
         self
     }
 
+    /// override the default colour blending mode.
+    /// default: replace
+    pub fn blending(mut self, mode: Blending) -> Self
+    {
+        self.settings.blending = mode;
+        self
+    }
+
+    /// override the default primitive topology.
+    /// default: triangles
+    pub fn topology(mut self, mode: Topology) -> Self
+    {
+        self.settings.topology = mode;
+        self
+    }
+
     /// set the vertex type used by this pipeline.
     /// this is a necesarry attribute and will panic
     /// on PipelineBuilder::build() if not set.
@@ -162,6 +181,16 @@ impl<'a> PipelineBuilder<'a>
         self
     }
 
+    /// keep depth testing, but don't write to the depth
+    /// buffer. this is useful for blended geometry, which
+    /// shouldn't hide what's drawn behind it afterwards.
+    /// has no effect if depth testing is off
+    pub fn depth_read_only(mut self, read_only: bool) -> Self
+    {
+        self.settings.depth_read_only = read_only;
+        self
+    }
+
     /// turn depth testing on or off. default is off
     pub fn depth(mut self, test: bool) -> Self
     {
@@ -190,6 +219,9 @@ impl<'a> PipelineBuilder<'a>
 
     pub fn build(self) -> super::Pipeline
     {
+        let (alpha_blend, color_blend) = self.settings.blending.to_wgpu();
+        let depth_write = !self.settings.depth_read_only;
+
         let layout = self.ctx.device.create_pipeline_layout
         (
             &wgpu::PipelineLayoutDescriptor
@@ -229,16 +261,20 @@ impl<'a> PipelineBuilder<'a>
                     depth_bias_slope_scale: 0.0,
                     depth_bias_clamp: 0.0
                 }),
-                primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+                primitive_topology: self.settings.topology.to_wgpu(),
                 color_states: &[wgpu::ColorStateDescriptor
                 {
                     format: wgpu::TextureFormat::Bgra8UnormSrgb,
-                    alpha_blend: wgpu::BlendDescriptor::REPLACE,
-                    color_blend: wgpu::BlendDescriptor::REPLACE,
+                    alpha_blend,
+                    color_blend,
                     write_mask: wgpu::ColorWrite::ALL,
                     
                 }],
-                depth_stencil_state: self.depth,
+                depth_stencil_state: self.depth.map(|mut depth|
+                {
+                    depth.depth_write_enabled = depth_write;
+                    depth
+                }),
                 vertex_state: wgpu::VertexStateDescriptor
                 {
                     index_format: self.index_format
@@ -284,6 +320,32 @@ pub enum Culling
     None,
 }
 
+/// how the colour output by the fragment shader is
+/// combined with the colour already in the frame.
+/// default: replace
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Blending
+{
+    /// the output overwrites the frame's colour
+    Replace,
+    /// the output is mixed with the frame's colour,
+    /// weighted by its alpha
+    Alpha,
+}
+
+/// how vertices, in index order, are assembled into
+/// primitives.
+/// default: triangles
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Topology
+{
+    /// every three vertices form a triangle
+    Triangles,
+    /// every two vertices form a line, ie. for
+    /// wireframes
+    Lines,
+}
+
 impl FaceWinding
 {
     /// translate this enum to a wgpu one
@@ -311,6 +373,46 @@ impl Culling
     }
 }
 
+impl Blending
+{
+    /// translate this enum to wgpu ones, as (alpha, colour)
+    fn to_wgpu(&self) -> (wgpu::BlendDescriptor, wgpu::BlendDescriptor)
+    {
+        match self
+        {
+            Blending::Replace => (wgpu::BlendDescriptor::REPLACE, wgpu::BlendDescriptor::REPLACE),
+            Blending::Alpha =>
+            (
+                wgpu::BlendDescriptor
+                {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                wgpu::BlendDescriptor
+                {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            ),
+        }
+    }
+}
+
+impl Topology
+{
+    /// translate this enum to a wgpu one
+    fn to_wgpu(&self) -> wgpu::PrimitiveTopology
+    {
+        match self
+        {
+            Topology::Triangles => wgpu::PrimitiveTopology::TriangleList,
+            Topology::Lines => wgpu::PrimitiveTopology::LineList,
+        }
+    }
+}
+
 impl Default for FaceWinding
 {
     fn default() -> Self
@@ -325,4 +427,20 @@ impl Default for Culling
     {
         Self::Back
     }
+}
+
+impl Default for Blending
+{
+    fn default() -> Self
+    {
+        Self::Replace
+    }
+}
+
+impl Default for Topology
+{
+    fn default() -> Self
+    {
+        Self::Triangles
+    }
 }
\ No newline at end of file
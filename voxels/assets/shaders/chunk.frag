@@ -1,10 +1,25 @@
 #version 450
 
 layout(location = 0) in vec2 v_uv;
+layout(location = 1) in float v_light;
 
 layout(location = 0) out vec4 f_color;
 
+layout(set = 2, binding = 0) uniform texture2D t_atlas;
+layout(set = 2, binding = 1) uniform sampler s_atlas;
+layout(set = 2, binding = 2) uniform Atlas
+{
+    uint u_tiles;
+};
+
 void main()
 {
-    f_color = vec4(v_uv.x, v_uv.y, 0.0, 1.0);
+    f_color = texture(sampler2D(t_atlas, s_atlas), v_uv / float(u_tiles));
+
+    // cutout holes
+    if (f_color.a < 0.01)
+    {
+        discard;
+    }
+    f_color.rgb *= v_light;
 }
\ No newline at end of file
@@ -3,6 +3,7 @@
 layout(location = 0) in uint a_compressed;
 
 layout(location = 0) out vec2 v_uv;
+layout(location = 1) out float v_light;
 
 layout(set = 0, binding = 0) uniform ViewProjection
 {
@@ -13,15 +14,28 @@ layout(set = 1, binding = 0) uniform ChunkOffset
     ivec3 u_offset;
 };
 
+// tile corner of each of a face's four vertices
+const vec2 CORNERS[4] = vec2[](vec2(0, 0), vec2(1, 0), vec2(1, 1), vec2(0, 1));
+
+// brightness of a light level, where each level down
+// from the brightest dims by a fifth
+float brightness(uint level)
+{
+    return max(pow(0.8, 15.0 - float(level)), 0.05);
+}
+
 void main()
 {
-    float x = float(a_compressed >> 26) + float(u_offset.x);
-    float y = float((a_compressed >> 20) & 63) + float(u_offset.y);
-    float z = float((a_compressed >> 14) & 63) + float(u_offset.z);
+    uint xyz = a_compressed >> 16;
+
+    float x = float(xyz % 33) + float(u_offset.x);
+    float y = float((xyz / 33) % 33) + float(u_offset.y);
+    float z = float(xyz / 1089) + float(u_offset.z);
 
-    float u = float((a_compressed >> 7) & 127) / 128;
-    float v = float(a_compressed & 127) / 128;
+    float u = float((a_compressed >> 10) & 63);
+    float v = float((a_compressed >> 4) & 63);
 
-    v_uv = vec2(u, v);
+    v_uv = vec2(u, v) + CORNERS[gl_VertexIndex % 4];
+    v_light = brightness(a_compressed & 15);
     gl_Position = u_view_proj * vec4(x, y, z, 1.0);
 }
\ No newline at end of file
@@ -0,0 +1,38 @@
+#version 450
+
+layout(location = 0) in uvec2 a_compressed;
+
+layout(location = 0) out vec2 v_uv;
+layout(location = 1) out float v_light;
+
+layout(set = 0, binding = 0) uniform ViewProjection
+{
+    mat4 u_view_proj;
+};
+layout(set = 1, binding = 0) uniform ChunkOffset
+{
+    ivec3 u_offset;
+};
+
+// brightness of a packed light value, where each level
+// down from the brightest dims by a fifth
+float brightness(uint light)
+{
+    float level = float(max(light >> 4, light & 15));
+
+    return max(pow(0.8, 15.0 - level), 0.05);
+}
+
+void main()
+{
+    float x = float(a_compressed.x >> 24) / 4 + float(u_offset.x);
+    float y = float((a_compressed.x >> 16) & 255) / 4 + float(u_offset.y);
+    float z = float((a_compressed.x >> 8) & 255) / 4 + float(u_offset.z);
+
+    float u = float((a_compressed.y >> 10) & 1023) / 4;
+    float v = float(a_compressed.y & 1023) / 4;
+
+    v_uv = vec2(u, v);
+    v_light = brightness(a_compressed.x & 255);
+    gl_Position = u_view_proj * vec4(x, y, z, 1.0);
+}
@@ -0,0 +1,17 @@
+#version 450
+
+layout(location = 0) in vec3 a_pos;
+
+layout(set = 0, binding = 0) uniform ViewProjection
+{
+    mat4 u_view_proj;
+};
+layout(set = 1, binding = 0) uniform BlockOffset
+{
+    ivec3 u_offset;
+};
+
+void main()
+{
+    gl_Position = u_view_proj * vec4(a_pos + vec3(u_offset), 1.0);
+}
\ No newline at end of file
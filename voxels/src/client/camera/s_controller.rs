@@ -35,8 +35,9 @@ impl System for SFpsController
                 // speed
                 let s = r_time.dt() * 4.0;
 
-                // look around
-                if r_in.button_down(MouseButton::Left)
+                // look around while shift is held, the
+                // mouse buttons break and place blocks
+                if r_in.key_down(KeyCode::LShift)
                 {
                     c_rot.0.x -= r_in.dy() as f32 * 0.01;
                     c_rot.0.y -= r_in.dx() as f32 * 0.01;
@@ -0,0 +1,27 @@
+use std::fmt;
+
+/// errors that can occur while packing the block
+/// texture atlas
+#[derive(Debug)]
+pub enum AtlasError
+{
+    /// the palette's faces need more distinct tiles
+    /// than the chunk vertex can address
+    TooManyTiles { count: usize, max: usize },
+}
+
+impl fmt::Display for AtlasError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            AtlasError::TooManyTiles { count, max } =>
+            {
+                write!(f, "block textures need {} distinct tiles, but at most {} fit in the atlas", count, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AtlasError { }
@@ -0,0 +1,8 @@
+mod r_atlas;
+mod err;
+
+pub use self::
+{
+    r_atlas::*,
+    err::*,
+};
\ No newline at end of file
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use ezgame::gfx::image::{ self, imageops, RgbaImage, Rgba };
+use ezgame::gfx::*;
+use ezmath::*;
+
+use crate::common::block::{ RBlockPalette, BlockFace };
+use super::AtlasError;
+
+/// resource that maps every face of every block type to
+/// its tile in the block texture atlas. tile coordinates
+/// fit in the 6-bit u/v fields of the chunk vertex
+#[derive(Debug, Default)]
+pub struct RBlockAtlas
+{
+    /// number of tiles along each side of the atlas
+    size: u32,
+    /// tile of each face of each block, indexed by
+    /// block ID then `BlockFace`
+    tiles: Vec<[uint2; 6]>,
+}
+
+buffer_data!
+(
+    /// uniform for the block texture atlas' layout
+    #[derive(Default)]
+    pub struct AtlasUniform
+    {
+        pub tiles: u32
+    }
+);
+
+/// block texture atlas bind group, for the fragment shader
+pub type AtlasBind = BindGroup<(Texture, Sampler, Uniform<AtlasUniform>)>;
+
+/// where a tile's pixels come from. tiles from the same
+/// source are only packed once
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+enum TileSource
+{
+    /// a texture file, and the colour to use if it can't
+    /// be loaded
+    File(PathBuf, [u8; 4]),
+    /// a solid colour, for blocks without textures
+    Colour([u8; 4]),
+}
+
+impl RBlockAtlas
+{
+    /// width and height of a tile, in pixels. textures of
+    /// any other size are scaled to fit
+    pub const TILE_SIZE: u32 = 16;
+    /// maximum number of tiles along each side of the
+    /// atlas, limited by the chunk vertex' u/v fields. loaded
+    /// palettes never have more than `RBlockPalette::MAX_TEXTURES`
+    pub const MAX_SIZE: u32 = 64;
+
+    /// pack the textures of every block in the palette into
+    /// a single atlas image. blocks without textures get a
+    /// tile of their fallback colour, and textures that can't
+    /// be loaded fall back to it too. fails if the tiles
+    /// don't fit the chunk vertex' u/v fields
+    pub fn build(pal: &RBlockPalette) -> Result<(Self, RgbaImage), AtlasError>
+    {
+        let mut sources = Vec::<TileSource>::new();
+        let mut indices = HashMap::<TileSource, u32>::new();
+
+        let mut index = |src: TileSource|
+        {
+            *indices.entry(src.clone()).or_insert_with(||
+            {
+                sources.push(src);
+                sources.len() as u32 - 1
+            })
+        };
+
+        // assign every face a tile
+        let faces = pal
+            .iter()
+            .map(|(_, block)|
+            {
+                let col =
+                [
+                    (block.col.x * 255.0) as u8,
+                    (block.col.y * 255.0) as u8,
+                    (block.col.z * 255.0) as u8,
+                    (block.col.w * 255.0) as u8,
+                ];
+
+                let mut faces = [0; 6];
+                for (i, face) in faces.iter_mut().enumerate()
+                {
+                    *face = match &block.textures
+                    {
+                        Some(tex) => index(TileSource::File(tex[i].clone(), col)),
+                        None => index(TileSource::Colour(col)),
+                    };
+                }
+                faces
+            })
+            .collect::<Vec<_>>();
+
+        // smallest power of two square that fits every tile
+        let mut size = 1;
+        while size * size < sources.len() as u32
+        {
+            size *= 2;
+        }
+        if size > Self::MAX_SIZE
+        {
+            return Err(AtlasError::TooManyTiles
+            {
+                count: sources.len(),
+                max: (Self::MAX_SIZE * Self::MAX_SIZE) as usize,
+            });
+        }
+
+        let tile = |i: u32| uint2::new(i % size, i / size);
+
+        // rasterize tiles
+        let mut img = RgbaImage::new(size * Self::TILE_SIZE, size * Self::TILE_SIZE);
+        for (i, src) in sources.iter().enumerate()
+        {
+            const TILE: u32 = RBlockAtlas::TILE_SIZE;
+
+            let pixels = match src
+            {
+                TileSource::File(path, col) => match image::open(path)
+                {
+                    Ok(tex) => imageops::resize(&tex.into_rgba(), TILE, TILE, imageops::FilterType::Nearest),
+                    Err(e) =>
+                    {
+                        println!("[warning] couldn't load texture {}: {}", path.display(), e);
+
+                        RgbaImage::from_pixel(TILE, TILE, Rgba(*col))
+                    }
+                },
+                TileSource::Colour(col) => RgbaImage::from_pixel(TILE, TILE, Rgba(*col)),
+            };
+
+            let pos = tile(i as u32) * Self::TILE_SIZE;
+            imageops::replace(&mut img, &pixels, pos.x, pos.y);
+        }
+
+        let tiles = faces
+            .iter()
+            .map(|f|
+            [
+                tile(f[0]), tile(f[1]), tile(f[2]),
+                tile(f[3]), tile(f[4]), tile(f[5]),
+            ])
+            .collect();
+
+        Ok((Self { size, tiles }, img))
+    }
+
+    /// get the tile of a block's face, given the block's
+    /// numeric ID
+    pub fn tile(&self, id: usize, face: BlockFace) -> uint2
+    {
+        self.tiles
+            .get(id)
+            .map_or(uint2::zeros(), |t| t[face as usize])
+    }
+
+    /// number of tiles along each side of the atlas
+    pub fn size(&self) -> u32
+    {
+        self.size
+    }
+}
@@ -1,131 +1,262 @@
 use ezmath::*;
 
-use crate::client::gfx::{ ChunkMeshBuilder, ChunkVertex };
+use crate::client::gfx::{ ChunkMeshBuilder, ChunkVertex, ChunkFineVertex, RBlockAtlas };
 use crate::common::block::*;
+use crate::common::light::BlockLight;
 
 impl<'a> UnpackedBlock<'a>
 {
     /// should this block's face be culled by the other block?
-    /// assumes `other` block touches `self` block on `face`
-    pub fn cull(&self, other: &UnpackedBlock, face: BlockFace) -> bool
+    /// assumes `other` block touches `self` block on `face`.
+    /// only the parts of this block's shape(its `boxes`) on
+    /// its boundary are considered, so a face is culled if the
+    /// other block's core boxes fully cover them. only opaque
+    /// blocks, or blocks of the same type, can cull a face
+    pub fn cull(&self, boxes: &[shapes::ShapeBox], other: &UnpackedBlock, face: BlockFace) -> bool
     {
-        use BlockShapes::*;
-        use shapes::*;
+        use shapes::BlockShapes::*;
 
         match self.shape()
         {
             None => true,
-            Cube => match other.shape()
+            _ =>
             {
-                None => false,                              // never cull
-                Cube => true,                               // always cull
-                Half => match other.variant().into()        // cull if fully covered
+                if !other.hides(self)
                 {
-                    HalfBlockVariants::North => match face  // opposing face = touching
-                    {
-                        BlockFace::South => true,
-                        _ => false,
-                    },
-                    HalfBlockVariants::South => match face  // opposing face = touching
-                    {
-                        BlockFace::North => true,
-                        _ => false,
-                    },
-                    HalfBlockVariants::West => match face   // opposing face = touching
-                    {
-                        BlockFace::East => true,
-                        _ => false,
-                    },
-                    HalfBlockVariants::East => match face   // opposing face = touching
-                    {
-                        BlockFace::West => true,
-                        _ => false,
-                    },
-                    HalfBlockVariants::Down => match face   // opposing face = touching
-                    {
-                        BlockFace::Up => true,
-                        _ => false,
-                    },
-                    HalfBlockVariants::Up => match face     // opposing face = touching
-                    {
-                        BlockFace::Down => true,
-                        _ => false,
-                    },
-                    HalfBlockVariants::NorthSouth => true,  // same as full blocks
-                    HalfBlockVariants::WestEast => true,    // same as full blocks
-                    HalfBlockVariants::DownUp => true,      // same as full blocks
+                    return false;
                 }
-            },
-            Half => todo!()
+
+                let own = shapes::face_mask(boxes, face);
+                let other = shapes::face_mask(&other.core_boxes(), face.opposite());
+
+                own & !other == 0
+            }
         }
     }
 
-    /// meshes a given face of this block
-    pub fn mesh(&self, mesh: &mut ChunkMeshBuilder, face: BlockFace)
+    /// meshes this block, textured from the atlas. each world
+    /// face is textured with the local face that points there.
+    /// faces covered by their neighbor are culled, see
+    /// `UnpackedBlock::cull`, but missing neighbors never cull.
+    /// faces on the block's boundary are lit by the neighbor
+    /// they face, and faces within it by the block itself
+    pub fn mesh(&self, mesh: &mut ChunkMeshBuilder, atlas: &RBlockAtlas, neighbors: &shapes::Neighbors, light: &BlockLight)
     {
-        match self.shape()
+        let shape = self.shape();
+
+        match shape
         {
-            shapes::BlockShapes::None => {}                 // strictly no mesh
-            shapes::BlockShapes::Cube =>                    // simple cube faces
+            shapes::BlockShapes::None => return,           // strictly no mesh
+            shapes::BlockShapes::Cross =>                   // diagonal planes, never culled
             {
-                gen_face(self, mesh, face)
+                gen_cross(self, mesh, atlas, light.center);
+                return;
             }
-            shapes::BlockShapes::Half =>                    // todo
+            _ => {}
+        }
+
+        let boxes = self.boxes_in(neighbors);
+
+        for (i, neighbor) in neighbors.iter().enumerate()
+        {
+            let face = BlockFace::from(i);
+            let culled = neighbor
+                .as_ref()
+                .map_or(false, |n| self.cull(&boxes, n, face));
+
+            match shape
             {
-                todo!()
+                shapes::BlockShapes::Cube =>                // simple cube faces
+                {
+                    if !culled
+                    {
+                        gen_face(self, mesh, atlas, face, light.faces[i])
+                    }
+                }
+                _ =>                                        // box faces, with fine precision
+                {
+                    for b in boxes.iter()
+                    {
+                        // faces inside the block are never covered
+                        if !(culled && b.touches(face))
+                        {
+                            let light = if b.touches(face) { light.faces[i] } else { light.center };
+
+                            gen_fine_face(self, mesh, atlas, b, face, light)
+                        }
+                    }
+                }
             }
         }
     }
 }
 
-/// creates a square face of a mesh using the chunk vertex
-fn gen_face(block: &Block, mesh: &mut ChunkMeshBuilder, face: BlockFace)
+/// corners of a face, where 0 is the min corner of the box and
+/// 1 is its max corner, on each axis
+const POS: [[u32; 3]; 8] =
+[
+    [ 1 , 1 , 1 ],
+    [ 0 , 1 , 1 ],
+    [ 0 , 0 , 1 ],
+    [ 1 , 0 , 1 ],
+    [ 0 , 1 , 0 ],
+    [ 1 , 1 , 0 ],
+    [ 1 , 0 , 0 ],
+    [ 0 , 0 , 0 ],
+];
+
+/// corners(in POS) of each face, in BlockFace order
+const TRI: [[usize; 4]; 6] =
+[
+    [ 4, 5, 6, 7 ],
+    [ 0, 1, 2, 3 ],
+    [ 1, 4, 7, 2 ],
+    [ 5, 0, 3, 6 ],
+    [ 3, 2, 7, 6 ],
+    [ 5, 4, 1, 0 ],
+];
+
+/// corners of the cross shape's planes, each seen from both
+/// sides, in the same order as a face's corners
+const CROSS: [[[u32; 3]; 4]; 4] =
+[
+    [ [ 0, 1, 0 ], [ 1, 1, 1 ], [ 1, 0, 1 ], [ 0, 0, 0 ] ],
+    [ [ 1, 1, 1 ], [ 0, 1, 0 ], [ 0, 0, 0 ], [ 1, 0, 1 ] ],
+    [ [ 1, 1, 0 ], [ 0, 1, 1 ], [ 0, 0, 1 ], [ 1, 0, 0 ] ],
+    [ [ 0, 1, 1 ], [ 1, 1, 0 ], [ 1, 0, 0 ], [ 0, 0, 1 ] ],
+];
+
+/// indices of a face's two triangles
+const IND: [u32; 6] =
+[
+    0, 1, 2, 0, 2, 3
+];
+
+/// get a corner of a face, as a vector
+fn face_corner(face: BlockFace, k: usize) -> int3
+{
+    let pos = POS[TRI[face as usize][k % 4]];
+
+    int3::new(pos[0] as i32, pos[1] as i32, pos[2] as i32)
+}
+
+/// get the tile of a block's world face, and the corner of the
+/// face at which the tile starts. the tile is that of the local
+/// face pointing there, turned so it rotates along with the block
+fn tile(block: &Block, atlas: &RBlockAtlas, face: BlockFace) -> (uint2, usize)
 {
-    const POS: [[u32; 3]; 8] = 
-    [
-        [ 1 , 1 , 1 ],
-        [ 0 , 1 , 1 ],
-        [ 0 , 0 , 1 ],
-        [ 1 , 0 , 1 ],
-        [ 0 , 1 , 0 ],
-        [ 1 , 1 , 0 ],
-        [ 1 , 0 , 0 ],
-        [ 0 , 0 , 0 ],
-    ];
-
-    const TRI: [[usize; 4]; 6] =
-    [
-        [ 4, 5, 6, 7 ],
-        [ 0, 1, 2, 3 ],
-        [ 1, 4, 7, 2 ],
-        [ 5, 0, 3, 6 ],
-        [ 3, 2, 7, 6 ],
-        [ 5, 4, 1, 0 ],
-    ];
-
-    const IND: [u32; 6] =
-    [
-        0, 1, 2, 0, 2, 3
-    ];
+    let rot = block.rotation();
+    let local = face.to_local(rot);
 
+    let tex = atlas.tile(block.id(), local);
+    if rot.is_identity()
+    {
+        return (tex, 0);
+    }
+
+    // the tile's "down" runs from its first corner to its last
+    let down = rot.apply(face_corner(local, 3) - face_corner(local, 0));
+    let start = (0..4)
+        .find(|k| face_corner(face, k + 3) - face_corner(face, *k) == down)
+        .unwrap_or(0);
+
+    (tex, start)
+}
+
+/// creates a square face of a mesh using the chunk vertex.
+/// every vertex stores the face's tile, and the shader picks
+/// the corner of the tile from the vertex index
+fn gen_face(block: &Block, mesh: &mut ChunkMeshBuilder, atlas: &RBlockAtlas, face: BlockFace, light: u8)
+{
     let pos = block.r_pos();
+    let (tex, start) = tile(block, atlas, face);
+
+    let mesh = mesh.layer(block.opacity());
 
-    for i in &TRI[face as usize]    // vertices
+    let j = mesh.vert.len() as u32;
+    for k in 0..4                   // vertices
     {
-        let x = POS[*i][0] + pos.x as u32;
-        let y = POS[*i][1] + pos.y as u32;
-        let z = POS[*i][2] + pos.z as u32;
+        let i = TRI[face as usize][(k + start) % 4];
 
-        let tex = block.color().xy() * 128.0 * (0.5 + (pos.y as f32 / 64.0));
-        let pos = uint3::new(x, y, z);
-        let tex = uint2::new(tex.x as u32, tex.y as u32);
+        let x = POS[i][0] + pos.x as u32;
+        let y = POS[i][1] + pos.y as u32;
+        let z = POS[i][2] + pos.z as u32;
 
-        mesh.vert.push(ChunkVertex::new(&pos, &tex));
+        mesh.vert.push(ChunkVertex::new(&uint3::new(x, y, z), &tex, light));
     }
 
-    let j = mesh.vert.len() as u32;
     for i in &IND                   // indices
     {
         mesh.ind.push(*i + j);
     }
-}
\ No newline at end of file
+}
+
+/// creates the face of a box within a block, using the fine
+/// chunk vertex. the box only shows the part of the face's
+/// tile that it covers, as if it were cut out of a full block
+fn gen_fine_face(block: &Block, mesh: &mut ChunkMeshBuilder, atlas: &RBlockAtlas, b: &shapes::ShapeBox, face: BlockFace, light: u8)
+{
+    const P: u32 = shapes::SHAPE_PRECISION as u32;
+
+    let pos = block.r_pos() * P as i32;
+    let (tex, start) = tile(block, atlas, face);
+    let tex = tex * P;
+
+    // texture axes of the face: u runs from its starting corner
+    // to the next, and v from its starting corner to the last
+    let corners = &TRI[face as usize];
+    let origin = POS[corners[start]];
+    let u = (0..3).find(|a| origin[*a] != POS[corners[(start + 1) % 4]][*a]).unwrap();
+    let v = (0..3).find(|a| origin[*a] != POS[corners[(start + 3) % 4]][*a]).unwrap();
+
+    let mesh = mesh.layer(block.opacity());
+
+    let j = mesh.fine_vert.len() as u32;
+    for k in 0..4                   // vertices
+    {
+        let i = &corners[(k + start) % 4];
+        let corner = |axis: usize| (if POS[*i][axis] == 1 { b.max[axis] } else { b.min[axis] }) as u32;
+        let dist = |axis: usize| if origin[axis] == 1 { P - corner(axis) } else { corner(axis) };
+
+        let x = corner(0) + pos.x as u32;
+        let y = corner(1) + pos.y as u32;
+        let z = corner(2) + pos.z as u32;
+
+        let uv = tex + uint2::new(dist(u), dist(v));
+
+        mesh.fine_vert.push(ChunkFineVertex::new(&uint3::new(x, y, z), &uv, light));
+    }
+
+    for i in &IND                   // indices
+    {
+        mesh.fine_ind.push(*i + j);
+    }
+}
+
+/// creates the two diagonal planes of a cross shaped block,
+/// using the chunk vertex. both use the block's north tile,
+/// and are lit by the block itself
+fn gen_cross(block: &Block, mesh: &mut ChunkMeshBuilder, atlas: &RBlockAtlas, light: u8)
+{
+    let pos = block.r_pos();
+    let tex = atlas.tile(block.id(), BlockFace::North);
+    let mesh = mesh.layer(block.opacity());
+
+    for plane in &CROSS
+    {
+        let j = mesh.vert.len() as u32;
+        for c in plane              // vertices
+        {
+            let x = c[0] + pos.x as u32;
+            let y = c[1] + pos.y as u32;
+            let z = c[2] + pos.z as u32;
+
+            mesh.vert.push(ChunkVertex::new(&uint3::new(x, y, z), &tex, light));
+        }
+
+        for i in &IND               // indices
+        {
+            mesh.ind.push(*i + j);
+        }
+    }
+}
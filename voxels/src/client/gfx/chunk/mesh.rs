@@ -1,23 +1,112 @@
 use ezgame::gfx::*;
 
-use super::{ ChunkPosition, ChunkVertex };
+use crate::common::block::Opacity;
+use super::{ ChunkPosition, ChunkVertex, ChunkFineVertex };
 
 /// the geometry and position uniform of a chunk
 pub struct ChunkMesh
 {
-    pub geo: ChunkGeometry,
+    pub opaque: ChunkMeshLayer,
+    pub translucent: ChunkMeshLayer,
     pub pos: ChunkPosBind,
 }
 
+/// geometry of a chunk that's drawn in the same pass,
+/// for either vertex model
+pub struct ChunkMeshLayer
+{
+    pub geo: Option<ChunkGeometry>,
+    pub fine: Option<ChunkFineGeometry>,
+}
+
 /// geometry of a given chunk
 pub type ChunkGeometry = Geometry<ChunkVertex, u32>;
+/// geometry of a given chunk, for shapes that don't
+/// snap to the block grid
+pub type ChunkFineGeometry = Geometry<ChunkFineVertex, u32>;
 /// position bind group of a chunk
 pub type ChunkPosBind = BindGroup<(Uniform<ChunkPosition>,)>;
 
 #[derive(Debug, Default)]
-/// temporary structure to generate chunk geometry
+/// temporary structure to generate chunk geometry. opaque
+/// and cutout blocks go in the opaque layer, and translucent
+/// blocks in the translucent layer, which is blended
 pub struct ChunkMeshBuilder
+{
+    pub opaque: ChunkLayerBuilder,
+    pub translucent: ChunkLayerBuilder,
+}
+
+#[derive(Debug, Default)]
+/// temporary structure to generate the geometry of a
+/// single chunk mesh layer
+pub struct ChunkLayerBuilder
 {
     pub vert: Vec<ChunkVertex>,
-    pub ind: Vec<u32>
-}
\ No newline at end of file
+    pub ind: Vec<u32>,
+
+    pub fine_vert: Vec<ChunkFineVertex>,
+    pub fine_ind: Vec<u32>,
+}
+
+impl ChunkMesh
+{
+    /// get either the opaque or the translucent layer of
+    /// this mesh
+    pub fn layer(&self, translucent: bool) -> &ChunkMeshLayer
+    {
+        if translucent
+        {
+            &self.translucent
+        }
+        else
+        {
+            &self.opaque
+        }
+    }
+}
+
+impl ChunkMeshBuilder
+{
+    /// does this builder contain no geometry at all?
+    pub fn is_empty(&self) -> bool
+    {
+        self.opaque.is_empty() && self.translucent.is_empty()
+    }
+
+    /// get the layer a block of the given opacity is
+    /// meshed in
+    pub fn layer(&mut self, opacity: Opacity) -> &mut ChunkLayerBuilder
+    {
+        match opacity
+        {
+            Opacity::Opaque | Opacity::Cutout => &mut self.opaque,
+            Opacity::Translucent => &mut self.translucent,
+        }
+    }
+}
+
+impl ChunkLayerBuilder
+{
+    /// does this layer contain no geometry at all?
+    pub fn is_empty(&self) -> bool
+    {
+        self.vert.is_empty() && self.fine_vert.is_empty()
+    }
+
+    /// upload this layer's geometry
+    pub fn build(&self, gfx: &Renderer) -> ChunkMeshLayer
+    {
+        ChunkMeshLayer
+        {
+            geo: if self.vert.is_empty() { None } else
+            {
+                Some(gfx.geometry(&self.vert[..], &self.ind[..]))
+            },
+            fine: if self.fine_vert.is_empty() { None } else
+            {
+                Some(gfx.geometry(&self.fine_vert[..], &self.fine_ind[..]))
+            },
+        }
+    }
+}
@@ -2,6 +2,7 @@ use ezgame::gfx::*;
 use ezmath::*;
 
 use super::{ ChunkPosBind, ChunkMesh };
+use crate::client::gfx::AtlasBind;
 
 /// shared graphic resources for chunks
 pub type RGraphicsChunk = Option
@@ -13,6 +14,14 @@ pub type RGraphicsChunk = Option
     Pipeline,       // shared rendering pipeline
 
     ChunkMeshes,    // pool of chunk meshes
+
+    Shader,         // shared fine vertex shader
+    Pipeline,       // shared fine rendering pipeline
+
+    AtlasBind,      // block texture atlas
+
+    Pipeline,       // shared translucent rendering pipeline
+    Pipeline,       // shared translucent fine rendering pipeline
 )>;
 
 /// resource that stores all the
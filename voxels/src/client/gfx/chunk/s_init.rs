@@ -1,17 +1,21 @@
 use ezgame::ecs::*;
 use ezgame::gfx::*;
 
+use crate::common::block::RBlockPalette;
 use super::super::
 {
     ChunkPosition, 
     ChunkVertex, 
+    ChunkFineVertex,
     RGraphicsShared, 
     RGraphicsChunk,
     SGraphicsShared,
+    RBlockAtlas,
+    AtlasUniform,
 };
 
 /// system that initializes the RGraphicsChunk
-/// resource
+/// resource, and packs the block texture atlas
 pub struct SGraphicsChunk;
 
 impl System for SGraphicsChunk
@@ -22,6 +26,7 @@ impl System for SGraphicsChunk
     fn prepare(r: &mut Resources)
     {
         r.insert(RGraphicsChunk::None);
+        r.insert(RBlockAtlas::default());
     }
 
     fn exe() -> Job
@@ -31,34 +36,88 @@ impl System for SGraphicsChunk
         // resources
         .read_resource::<RGraphics>()
         .read_resource::<RGraphicsShared>()
+        .read_resource::<RBlockPalette>()
         .write_resource::<RGraphicsChunk>()
+        .write_resource::<RBlockAtlas>()
         // system
-        .build(move |_, _, (r_gfx, r_shared, r_chunk), _|
+        .build(move |_, _, (r_gfx, r_shared, r_pal, r_chunk, r_atlas), _|
         {
             const VS_SRC: &str = include_str!("../../../../assets/shaders/chunk.vert");
             const FS_SRC: &str = include_str!("../../../../assets/shaders/chunk.frag");
+            const FINE_VS_SRC: &str = include_str!("../../../../assets/shaders/chunk_fine.vert");
 
             let ctx = r_gfx.as_ref().unwrap();
             
             let vs = ctx.shader(ShaderKind::Vertex, VS_SRC);
             let fs = ctx.shader(ShaderKind::Fragment, FS_SRC);
+            let fine_vs = ctx.shader(ShaderKind::Vertex, FINE_VS_SRC);
 
             let vp = &r_shared.as_ref().unwrap().0;
 
             let pos = ctx.uniform(ChunkPosition::default());
             let pos = ctx.bind_group(ShaderKind::Vertex, (pos,));
 
+            let (atlas, img) = RBlockAtlas::build(r_pal)
+                .unwrap_or_else(|e| panic!("[error] couldn't build block texture atlas: {}", e));
+            let atlas_uniform = ctx.uniform(AtlasUniform { tiles: atlas.size() });
+            let atlas_tex = ctx.texture_image("block_atlas", image::DynamicImage::ImageRgba8(img), wgpu::TextureComponentType::Float);
+            let atlas_smp = ctx.sampler(&SamplerDesc
+            {
+                mag: Filter::Nearest,
+                min: Filter::Nearest,
+                edge: (Edge::Clamp, Edge::Clamp),
+                mode: Mode::Normal,
+            });
+            let atlas_bind = ctx.bind_group(ShaderKind::Fragment, (atlas_tex, atlas_smp, atlas_uniform));
+
+            **r_atlas = atlas;
+
             let pipeline = ctx
                 .pipeline()
-                    .bindings(&[vp, &pos])
+                    .bindings(&[vp, &pos, &atlas_bind])
                     .vertex::<ChunkVertex>()
                     .index::<u32>()
                     .shader(&vs)
                     .shader(&fs)
                     .depth(true)
                 .build();
+            let fine_pipeline = ctx
+                .pipeline()
+                    .bindings(&[vp, &pos, &atlas_bind])
+                    .vertex::<ChunkFineVertex>()
+                    .index::<u32>()
+                    .shader(&fine_vs)
+                    .shader(&fs)
+                    .depth(true)
+                .build();
+            let trans_pipeline = ctx
+                .pipeline()
+                    .bindings(&[vp, &pos, &atlas_bind])
+                    .vertex::<ChunkVertex>()
+                    .index::<u32>()
+                    .shader(&vs)
+                    .shader(&fs)
+                    .depth(true)
+                    .depth_read_only(true)
+                    .blending(Blending::Alpha)
+                .build();
+            let trans_fine_pipeline = ctx
+                .pipeline()
+                    .bindings(&[vp, &pos, &atlas_bind])
+                    .vertex::<ChunkFineVertex>()
+                    .index::<u32>()
+                    .shader(&fine_vs)
+                    .shader(&fs)
+                    .depth(true)
+                    .depth_read_only(true)
+                    .blending(Blending::Alpha)
+                .build();
             
-            r_chunk.replace((vs, fs, pos, pipeline, Default::default()));
+            r_chunk.replace
+            ((
+                vs, fs, pos, pipeline, Default::default(), fine_vs, fine_pipeline, atlas_bind,
+                trans_pipeline, trans_fine_pipeline
+            ));
         })
     }
 }
\ No newline at end of file
@@ -3,9 +3,10 @@ use ezgame::gfx::*;
 use ezgame::time;
 use ezmath::*;
 
-use crate::common::chunk::{ CChunk, CBlockBuffer, BlockBufferIndex, TUpdated, RChunkCache };
-use crate::client::gfx::{ SRender, RGraphicsChunk, ChunkMeshBuilder, ChunkPosition, ChunkMesh };
-use crate::common::block::{ Block, BlockFace, shapes::BlockShapes, RBlockPalette };
+use crate::common::chunk::{ CChunk, CBlockBuffer, CLightBuffer, BlockBufferIndex, TUpdated, RChunkCache };
+use crate::client::gfx::{ SRender, RGraphicsChunk, ChunkMeshBuilder, ChunkPosition, ChunkMesh, RBlockAtlas };
+use crate::common::block::{ Block, BlockFace, shapes::{ BlockShapes, Neighbors }, RBlockPalette };
+use crate::common::light::BlockLight;
 use crate::common::CHUNK_SIZE;
 
 /// system that remeshes chunks
@@ -25,13 +26,15 @@ impl System for SChunkMesh
         // components...
         .with_query(<Read<CChunk>>::query().filter(tag::<TUpdated>()))
         .read_component::<CBlockBuffer>()
+        .read_component::<CLightBuffer>()
         // resources...
         .read_resource::<RChunkCache>()
         .read_resource::<RBlockPalette>()
+        .read_resource::<RBlockAtlas>()
         .read_resource::<RGraphics>()
         .write_resource::<RGraphicsChunk>()
         // system...
-        .build(|cmd, world, (r_cache, r_pal, r_gfx, r_gfx_chunk), q_chunk|
+        .build(|cmd, world, (r_cache, r_pal, r_atlas, r_gfx, r_gfx_chunk), q_chunk|
         {
             if r_gfx.is_none() || r_gfx_chunk.is_none()
             {
@@ -40,6 +43,9 @@ impl System for SChunkMesh
             let gfx = r_gfx.as_ref().unwrap();
             let gfx_chunk = r_gfx_chunk.as_mut().unwrap();
 
+            // evict the meshes of unloaded chunks
+            gfx_chunk.4.retain(|pos, _| r_cache.at(*pos).is_some());
+
             for (ent, chunk) in q_chunk.iter_entities(world)
             {
                 // neighbors
@@ -48,8 +54,12 @@ impl System for SChunkMesh
                 // geometry buffer
                 let mut mesh = ChunkMeshBuilder::default();
 
+                // all air, nothing to mesh
+                let empty = region.center.uniform().is_some()
+                    && region.center.get(int3::zeros(), r_pal).shape() == BlockShapes::None;
+
                 // go through every block
-                for x in 0..CHUNK_SIZE as i32
+                for x in (0..CHUNK_SIZE as i32).filter(|_| !empty)
                 {
                     for y in 0..CHUNK_SIZE as i32
                     {
@@ -67,18 +77,9 @@ impl System for SChunkMesh
                                 continue;
                             }
 
-                            // check block in every direction
-                            for d in 0..6usize
-                            {
-                                let face = BlockFace::from(d);
-
-                                // only generate face is neighbor face isn't
-                                // full opaque
-                                if !region.culled(&block, face)
-                                {
-                                    block.mesh(&mut mesh, face);
-                                }
-                            }
+                            // only generate faces on the block's boundary
+                            // if neighbor face doesn't fully cover them
+                            block.mesh(&mut mesh, r_atlas, &region.neighbors(&block), &region.light(&block));
                         }
                     }
                 }
@@ -86,9 +87,11 @@ impl System for SChunkMesh
                 // done meshing, remove tag
                 cmd.remove_tag::<TUpdated>(ent);
 
-                // no empty meshes(this crashes anyways)
-                if mesh.vert.is_empty()
+                // no empty meshes(this crashes anyways), but
+                // drop the one the chunk had
+                if mesh.is_empty()
                 {
+                    gfx_chunk.4.remove(&chunk.pos());
                     continue;
                 }
 
@@ -98,7 +101,8 @@ impl System for SChunkMesh
                 //create mesh
                 let mesh = ChunkMesh
                 {
-                    geo: gfx.geometry(&mesh.vert[..], &mesh.ind[..]),
+                    opaque: mesh.opaque.build(gfx),
+                    translucent: mesh.translucent.build(gfx),
                     pos: gfx.clone_bind_group(&gfx_chunk.2, (pos,))
                 };
 
@@ -117,6 +121,9 @@ struct Region<'a>
     center: CmpRef<'a, CBlockBuffer>,
     neighbors: [Option<CmpRef<'a, CBlockBuffer>>; 6],
 
+    center_light: CmpRef<'a, CLightBuffer>,
+    neighbors_light: [Option<CmpRef<'a, CLightBuffer>>; 6],
+
     pal: &'a RBlockPalette
 }
 
@@ -126,32 +133,54 @@ impl<'a> Region<'a>
     {
         // neighbors
         let mut neighbors = [None, None, None, None, None, None];
+        let mut neighbors_light = [None, None, None, None, None, None];
         for i in 0..6usize
         {
             let dir = BlockFace::from(i).normal() * CHUNK_SIZE as i32;
 
-            if let Some(ent) = cache.at(center + dir)
+            // chunks without their blocks yet count as missing
+            if let Some(ent) = cache.generated_at(center + dir)
             {
                 neighbors[i] = world.get_component::<CBlockBuffer>(*ent);
+                neighbors_light[i] = world.get_component::<CLightBuffer>(*ent);
             }
         }
 
         // center
+        let ent = *cache.at(center).unwrap();
         let center = world
-            .get_component::<CBlockBuffer>(*cache.at(center).unwrap())
+            .get_component::<CBlockBuffer>(ent)
+            .unwrap();
+        let center_light = world
+            .get_component::<CLightBuffer>(ent)
             .unwrap();
 
         Self
         {
             center,
             neighbors,
+            center_light,
+            neighbors_light,
             pal
         }
     }
 
-    /// get a block in this region given the relative
-    /// coordinates.
-    fn culled(&self, block: &Block, face: BlockFace) -> bool
+    /// get the blocks touching a block of the center chunk,
+    /// which may be in neighboring chunks
+    fn neighbors(&self, block: &Block) -> Neighbors
+    {
+        let mut neighbors = [None, None, None, None, None, None];
+        for (i, n) in neighbors.iter_mut().enumerate()
+        {
+            *n = self.neighbor(block, BlockFace::from(i));
+        }
+        neighbors
+    }
+
+    /// get the block touching a block of the center chunk
+    /// on the given face. fails if that block is in a
+    /// neighbor chunk that isn't loaded
+    fn neighbor(&self, block: &Block, face: BlockFace) -> Option<Block>
     {
         const SIZE: i32 = CHUNK_SIZE as i32;
 
@@ -168,17 +197,45 @@ impl<'a> Region<'a>
             let rz = n_pos.z.rem_euclid(SIZE);
 
             // neighbor chunk
-            if let Some(neighbor) = &self.neighbors[face as usize]
-            {
-                // do test
-                block.cull(&neighbor.get((rx, ry, rz), self.pal), face)
-            }
-            else { false }
+            self.neighbors[face as usize]
+                .as_ref()
+                .map(|neighbor| neighbor.get((rx, ry, rz), self.pal))
         }
         else
         {
-            // do test
-            block.cull(&self.center.get(n_pos, self.pal), face)
+            Some(self.center.get(n_pos, self.pal))
         }
     }
-}
\ No newline at end of file
+
+    /// get the light around a block of the center chunk. blocks
+    /// in neighbor chunks that aren't loaded are seen as open sky
+    fn light(&self, block: &Block) -> BlockLight
+    {
+        const SIZE: i32 = CHUNK_SIZE as i32;
+        const OPEN: u8 = CLightBuffer::MAX << 4;
+
+        let mut light = BlockLight
+        {
+            faces: [0; 6],
+            center: self.center_light.get(block.r_pos()),
+        };
+
+        for (i, l) in light.faces.iter_mut().enumerate()
+        {
+            let n_pos = block.r_pos() + BlockFace::from(i).normal();
+            let r_pos = int3::new(n_pos.x.rem_euclid(SIZE), n_pos.y.rem_euclid(SIZE), n_pos.z.rem_euclid(SIZE));
+
+            *l = if r_pos == n_pos
+            {
+                self.center_light.get(n_pos)
+            }
+            else
+            {
+                self.neighbors_light[i]
+                    .as_ref()
+                    .map_or(OPEN, |neighbor| neighbor.get(r_pos))
+            };
+        }
+        light
+    }
+}
@@ -4,17 +4,19 @@ use ezmath::*;
 buffer_data!
 (
     /// compressed vertex layout
-    /// 6 bit x -> 0-64 local position
-    /// 6 bit y -> 0-64 local position
-    /// 6 bit z -> 0-64 local position
-    /// 7 bit u -> 128 x 128 texture atlas
-    /// 7 bit v -> 128 x 128 texture atlas
+    /// 16 bit xyz -> 0-32 local position, as x + 33 * (y + 33 * z)
+    /// 6 bit u -> tile x in the 64 x 64 texture atlas
+    /// 6 bit v -> tile y in the 64 x 64 texture atlas
+    /// 4 bit light -> brightest of the sky and block light levels, 0-15
     ///
     /// remarks
-    ///     - position can support bigger chunks up to 63, but it's
-    ///       unlikely the 32 size will change
-    ///     - texture atlas can have at most ~16,000 square textures,
-    ///       which is good enough for the current block limit of 4096
+    ///     - the corner of the tile is derived from the vertex
+    ///       index, so every face's four vertices must be pushed
+    ///       in order, starting at a multiple of four
+    ///     - position only fits the 32 chunk size: the 33 corners
+    ///       along each axis, cubed, just fit in 16 bits
+    ///     - texture atlas can have at most 4,096 square textures,
+    ///       which is plenty since tiles are shared between faces
     ///     - only simple square blocks are supported by this vertex
     ///         - stairs and half-blocks which need fractional positions,
     ///           (ie. x = 20.5) can't be represented in this model.
@@ -24,6 +26,31 @@ buffer_data!
     }
 );
 
+buffer_data!
+(
+    /// compressed vertex layout, with quarter-block precision
+    /// (the "4th degree precision" model)
+    /// 8 bit x -> 0-64 local position, in quarter blocks
+    /// 8 bit y -> 0-64 local position, in quarter blocks
+    /// 8 bit z -> 0-64 local position, in quarter blocks
+    /// 4 bit sky -> sky light level, 0-15
+    /// 4 bit block -> block light level, 0-15
+    /// 10 bit u -> 64 x 64 texture atlas, in quarter tiles
+    /// 10 bit v -> 64 x 64 texture atlas, in quarter tiles
+    ///
+    /// remarks
+    ///     - this is used for shapes that don't snap to the
+    ///       block grid, like half blocks(ie. x = 20.5)
+    ///     - texture coordinates are exact, so a box only
+    ///       shows the part of its tile it covers
+    ///     - it's twice the size of the ChunkVertex, so blocks
+    ///       that can be represented by the latter should be
+    pub struct ChunkFineVertex
+    {
+        compressed: [u32; 2]
+    }
+);
+
 buffer_data!
 (
     /// uniform for a chunk position
@@ -41,48 +68,128 @@ impl Vertex for ChunkVertex
 
 impl ChunkVertex
 {
-    pub fn new(pos: &uint3, tex: &uint2) -> Self
+    /// number of corner positions along each axis
+    const CORNERS: u32 = 33;
+
+    /// create a new vertex. light is packed like in a
+    /// CLightBuffer, `sky << 4 | block`, and only the
+    /// brightest of the two is kept
+    pub fn new(pos: &uint3, tex: &uint2, light: u8) -> Self
     {
-        debug_assert!(pos.x <= 63 && pos.y <= 63 && pos.z <= 63, "vertex position needs to be localized 0..64!");
-        debug_assert!(tex.x <= 127 && tex.y <= 127, "texture coord cannot exceed 0..128 range!");
+        debug_assert!(pos.x <= 32 && pos.y <= 32 && pos.z <= 32, "vertex position needs to be localized 0..=32!");
+        debug_assert!(tex.x <= 63 && tex.y <= 63, "texture coord cannot exceed 0..64 range!");
+
+        let xyz = pos.x + Self::CORNERS * (pos.y + Self::CORNERS * pos.z);
+        let light = (light >> 4).max(light & 15) as u32;
 
         Self
         {
-            compressed: (pos.x << 26)
-            | (pos.y << 20)
-            | (pos.z << 14)
-            | (tex.x << 7)
-            | (tex.y)
+            compressed: (xyz << 16)
+            | (tex.x << 10)
+            | (tex.y << 4)
+            | light
         }
     }
 
     #[allow(dead_code)]
     pub fn x(&self) -> u32
     {
-        self.compressed >> 26
+        (self.compressed >> 16) % Self::CORNERS
     }
 
     #[allow(dead_code)]
     pub fn y(&self) -> u32
     {
-        (self.compressed >> 20) & 0b0011_1111
+        (self.compressed >> 16) / Self::CORNERS % Self::CORNERS
     }
 
     #[allow(dead_code)]
     pub fn z(&self) -> u32
     {
-        (self.compressed >> 14) & 0b0011_1111
+        (self.compressed >> 16) / (Self::CORNERS * Self::CORNERS)
     }
 
     #[allow(dead_code)]
     pub fn u(&self) -> u32
     {
-        (self.compressed >> 7) & 0b0111_1111
+        (self.compressed >> 10) & 0b0011_1111
     }
 
     #[allow(dead_code)]
     pub fn v(&self) -> u32
     {
-        self.compressed & 0b0111_1111
+        (self.compressed >> 4) & 0b0011_1111
+    }
+
+    #[allow(dead_code)]
+    pub fn light(&self) -> u8
+    {
+        (self.compressed & 0b1111) as u8
+    }
+}
+
+impl Vertex for ChunkFineVertex
+{
+    const DESC: &'static [VertexAttr] = &[VertexAttr::Uint2];
+}
+
+impl ChunkFineVertex
+{
+    /// create a new fine vertex. position is in quarter
+    /// blocks, relative to the chunk, and texture coordinate
+    /// in quarter tiles. light is packed like in a CLightBuffer
+    pub fn new(pos: &uint3, tex: &uint2, light: u8) -> Self
+    {
+        debug_assert!(pos.x <= 255 && pos.y <= 255 && pos.z <= 255, "vertex position needs to be localized 0..256 quarter blocks!");
+        debug_assert!(tex.x <= 256 && tex.y <= 256, "texture coord cannot exceed 0..=256 range!");
+
+        Self
+        {
+            compressed:
+            [
+                (pos.x << 24)
+                | (pos.y << 16)
+                | (pos.z << 8)
+                | light as u32,
+                (tex.x << 10)
+                | (tex.y)
+            ]
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn x(&self) -> u32
+    {
+        self.compressed[0] >> 24
+    }
+
+    #[allow(dead_code)]
+    pub fn y(&self) -> u32
+    {
+        (self.compressed[0] >> 16) & 0b1111_1111
+    }
+
+    #[allow(dead_code)]
+    pub fn z(&self) -> u32
+    {
+        (self.compressed[0] >> 8) & 0b1111_1111
+    }
+
+    #[allow(dead_code)]
+    pub fn u(&self) -> u32
+    {
+        (self.compressed[1] >> 10) & 0b11_1111_1111
+    }
+
+    #[allow(dead_code)]
+    pub fn v(&self) -> u32
+    {
+        self.compressed[1] & 0b11_1111_1111
+    }
+
+    #[allow(dead_code)]
+    pub fn light(&self) -> u8
+    {
+        self.compressed[0] as u8
     }
 }
\ No newline at end of file
@@ -2,6 +2,8 @@ mod s_render;
 mod shared;
 mod chunk;
 mod block;
+mod atlas;
+mod outline;
 
 pub use self::
 {
@@ -9,4 +11,6 @@ pub use self::
     shared::*,
     chunk::*,
     block::*,
+    atlas::*,
+    outline::*,
 };
\ No newline at end of file
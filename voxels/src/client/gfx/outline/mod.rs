@@ -0,0 +1,12 @@
+mod s_init;
+mod s_outline;
+mod r_gfx;
+mod vert;
+
+pub use self::
+{
+    s_init::*,
+    s_outline::*,
+    r_gfx::*,
+    vert::*,
+};
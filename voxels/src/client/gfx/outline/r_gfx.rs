@@ -0,0 +1,13 @@
+use ezgame::gfx::*;
+
+use super::OutlineVertex;
+use crate::client::gfx::ChunkPosBind;
+
+/// graphic resources for the outline drawn
+/// around the targeted block
+pub type RGraphicsOutline = Option
+<(
+    Pipeline,                       // line rendering pipeline
+    Geometry<OutlineVertex, u16>,   // wireframe of a block
+    ChunkPosBind,                   // targeted block position uniform
+)>;
@@ -0,0 +1,63 @@
+use ezgame::ecs::*;
+use ezgame::gfx::*;
+
+use crate::client::gfx::{ ChunkPosition, RGraphicsShared, SGraphicsChunk };
+use super::{ OutlineVertex, RGraphicsOutline };
+
+/// system that initializes the RGraphicsOutline
+/// resource
+pub struct SGraphicsOutline;
+
+impl System for SGraphicsOutline
+{
+    const EVENT: Event = evt::READY;
+    const ORDER: Order = SGraphicsChunk::ORDER + 1;
+
+    fn prepare(r: &mut Resources)
+    {
+        r.insert(RGraphicsOutline::None);
+    }
+
+    fn exe() -> Job
+    {
+        // begin...
+        sys("outline_graphics_init_system")
+        // resources
+        .read_resource::<RGraphics>()
+        .read_resource::<RGraphicsShared>()
+        .write_resource::<RGraphicsOutline>()
+        // system
+        .build(move |_, _, (r_gfx, r_shared, r_outline), _|
+        {
+            const VS_SRC: &str = include_str!("../../../../assets/shaders/outline.vert");
+            const FS_SRC: &str = include_str!("../../../../assets/shaders/outline.frag");
+
+            let ctx = r_gfx.as_ref().unwrap();
+
+            let vs = ctx.shader(ShaderKind::Vertex, VS_SRC);
+            let fs = ctx.shader(ShaderKind::Fragment, FS_SRC);
+
+            let vp = &r_shared.as_ref().unwrap().0;
+
+            let pos = ctx.uniform(ChunkPosition::default());
+            let pos = ctx.bind_group(ShaderKind::Vertex, (pos,));
+
+            let (vertices, indices) = OutlineVertex::block();
+            let geo = ctx.geometry(&vertices, &indices);
+
+            let pipeline = ctx
+                .pipeline()
+                    .bindings(&[vp, &pos])
+                    .vertex::<OutlineVertex>()
+                    .index::<u16>()
+                    .shader(&vs)
+                    .shader(&fs)
+                    .topology(Topology::Lines)
+                    .depth(true)
+                    .depth_read_only(true)
+                .build();
+
+            r_outline.replace((pipeline, geo, pos));
+        })
+    }
+}
@@ -0,0 +1,42 @@
+use ezgame::time::evt;
+use ezgame::ecs::*;
+use ezgame::gfx::*;
+
+use crate::client::gfx::{ ChunkPosition, SRender };
+use crate::client::interact::RBlockTarget;
+use super::RGraphicsOutline;
+
+/// system that moves the block outline to
+/// the targeted block
+pub struct SBlockOutline;
+
+impl System for SBlockOutline
+{
+    const EVENT: Event = evt::RENDER;
+    const ORDER: Order = SRender::ORDER - 1;
+
+    fn exe() -> Job
+    {
+        // begin...
+        sys("block_outline_system")
+        // resources...
+        .read_resource::<RBlockTarget>()
+        .read_resource::<RGraphicsOutline>()
+        .read_resource::<RGraphics>()
+        // system...
+        .build(|_, _, (r_target, r_outline, r_gfx), _|
+        {
+            if r_gfx.is_none() || r_outline.is_none()
+            {
+                return;
+            }
+            let gfx = r_gfx.as_ref().unwrap();
+            let outline = r_outline.as_ref().unwrap();
+
+            if let Some(hit) = &r_target.0
+            {
+                gfx.update_uniform(&outline.2.bindings.0, ChunkPosition { position: hit.pos });
+            }
+        })
+    }
+}
@@ -0,0 +1,52 @@
+use ezgame::gfx::*;
+use ezmath::*;
+
+buffer_data!
+(
+    /// vertex of the block outline's wireframe,
+    /// relative to the block's min corner
+    pub struct OutlineVertex
+    {
+        pos: float3
+    }
+);
+
+impl Vertex for OutlineVertex
+{
+    const DESC: &'static [VertexAttr] = &[VertexAttr::Float3];
+}
+
+impl OutlineVertex
+{
+    /// create a new outline vertex
+    pub fn new(pos: float3) -> Self
+    {
+        Self { pos }
+    }
+
+    /// wireframe of a block, as the eight corners of a box
+    /// slightly bigger than it, and the twelve edges between
+    /// them. it's inflated so it isn't hidden by the faces
+    pub fn block() -> ([Self; 8], [u16; 24])
+    {
+        const GROW: f32 = 0.002;
+
+        let mut corners = [Self::new(float3::zeros()); 8];
+        for (i, corner) in corners.iter_mut().enumerate()
+        {
+            let c = |bit: usize| if i & bit != 0 { 1.0 + GROW } else { -GROW };
+
+            *corner = Self::new(float3::new(c(1), c(2), c(4)));
+        }
+
+        // corners whose index differ by a single bit
+        let indices =
+        [
+            0, 1,  2, 3,  4, 5,  6, 7, // x
+            0, 2,  1, 3,  4, 6,  5, 7, // y
+            0, 4,  1, 5,  2, 6,  3, 7, // z
+        ];
+
+        (corners, indices)
+    }
+}
@@ -6,7 +6,9 @@ use crate::client::gfx::
 {
     RGraphicsChunk,
     RGraphicsShared,
+    RGraphicsOutline,
 };
+use crate::client::interact::RBlockTarget;
 
 /// system that renders 3D models
 pub struct SRender;
@@ -25,9 +27,11 @@ impl System for SRender
         // resources...
         .read_resource::<RGraphicsShared>()
         .read_resource::<RGraphicsChunk>()
+        .read_resource::<RGraphicsOutline>()
+        .read_resource::<RBlockTarget>()
         .write_resource::<RGraphics>()
         // system...
-        .build(|_, _, (r_shared, r_chunk, r_gfx), _|
+        .build(|_, _, (r_shared, r_chunk, r_outline, r_target, r_gfx), _|
         {
             if r_gfx.is_none()
             {
@@ -53,17 +57,57 @@ impl System for SRender
                 // chunks
                 if let Some(chunk) = &**r_chunk
                 {
-                    // pipeline
-                    pass.pipeline(&chunk.3);
+                    // texture atlas
+                    pass.bind_group(2, &chunk.7);
 
-                    // iter meshes
-                    for mesh in chunk.4.values()
+                    // opaque first, then translucent on top,
+                    // blended with it
+                    let layers =
+                    [
+                        (&chunk.3, &chunk.6, false),
+                        (&chunk.8, &chunk.9, true),
+                    ];
+
+                    for (pipeline, fine_pipeline, translucent) in layers.iter().copied()
                     {
-                        pass.bind_group(1, &mesh.pos);
-                        pass.geometry(&mesh.geo);
-                        pass.draw(0..1);
+                        // pipeline
+                        pass.pipeline(pipeline);
+
+                        // iter meshes
+                        for mesh in chunk.4.values()
+                        {
+                            if let Some(geo) = &mesh.layer(translucent).geo
+                            {
+                                pass.bind_group(1, &mesh.pos);
+                                pass.geometry(geo);
+                                pass.draw(0..1);
+                            }
+                        }
+
+                        // fine pipeline
+                        pass.pipeline(fine_pipeline);
+
+                        // iter fine meshes
+                        for mesh in chunk.4.values()
+                        {
+                            if let Some(geo) = &mesh.layer(translucent).fine
+                            {
+                                pass.bind_group(1, &mesh.pos);
+                                pass.geometry(geo);
+                                pass.draw(0..1);
+                            }
+                        }
                     }
                 }
+
+                // targeted block, over the chunks
+                if let (Some(outline), Some(_)) = (&**r_outline, &r_target.0)
+                {
+                    pass.pipeline(&outline.0);
+                    pass.bind_group(1, &outline.2);
+                    pass.geometry(&outline.1);
+                    pass.draw(0..1);
+                }
             }
             // </frame>
 
@@ -0,0 +1,10 @@
+mod r_interact;
+mod r_target;
+mod s_interact;
+
+pub use self::
+{
+    r_interact::*,
+    r_target::*,
+    s_interact::*,
+};
@@ -0,0 +1,26 @@
+/// resource that configures how the player breaks and
+/// places blocks
+#[derive(Debug)]
+pub struct RInteraction
+{
+    /// farthest block that can be targeted
+    pub reach: f32,
+    /// seconds between repeated breaks or placements
+    /// while a mouse button is held
+    pub delay: f32,
+    /// numeric ID of the block that's placed
+    pub selected: u16,
+}
+
+impl Default for RInteraction
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            reach: 6.0,
+            delay: 0.25,
+            selected: 1,
+        }
+    }
+}
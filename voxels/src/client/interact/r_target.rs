@@ -0,0 +1,6 @@
+use crate::common::ray::RayHit;
+
+/// resource for the block the main camera is looking at,
+/// within reach, if any
+#[derive(Debug, Default)]
+pub struct RBlockTarget(pub Option<RayHit>);
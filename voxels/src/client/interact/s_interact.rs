@@ -0,0 +1,163 @@
+use ezgame::input::*;
+use ezgame::time::*;
+use ezgame::ecs::*;
+use ezmath::*;
+
+use crate::common::transform::{ CTranslation, CLocalToWorld };
+use crate::common::chunk::{ CBlockBuffer, RChunkCache, WorldBlocks };
+use crate::common::block::{ PackedBlock, UnpackedBlock, RBlockPalette, BlockEntities, BlockFace, Axis };
+use crate::common::light::RLightQueue;
+use crate::common::ray::{ Ray, RayUnloaded };
+use crate::client::camera::{ SFpsController, TMainCamera };
+use super::{ RInteraction, RBlockTarget };
+
+/// system that targets the block the main camera looks
+/// at, and breaks(left click) or places(right click) blocks
+/// against it. holding either button repeats it. the block
+/// placed is picked with the scroll wheel, and oriented along
+/// the face it's placed against
+pub struct SInteract;
+
+impl System for SInteract
+{
+    const EVENT: Event = evt::UPDATE;
+    const ORDER: Order = SFpsController::ORDER + 1;
+
+    const FLUSH: bool = true;
+
+    fn prepare(res: &mut Resources)
+    {
+        res.insert(RInteraction::default());
+        res.insert(RBlockTarget::default());
+    }
+
+    fn exe() -> Job
+    {
+        // time until the held buttons repeat, (break, place)
+        let mut cooldown = (0.0f32, 0.0f32);
+
+        // begin...
+        sys("block_interaction_system")
+        // components...
+        .with_query
+        (
+            <(Read<CTranslation>, Read<CLocalToWorld>)>::query()
+                .filter(tag::<TMainCamera>())
+        )
+        .write_component::<CBlockBuffer>()
+        // resources...
+        .read_resource::<RInput>()
+        .read_resource::<RTime>()
+        .read_resource::<RChunkCache>()
+        .read_resource::<RBlockPalette>()
+        .write_resource::<RLightQueue>()
+        .write_resource::<RInteraction>()
+        .write_resource::<RBlockTarget>()
+        // system...
+        .build(move |cmd, world, (r_in, r_time, r_cache, r_pal, r_light, r_interact, r_target), q_camera|
+        {
+            // pick the block to place, skipping air
+            let scroll = r_in.scroll_y();
+            if scroll != 0.0 && r_pal.count() > 1
+            {
+                let count = r_pal.count() as i32 - 1;
+                let step = if scroll > 0.0 { 1 } else { -1 };
+
+                r_interact.selected = ((r_interact.selected as i32 - 1 + step).rem_euclid(count) + 1) as u16;
+            }
+
+            // the camera looks down its -z
+            let (ray, dir) = match q_camera.iter(world).next()
+            {
+                Some((c_pos, c_ltw)) =>
+                {
+                    let dir = -c_ltw.right();
+
+                    (Ray::new(c_pos.0, dir, r_interact.reach), dir)
+                }
+                None => return,
+            };
+            r_target.0 = ray
+                .unloaded(RayUnloaded::Miss)
+                .cast(world, &r_cache);
+
+            let hit = match r_target.0
+            {
+                Some(hit) => hit,
+                None =>
+                {
+                    cooldown = (0.0, 0.0);
+                    return;
+                }
+            };
+
+            // act on press, then every `delay` while held
+            let repeat = |button: MouseButton, timer: &mut f32| -> bool
+            {
+                if r_in.button_pressed(button)
+                {
+                    *timer = r_interact.delay;
+                    return true;
+                }
+                if !r_in.button_down(button)
+                {
+                    return false;
+                }
+
+                *timer -= r_time.dt();
+                if *timer <= 0.0
+                {
+                    *timer += r_interact.delay;
+                    return true;
+                }
+                false
+            };
+            let break_block = repeat(MouseButton::Left, &mut cooldown.0);
+            let place_block = repeat(MouseButton::Right, &mut cooldown.1);
+
+            let mut blocks = WorldBlocks::new(world, cmd, &r_cache, &mut r_light);
+
+            if break_block
+            {
+                blocks.set_block(hit.pos, PackedBlock::default());
+            }
+            else if place_block && blocks.get_block(hit.prev) == Some(PackedBlock::default())
+            {
+                let block = PackedBlock::from_data(r_interact.selected, 0);
+
+                blocks.set_block(hit.prev, orient(block, hit.face, dir, &r_pal));
+            }
+        })
+    }
+}
+
+/// orient a block placed against a block's face, while looking
+/// along `dir`. blocks with an axis lie along the face's, and
+/// those facing somewhere face out of it, or towards the camera
+/// if they can't face up or down. other blocks are left as is
+fn orient(block: PackedBlock, face: BlockFace, dir: float3, pal: &RBlockPalette) -> PackedBlock
+{
+    let axis = match face.axis().0
+    {
+        0 => Axis::X,
+        1 => Axis::Y,
+        _ => Axis::Z,
+    };
+    let towards = if dir.x.abs() > dir.z.abs()
+    {
+        if dir.x > 0.0 { BlockFace::West } else { BlockFace::East }
+    }
+    else
+    {
+        if dir.z > 0.0 { BlockFace::North } else { BlockFace::South }
+    };
+
+    let ents = BlockEntities::default();
+    let unpacked = UnpackedBlock::new(block, int3::zeros(), pal, &ents);
+
+    unpacked
+        .with_property("axis", axis)
+        .or_else(|_| unpacked.with_property("facing", face))
+        .or_else(|_| unpacked.with_property("facing", towards))
+        .unwrap_or(block)
+}
@@ -1,5 +1,6 @@
 pub mod camera;
 pub mod gfx;
+pub mod interact;
 
 // client system bundle
 pub type Bundle =
@@ -7,8 +8,11 @@ pub type Bundle =
     camera::SFpsController,
     camera::SCameraUniform,
     camera::SCameraResize,
+    interact::SInteract,
     gfx::SGraphicsShared,
     gfx::SGraphicsChunk,
     gfx::SChunkMesh,
+    gfx::SGraphicsOutline,
+    gfx::SBlockOutline,
     gfx::SRender,
 );
\ No newline at end of file
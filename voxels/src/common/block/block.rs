@@ -24,12 +24,33 @@ pub enum PackedBlockFormat { Data, Addr }
 impl PackedBlock
 {
     pub const SIZE:usize = std::mem::size_of::<Self>();
+    /// number of block types the 11-bit ID can address
+    pub const MAX_BLOCKS: usize = 1 << 11;
 
     pub fn new(data: u16) -> Self
     {
         Self { data }
     }
 
+    /// create a data format block, given its ID(0..2048)
+    /// and variant(0..16)
+    pub fn from_data(id: u16, variant: u16) -> Self
+    {
+        debug_assert!((id as usize) < Self::MAX_BLOCKS, "block ID cannot exceed 0..2048 range!");
+        debug_assert!(variant < 16, "block variant cannot exceed 0..16 range!");
+
+        Self { data: (id << 4) | variant }
+    }
+
+    /// create an address format block, given its address(0..32768)
+    /// in its chunk's block entities
+    pub fn from_addr(addr: u16) -> Self
+    {
+        debug_assert!(addr >> 15 == 0, "block address cannot exceed 0..32768 range!");
+
+        Self { data: (1 << 15) | addr }
+    }
+
     /// get how this block is represented in memory
     pub fn format(self) -> PackedBlockFormat
     {
@@ -57,4 +78,20 @@ impl PackedBlock
     {
         self.data & 0b0000_0000_0000_1111
     }
+
+    /// (unsafe) get this block's address(0..32768) in its chunk's
+    /// block entities directly.
+    ///
+    /// data is innacurate if self.format() != BlockFormat::Addr
+    pub fn addr(self) -> u16
+    {
+        self.data & 0b0111_1111_1111_1111
+    }
+
+    /// get this block's raw 2-byte representation, as
+    /// given to `PackedBlock::new`
+    pub fn raw(self) -> u16
+    {
+        self.data
+    }
 }
\ No newline at end of file
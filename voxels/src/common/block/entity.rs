@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+use std::any::{ Any, TypeId };
+use std::fmt::Debug;
+use std::convert::TryInto;
+
+use serde::{ Serialize, de::DeserializeOwned };
+
+use super::PackedBlock;
+
+/// any data that can be attached to a block entity, like
+/// a chest's inventory or a sign's text. implemented for
+/// every cloneable, thread-safe, serializable type
+pub trait BlockEntityData: Any + Debug + Send + Sync
+{
+    /// clone this data behind a box
+    fn clone_box(&self) -> Box<dyn BlockEntityData>;
+    /// upcast to `Any`, for downcasting
+    fn as_any(&self) -> &dyn Any;
+    /// upcast to `Any`, for downcasting
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    /// name of this data's type, for payloads whose type
+    /// wasn't registered, see `BlockEntityTypes`
+    fn type_name(&self) -> &str;
+    /// encode this data as RON
+    fn encode(&self) -> Vec<u8>;
+}
+
+impl<T: Any + Debug + Send + Sync + Clone + Serialize> BlockEntityData for T
+{
+    fn clone_box(&self) -> Box<dyn BlockEntityData>
+    {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any
+    {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any
+    {
+        self
+    }
+
+    fn type_name(&self) -> &str
+    {
+        std::any::type_name::<T>()
+    }
+
+    fn encode(&self) -> Vec<u8>
+    {
+        // only fails for types serde can't represent in RON
+        ron::ser::to_string(self)
+            .map(String::into_bytes)
+            .unwrap_or_default()
+    }
+}
+
+/// payload of a type that no `BlockEntityTypes` knows, as it
+/// was decoded. it's encoded back as is, so saving a chunk or
+/// a schematic never loses it
+#[derive(Debug, Clone)]
+pub struct UnknownData
+{
+    /// name of the payload's type
+    pub ty: String,
+    /// the payload, as RON
+    pub ron: Vec<u8>,
+}
+
+impl BlockEntityData for UnknownData
+{
+    fn clone_box(&self) -> Box<dyn BlockEntityData>
+    {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any
+    {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any
+    {
+        self
+    }
+
+    fn type_name(&self) -> &str
+    {
+        &self.ty
+    }
+
+    fn encode(&self) -> Vec<u8>
+    {
+        self.ron.clone()
+    }
+}
+
+/// decodes a block entity payload of a registered type
+type Decoder = fn(&[u8]) -> Option<Box<dyn BlockEntityData>>;
+
+/// every type of block entity payload that can be saved and
+/// loaded back, by a stable name. payloads are encoded as:
+///     - u16 -> length of their type's name
+///     - their type's name, as UTF-8
+///     - the payload, as RON
+/// ```rust
+/// r_pal.entity_types_mut().register::<SignText>("sign");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BlockEntityTypes
+{
+    decoders: HashMap<String, Decoder>,
+    names: HashMap<TypeId, String>,
+}
+
+/// "big-block" data, for blocks that can't fit all
+/// their data in a packed block. these live in a chunk's
+/// `BlockEntities` table, and the packed block only
+/// stores its address in that table
+#[derive(Debug)]
+pub struct BlockEntity
+{
+    /// block ID(0..2048) of this block
+    pub id: u16,
+    /// variant(0..16) of this block
+    pub variant: u16,
+
+    /// arbitrary payload of this block
+    data: Box<dyn BlockEntityData>,
+}
+
+/// per-chunk storage of block entities, which address
+/// format packed blocks point into. slots are recycled
+/// once freed
+#[derive(Debug, Default, Clone)]
+pub struct BlockEntities
+{
+    slots: Vec<Option<BlockEntity>>,
+    free: Vec<u16>,
+}
+
+impl BlockEntity
+{
+    /// create a new block entity given its block type
+    /// and payload
+    pub fn new<T: BlockEntityData>(id: u16, variant: u16, data: T) -> Self
+    {
+        Self { id, variant, data: Box::new(data) }
+    }
+
+    /// get this block entity's payload, if it's of type T
+    pub fn data<T: BlockEntityData>(&self) -> Option<&T>
+    {
+        self.data.as_any().downcast_ref()
+    }
+
+    /// get this block entity's payload mutably, if it's
+    /// of type T
+    pub fn data_mut<T: BlockEntityData>(&mut self) -> Option<&mut T>
+    {
+        self.data.as_any_mut().downcast_mut()
+    }
+}
+
+impl Clone for BlockEntity
+{
+    fn clone(&self) -> Self
+    {
+        Self
+        {
+            id: self.id,
+            variant: self.variant,
+            data: self.data.clone_box(),
+        }
+    }
+}
+
+impl BlockEntities
+{
+    /// maximum number of block entities in a single table,
+    /// limited by the 15-bit address
+    pub const CAPACITY: usize = 1 << 15;
+
+    /// store a block entity, returning the address format
+    /// packed block that points to it. the entity is given
+    /// back if the table is full
+    pub fn alloc(&mut self, ent: BlockEntity) -> Result<PackedBlock, BlockEntity>
+    {
+        let addr = if let Some(addr) = self.free.pop()
+        {
+            self.slots[addr as usize] = Some(ent);
+            addr
+        }
+        else if self.slots.len() < Self::CAPACITY
+        {
+            self.slots.push(Some(ent));
+            (self.slots.len() - 1) as u16
+        }
+        else
+        {
+            return Err(ent);
+        };
+
+        Ok(PackedBlock::from_addr(addr))
+    }
+
+    /// remove the block entity at the given address,
+    /// making its slot available again
+    pub fn free(&mut self, addr: u16) -> Option<BlockEntity>
+    {
+        let ent = self.slots
+            .get_mut(addr as usize)?
+            .take()?;
+
+        self.free.push(addr);
+
+        Some(ent)
+    }
+
+    /// get the block entity at the given address
+    pub fn get(&self, addr: u16) -> Option<&BlockEntity>
+    {
+        self.slots.get(addr as usize)?.as_ref()
+    }
+
+    /// get the block entity at the given address mutably
+    pub fn get_mut(&mut self, addr: u16) -> Option<&mut BlockEntity>
+    {
+        self.slots.get_mut(addr as usize)?.as_mut()
+    }
+
+    /// number of live block entities in this table
+    pub fn len(&self) -> usize
+    {
+        self.slots.len() - self.free.len()
+    }
+
+    /// is this table empty?
+    pub fn is_empty(&self) -> bool
+    {
+        self.len() == 0
+    }
+
+    /// iterate all live block entities mutably, and their
+    /// address
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (u16, &mut BlockEntity)>
+    {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, e)| e.as_mut().map(|e| (i as u16, e)))
+    }
+
+    /// iterate all live block entities, and their address
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &BlockEntity)>
+    {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| e.as_ref().map(|e| (i as u16, e)))
+    }
+}
+
+impl BlockEntityTypes
+{
+    /// register a payload type under a name, which must stay
+    /// the same for saved payloads to load
+    pub fn register<T: BlockEntityData + DeserializeOwned>(&mut self, name: &str)
+    {
+        let decode: Decoder = |body| ron::de::from_bytes::<T>(body)
+            .ok()
+            .map(|data| Box::new(data) as Box<dyn BlockEntityData>);
+
+        self.decoders.insert(name.to_string(), decode);
+        self.names.insert(TypeId::of::<T>(), name.to_string());
+    }
+
+    /// encode a block entity's payload, see `BlockEntityTypes`
+    pub fn encode(&self, ent: &BlockEntity) -> Vec<u8>
+    {
+        let name = self.names
+            .get(&ent.data.as_any().type_id())
+            .map_or(ent.data.type_name(), |name| name.as_str())
+            .as_bytes();
+
+        let mut out = (name.len() as u16).to_le_bytes().to_vec();
+        out.extend_from_slice(name);
+        out.extend(ent.data.encode());
+        out
+    }
+
+    /// decode a block entity of the given type from its payload.
+    /// payloads of unregistered types, or that don't match theirs
+    /// anymore, are kept as `UnknownData`. fails with a description
+    /// if the payload is damaged
+    pub fn decode(&self, id: u16, variant: u16, payload: &[u8]) -> Result<BlockEntity, String>
+    {
+        let len = payload
+            .get(..2)
+            .map(|b| u16::from_le_bytes(b.try_into().unwrap()) as usize)
+            .ok_or_else(|| "block entity payload is empty".to_string())?;
+        let name = payload
+            .get(2..2 + len)
+            .and_then(|b| std::str::from_utf8(b).ok())
+            .ok_or_else(|| "block entity payload has a damaged type name".to_string())?;
+        let body = &payload[2 + len..];
+
+        let data = match self.decoders.get(name).and_then(|decode| decode(body))
+        {
+            Some(data) => data,
+            None => Box::new(UnknownData { ty: name.to_string(), ron: body.to_vec() }),
+        };
+        Ok(BlockEntity { id, variant, data })
+    }
+}
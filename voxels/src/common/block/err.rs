@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+use std::fmt;
+
+use super::{ PropertyKind, PropertyValue };
+
+/// errors that can occur while loading the block
+/// palette from its definitions folder
+#[derive(Debug)]
+pub enum PaletteError
+{
+    /// the definitions folder or one of its files
+    /// couldn't be read
+    Io { path: PathBuf, err: std::io::Error },
+    /// a definition file isn't valid RON, or is
+    /// missing fields
+    Parse { path: PathBuf, msg: String },
+    /// a definition file parsed, but describes a
+    /// block that can't exist
+    Invalid { path: PathBuf, msg: String },
+    /// two definition files declare the same text ID
+    DuplicateId { id: String, first: PathBuf, second: PathBuf },
+    /// more blocks than the packed block ID can
+    /// address
+    TooManyBlocks { count: usize },
+    /// more distinct textures than fit in the block
+    /// texture atlas
+    TooManyTextures { count: usize },
+}
+
+impl fmt::Display for PaletteError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            PaletteError::Io { path, err } =>
+            {
+                write!(f, "couldn't read {}: {}", path.display(), err)
+            }
+            PaletteError::Parse { path, msg } =>
+            {
+                write!(f, "couldn't parse {}: {}", path.display(), msg)
+            }
+            PaletteError::Invalid { path, msg } =>
+            {
+                write!(f, "invalid block in {}: {}", path.display(), msg)
+            }
+            PaletteError::DuplicateId { id, first, second } =>
+            {
+                write!(f, "block ID \"{}\" is declared in both {} and {}", id, first.display(), second.display())
+            }
+            PaletteError::TooManyBlocks { count } =>
+            {
+                write!(f, "{} blocks declared, but at most {} are supported", count, super::PackedBlock::MAX_BLOCKS)
+            }
+            PaletteError::TooManyTextures { count } =>
+            {
+                write!(f, "{} block textures declared, but at most {} fit in the texture atlas", count, super::RBlockPalette::MAX_TEXTURES)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PaletteError
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)>
+    {
+        match self
+        {
+            PaletteError::Io { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// errors that can occur while reading or writing
+/// a block's state properties
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum StateError
+{
+    /// the block type doesn't declare this property
+    UnknownProperty { name: String },
+    /// the value isn't of the property's kind, or
+    /// is out of its range
+    InvalidValue { name: String, kind: PropertyKind, val: PropertyValue },
+    /// the block is of the address format, whose state
+    /// lives in its chunk's block entities and must be
+    /// written there
+    AddressFormat,
+}
+
+impl fmt::Display for StateError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            StateError::UnknownProperty { name } =>
+            {
+                write!(f, "block has no property \"{}\"", name)
+            }
+            StateError::InvalidValue { name, kind, val } =>
+            {
+                write!(f, "{:?} isn't a valid value for property \"{}\" of kind {:?}", val, name, kind)
+            }
+            StateError::AddressFormat =>
+            {
+                write!(f, "address format blocks store their state in block entities")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StateError { }
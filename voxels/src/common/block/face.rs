@@ -1,8 +1,12 @@
 use ezmath::*;
 
+use super::Rotation;
+
 /// block face enum, in global coordinates.
 /// that means a block's right face, for example
 /// is always right no matter how it's rotated.
+/// oriented blocks map their own, local faces
+/// to these using `to_world` and `to_local`
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 #[allow(dead_code)]
 #[repr(u8)]
@@ -67,6 +71,58 @@ impl BlockFace
             BlockFace::Up    => BlockFace::Down,
         }
     }
+
+    /// get the block face whose normal is the given
+    /// vector, if any
+    pub fn from_normal(normal: int3) -> Option<BlockFace>
+    {
+        (0..6usize)
+            .map(BlockFace::from)
+            .find(|f| f.normal() == normal)
+    }
+
+    /// get the world face that this local face of a
+    /// block points to, given the block's rotation
+    pub fn to_world(self, rot: Rotation) -> BlockFace
+    {
+        Self::from_normal(rot.apply(self.normal())).unwrap()
+    }
+
+    /// get the local face of a block that points to
+    /// this world face, given the block's rotation
+    pub fn to_local(self, rot: Rotation) -> BlockFace
+    {
+        Self::from_normal(rot.invert(self.normal())).unwrap()
+    }
+
+    /// get the axis(0 = x, 1 = y, 2 = z) this block
+    /// face is perpendicular to, and whether it faces
+    /// that axis' positive direction
+    pub fn axis(self) -> (usize, bool)
+    {
+        match self
+        {
+            BlockFace::North => (2, false),
+            BlockFace::South => (2, true),
+            BlockFace::West  => (0, false),
+            BlockFace::East  => (0, true),
+            BlockFace::Down  => (1, false),
+            BlockFace::Up    => (1, true),
+        }
+    }
+
+    /// get the two axes(0 = x, 1 = y, 2 = z) that
+    /// span this block face's plane, as (u, v).
+    /// opposite faces share the same plane axes
+    pub fn plane(self) -> (usize, usize)
+    {
+        match self
+        {
+            BlockFace::North | BlockFace::South => (0, 1),
+            BlockFace::West  | BlockFace::East  => (2, 1),
+            BlockFace::Down  | BlockFace::Up    => (0, 2),
+        }
+    }
 }
 
 impl From<usize> for BlockFace
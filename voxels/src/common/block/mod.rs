@@ -2,10 +2,25 @@ mod unpacked;
 mod palette;
 mod block;
 mod face;
+mod entity;
+mod state;
+mod registry;
+mod rotation;
+mod opacity;
+mod err;
 
 pub use unpacked::*;
 pub use palette::*;
 pub use block::*;
 pub use face::*;
+pub use entity::*;
+pub use state::*;
+pub use registry::*;
+pub use rotation::*;
+pub use opacity::*;
+pub use err::*;
 
-pub mod shapes;
\ No newline at end of file
+pub mod shapes;
+
+#[cfg(test)]
+mod tests;
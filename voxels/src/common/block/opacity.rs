@@ -0,0 +1,33 @@
+use serde::Deserialize;
+
+/// how much can be seen through a block type, which decides
+/// which faces it hides and how it's drawn
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+pub enum Opacity
+{
+    /// nothing can be seen through the block, ie. stone
+    Opaque,
+    /// the block's texture has fully transparent holes,
+    /// but is otherwise opaque, ie. leaves
+    Cutout,
+    /// the block's texture is partly see-through, and is
+    /// blended with whatever is behind it, ie. glass
+    Translucent,
+}
+
+impl Opacity
+{
+    /// can nothing be seen through this block?
+    pub fn is_opaque(self) -> bool
+    {
+        self == Opacity::Opaque
+    }
+}
+
+impl Default for Opacity
+{
+    fn default() -> Self
+    {
+        Opacity::Opaque
+    }
+}
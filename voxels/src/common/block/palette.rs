@@ -1,3 +1,7 @@
+use std::collections::{ HashMap, HashSet };
+use std::path::{ Path, PathBuf };
+
+use serde::Deserialize;
 use ezmath::*;
 
 use shapes::*;
@@ -19,50 +23,196 @@ pub struct BlockData
 
     /// block's shape
     pub shape: BlockShapes,
+    /// how the block can be oriented
+    pub rotation: RotationMode,
+    /// how much can be seen through the block
+    pub opacity: Opacity,
+    /// block light level(0..16) the block emits
+    pub emission: u8,
+
+    /// texture of each face of the block, in `BlockFace`
+    /// order. blocks without textures are drawn in their
+    /// fallback colour
+    pub textures: Option<[PathBuf; 6]>,
+
+    /// block's state properties, packed in the
+    /// variant bits. shape properties come first
+    pub properties: Vec<BlockProperty>,
 }
 
 /// block data resource. stores all types
-/// of blocks in the game
+/// of blocks in the game, and maps their
+/// text IDs to the numeric IDs used by
+/// packed blocks
 #[derive(Debug)]
 pub struct RBlockPalette
 {
-    blocks: Vec<BlockData>
+    blocks: Vec<BlockData>,
+    ids: HashMap<String, u16>,
+
+    /// payload types of block entities, for saving them
+    entity_types: BlockEntityTypes,
+}
+
+/// a block as it's written in a definition file. every
+/// field that isn't strictly needed should have a default,
+/// so that adding properties doesn't break existing files
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlockDef
+{
+    name: String,
+    id: String,
+
+    col: (f32, f32, f32, f32),
+
+    #[serde(default = "BlockDef::default_shape")]
+    shape: BlockShapes,
+    #[serde(default)]
+    rotation: RotationMode,
+    #[serde(default)]
+    opacity: Opacity,
+    #[serde(default)]
+    emission: u8,
+
+    #[serde(default)]
+    textures: Option<TexturesDef>,
+
+    #[serde(default)]
+    properties: Vec<PropertyDef>,
+}
+
+/// a block's textures as they're written in a definition
+/// file. paths are relative to the definitions folder
+/// ```ron
+/// textures: All("stone.png"),
+/// textures: Column(top: "grass_top.png", bottom: "dirt.png", side: "grass_side.png"),
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+enum TexturesDef
+{
+    /// same texture on every face
+    All(String),
+    /// one texture on top, one on the bottom, and one
+    /// on the four sides
+    Column { top: String, bottom: String, side: String },
+    /// a texture for each face
+    Faces { north: String, south: String, west: String, east: String, down: String, up: String },
+}
+
+/// a block state property as it's written in a
+/// definition file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PropertyDef
+{
+    name: String,
+    kind: PropertyKind,
 }
 
 impl RBlockPalette
 {
+    /// maximum number of distinct textures, which is the
+    /// number of tiles in the 64 x 64 block texture atlas
+    /// the chunk vertex' u/v fields can address
+    pub const MAX_TEXTURES: usize = 4096;
+
     /// loads blocks given the path folder where
-    /// their config is located
-    pub fn load(_: &str) -> Self
+    /// their config is located. every `.ron` file
+    /// in that folder describes one block, and
+    /// IDs are assigned in file name order. air
+    /// is built-in and always has ID 0
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PaletteError>
     {
-        Self
+        let path = path.as_ref();
+
+        // collect definition files
+        let mut files = std::fs::read_dir(path)
+            .map_err(|err| PaletteError::Io { path: path.into(), err })?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<PathBuf>, _>>()
+            .map_err(|err| PaletteError::Io { path: path.into(), err })?;
+
+        files.retain(|f| f.is_file() && f.extension().map_or(false, |e| e == "ron"));
+        files.sort();
+
+        // 2048 limit, counting air
+        if files.len() + 1 > PackedBlock::MAX_BLOCKS
         {
-            // temporary hard-coded blocks
-            blocks: vec!
-            [
-                BlockData
-                {
-                    name: "Air".into(),
-                    id: "air".into(),
-                    col: float4::one(),
-                    shape: BlockShapes::None
-                },
-                BlockData
+            return Err(PaletteError::TooManyBlocks { count: files.len() + 1 });
+        }
+
+        let mut blocks = vec![Self::air()];
+        let mut origins = vec![PathBuf::from("<built-in>")];
+
+        for file in files
+        {
+            let block = BlockDef::load(&file)?;
+
+            // text IDs are unique
+            if let Some(i) = blocks.iter().position(|b| b.id == block.id)
+            {
+                return Err(PaletteError::DuplicateId
                 {
-                    name: "Grass".into(),
-                    id: "grass".into(),
-                    col: float4::new(0.0, 1.0, 0.0, 1.0),
-                    shape: BlockShapes::Cube
-                },
-                BlockData
+                    id: block.id,
+                    first: origins[i].clone(),
+                    second: file,
+                });
+            }
+
+            blocks.push(block);
+            origins.push(file);
+        }
+
+        // every texture, or fallback colour for blocks without
+        // textures, takes a tile in the texture atlas
+        let textures = blocks
+            .iter()
+            .flat_map(|b|
+            {
+                // colours are as precise as the atlas' pixels
+                let col =
+                [
+                    (b.col.x * 255.0) as u8,
+                    (b.col.y * 255.0) as u8,
+                    (b.col.z * 255.0) as u8,
+                    (b.col.w * 255.0) as u8,
+                ];
+                match &b.textures
                 {
-                    name: "Dirt".into(),
-                    id: "dirt".into(),
-                    col: float4::new(0.167, 0.133, 0.0, 1.0),
-                    shape: BlockShapes::Cube
+                    Some(tex) => tex.iter().map(|t| (Some(t), col)).collect::<Vec<_>>(),
+                    None => vec![(None, col)],
                 }
-            ]
+            })
+            .collect::<HashSet<_>>()
+            .len();
+        if textures > Self::MAX_TEXTURES
+        {
+            return Err(PaletteError::TooManyTextures { count: textures });
         }
+
+        let ids = blocks
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (b.id.clone(), i as u16))
+            .collect();
+
+        Ok(Self { blocks, ids, entity_types: BlockEntityTypes::default() })
+    }
+
+    /// get the block entity payload types that can be
+    /// saved and loaded back
+    pub fn entity_types(&self) -> &BlockEntityTypes
+    {
+        &self.entity_types
+    }
+
+    /// get the block entity payload types mutably, to
+    /// register one, see `BlockEntityTypes`
+    pub fn entity_types_mut(&mut self) -> &mut BlockEntityTypes
+    {
+        &mut self.entity_types
     }
 
     /// get block data for a given block ID
@@ -70,4 +220,206 @@ impl RBlockPalette
     {
         &self.blocks[id]
     }
-}
\ No newline at end of file
+
+    /// get the numeric ID of a block given its text ID.
+    /// numeric IDs aren't stable across palette changes,
+    /// so they should never be saved without a name table
+    pub fn id(&self, text_id: &str) -> Option<u16>
+    {
+        self.ids.get(text_id).copied()
+    }
+
+    /// get the default state of a block given its text ID
+    /// ```rust
+    /// let grass = r_pal.block("grass").unwrap();
+    /// blocks.set_packed((0, 0, 0), grass);
+    /// ```
+    pub fn block(&self, text_id: &str) -> Option<PackedBlock>
+    {
+        self.id(text_id).map(|id| PackedBlock::from_data(id, 0))
+    }
+
+    /// get the text ID of a block given its numeric ID
+    pub fn text_id(&self, id: u16) -> Option<&str>
+    {
+        self.blocks.get(id as usize).map(|b| b.id.as_str())
+    }
+
+    /// iterate every block type in this palette and its
+    /// numeric ID, including air
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &BlockData)>
+    {
+        self.blocks
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (i as u16, b))
+    }
+
+    /// number of block types in this palette, including
+    /// air
+    pub fn count(&self) -> usize
+    {
+        self.blocks.len()
+    }
+
+    /// the built-in air block, which is what
+    /// the default packed block points to
+    fn air() -> BlockData
+    {
+        BlockData
+        {
+            name: "Air".into(),
+            id: "air".into(),
+            col: float4::one(),
+            shape: BlockShapes::None,
+            rotation: RotationMode::None,
+            opacity: Opacity::Translucent,
+            emission: 0,
+            textures: None,
+            properties: Vec::new(),
+        }
+    }
+}
+
+impl BlockData
+{
+    /// get one of this block's state properties by name
+    pub fn property(&self, name: &str) -> Option<&BlockProperty>
+    {
+        self.properties
+            .iter()
+            .find(|p| p.name == name)
+    }
+}
+
+impl BlockDef
+{
+    /// read, parse, and validate a single definition file
+    fn load(path: &Path) -> Result<BlockData, PaletteError>
+    {
+        let src = std::fs::read_to_string(path)
+            .map_err(|err| PaletteError::Io { path: path.into(), err })?;
+
+        let def: Self = ron::de::from_str(&src)
+            .map_err(|err| PaletteError::Parse { path: path.into(), msg: err.to_string() })?;
+
+        let invalid = |msg: String| Err(PaletteError::Invalid { path: path.into(), msg });
+
+        // snakecase text ID
+        if !is_snakecase(&def.id)
+        {
+            return invalid(format!("ID \"{}\" must be non-empty snakecase", def.id));
+        }
+        // colour channels are normalized
+        let col = float4::new(def.col.0, def.col.1, def.col.2, def.col.3);
+        if col.iter().any(|c| !(0.0..=1.0).contains(c))
+        {
+            return invalid(format!("colour {:?} must be within 0.0..=1.0", def.col));
+        }
+        // emission is a light level
+        if def.emission > 15
+        {
+            return invalid(format!("emission {} must be within 0..=15", def.emission));
+        }
+        // textures exist, relative to the definition
+        let textures = match def.textures
+        {
+            Some(tex) =>
+            {
+                let dir = path.parent().unwrap_or_else(|| Path::new(""));
+                let faces = tex.faces();
+                let textures =
+                [
+                    dir.join(&faces[0]), dir.join(&faces[1]), dir.join(&faces[2]),
+                    dir.join(&faces[3]), dir.join(&faces[4]), dir.join(&faces[5]),
+                ];
+
+                if let Some(missing) = textures.iter().find(|t| !t.is_file())
+                {
+                    return invalid(format!("texture {} doesn't exist", missing.display()));
+                }
+                Some(textures)
+            }
+            None => None,
+        };
+        // shape properties, then rotation, then own, packed
+        // in order
+        let mut properties = Vec::<BlockProperty>::new();
+        let mut offset = 0;
+
+        let shape_props = def.shape
+            .properties()
+            .iter()
+            .map(|(name, kind)| (name.to_string(), *kind));
+        let rotation_props = def.rotation
+            .property()
+            .into_iter()
+            .map(|(name, kind)| (name.to_string(), kind));
+        let own_props = def.properties
+            .into_iter()
+            .map(|p| (p.name, p.kind));
+
+        for (name, kind) in shape_props.chain(rotation_props).chain(own_props)
+        {
+            if !is_snakecase(&name)
+            {
+                return invalid(format!("property \"{}\" must be non-empty snakecase", name));
+            }
+            if properties.iter().any(|p| p.name == name)
+            {
+                return invalid(format!("property \"{}\" is declared twice", name));
+            }
+            if offset + kind.bits() > 4
+            {
+                return invalid(format!("property \"{}\" doesn't fit in the block's 4 variant bits", name));
+            }
+
+            properties.push(BlockProperty { name, kind, offset });
+            offset += kind.bits();
+        }
+
+        Ok(BlockData
+        {
+            name: def.name,
+            id: def.id,
+            col,
+            shape: def.shape,
+            rotation: def.rotation,
+            opacity: def.opacity,
+            emission: def.emission,
+            textures,
+            properties,
+        })
+    }
+
+    fn default_shape() -> BlockShapes
+    {
+        BlockShapes::Cube
+    }
+}
+
+impl TexturesDef
+{
+    /// texture path of every face, in `BlockFace` order
+    fn faces(self) -> [String; 6]
+    {
+        match self
+        {
+            TexturesDef::All(t) => [t.clone(), t.clone(), t.clone(), t.clone(), t.clone(), t],
+            TexturesDef::Column { top, bottom, side } =>
+            [
+                side.clone(), side.clone(), side.clone(), side, bottom, top
+            ],
+            TexturesDef::Faces { north, south, west, east, down, up } =>
+            [
+                north, south, west, east, down, up
+            ],
+        }
+    }
+}
+
+/// is the string a non-empty, snakecase identifier?
+fn is_snakecase(s: &str) -> bool
+{
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
@@ -0,0 +1,158 @@
+use serde::{ Serialize, Deserialize };
+
+use super::{ PackedBlock, PackedBlockFormat, RBlockPalette };
+
+/// text IDs of every block in a palette, indexed by
+/// their numeric ID at the time the table was made.
+/// saved or exported block data carries one of these,
+/// so it can be remapped to whatever palette loads it
+#[derive(Debug, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct BlockNameTable(pub Vec<String>);
+
+/// maps numeric IDs of an old palette(described by its
+/// name table) to those of the current palette
+#[derive(Debug, Clone)]
+pub struct BlockRemap
+{
+    /// new ID for each old ID
+    map: Vec<u16>,
+    /// text IDs from the old palette that don't exist
+    /// anymore. these are remapped to air
+    missing: Vec<String>,
+}
+
+impl RBlockPalette
+{
+    /// get the name table of this palette, to be saved
+    /// alongside block data
+    pub fn name_table(&self) -> BlockNameTable
+    {
+        BlockNameTable
+        (
+            (0..self.count() as u16)
+                .filter_map(|id| self.text_id(id))
+                .map(String::from)
+                .collect()
+        )
+    }
+
+    /// create a remapping from the palette described by the
+    /// name table to this palette. blocks that were removed
+    /// since become air
+    pub fn remap(&self, table: &BlockNameTable) -> BlockRemap
+    {
+        let mut missing = Vec::new();
+
+        let map = table.0
+            .iter()
+            .map(|name| match self.id(name)
+            {
+                Some(id) => id,
+                None =>
+                {
+                    missing.push(name.clone());
+                    0
+                }
+            })
+            .collect();
+
+        BlockRemap { map, missing }
+    }
+
+    /// create a remapping from this palette to the one
+    /// described by the name table, the inverse of
+    /// `RBlockPalette::remap`. blocks the table doesn't
+    /// name become air
+    pub fn remap_to(&self, table: &BlockNameTable) -> BlockRemap
+    {
+        let mut missing = Vec::new();
+
+        let map = (0..self.count() as u16)
+            .map(|id|
+            {
+                let name = self.text_id(id).unwrap_or_default();
+
+                match table.0.iter().position(|n| n == name)
+                {
+                    Some(old) => old as u16,
+                    None =>
+                    {
+                        missing.push(name.to_string());
+                        0
+                    }
+                }
+            })
+            .collect();
+
+        BlockRemap { map, missing }
+    }
+}
+
+impl BlockNameTable
+{
+    /// add the text IDs of a palette's blocks this table
+    /// doesn't have yet, keeping the IDs of those it has
+    pub fn extend(&mut self, pal: &RBlockPalette)
+    {
+        for id in 0..pal.count() as u16
+        {
+            if let Some(name) = pal.text_id(id)
+            {
+                if !self.0.iter().any(|n| n == name)
+                {
+                    self.0.push(name.to_string());
+                }
+            }
+        }
+    }
+}
+
+impl BlockRemap
+{
+    /// get the current ID for an old ID. IDs beyond
+    /// the old palette become air
+    pub fn id(&self, old: u16) -> u16
+    {
+        self.map
+            .get(old as usize)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// remap a data format packed block, keeping its
+    /// variant. blocks that are missing become plain air,
+    /// since their variant bits only meant something for
+    /// their old type. address format blocks are returned
+    /// as is, and their block entities must be remapped
+    /// instead
+    pub fn apply(&self, block: PackedBlock) -> PackedBlock
+    {
+        match block.format()
+        {
+            PackedBlockFormat::Data => match self.id(block.id())
+            {
+                0 => PackedBlock::default(),
+                id => PackedBlock::from_data(id, block.variant()),
+            },
+            PackedBlockFormat::Addr => block,
+        }
+    }
+
+    /// does this remapping change nothing? if so, it
+    /// can be skipped
+    pub fn is_identity(&self) -> bool
+    {
+        self.missing.is_empty()
+        && self.map
+            .iter()
+            .enumerate()
+            .all(|(i, id)| i == *id as usize)
+    }
+
+    /// text IDs of the old palette that couldn't be
+    /// found in the current one
+    pub fn missing(&self) -> &[String]
+    {
+        &self.missing
+    }
+}
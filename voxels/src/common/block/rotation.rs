@@ -0,0 +1,136 @@
+use serde::Deserialize;
+use ezmath::*;
+
+use super::{ BlockFace, Axis, PropertyKind };
+
+/// ways a block type can be oriented, each storing the
+/// block's rotation in a different state property
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+pub enum RotationMode
+{
+    /// the block is never rotated
+    None,
+    /// the block's local up face points along its "axis"
+    /// property, ie. logs
+    Axis,
+    /// the block's local north face points towards its
+    /// "facing" property, any of the six faces, ie. furnaces
+    Facing,
+    /// like `Facing`, but only towards the four horizontal
+    /// faces, ie. stairs
+    Horizontal,
+}
+
+/// one of the 24 rotations of a cube, mapping a block's local
+/// faces onto world faces. it's described by the world faces
+/// that the block's local north and up faces point to
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Rotation
+{
+    north: BlockFace,
+    up: BlockFace,
+}
+
+impl RotationMode
+{
+    /// get the block state property this mode stores a
+    /// block's rotation in, if any. it's declared right
+    /// after the shape's own properties
+    pub fn property(self) -> Option<(&'static str, PropertyKind)>
+    {
+        match self
+        {
+            RotationMode::None => None,
+            RotationMode::Axis => Some(("axis", PropertyKind::Axis)),
+            RotationMode::Facing => Some(("facing", PropertyKind::Facing)),
+            RotationMode::Horizontal => Some(("facing", PropertyKind::Horizontal)),
+        }
+    }
+}
+
+impl Default for RotationMode
+{
+    fn default() -> Self
+    {
+        RotationMode::None
+    }
+}
+
+impl Rotation
+{
+    /// rotation that leaves every face as it is
+    pub const IDENTITY: Rotation = Rotation { north: BlockFace::North, up: BlockFace::Up };
+
+    /// create a new rotation given the world faces that a
+    /// block's local north and up faces point to. fails if
+    /// those aren't perpendicular
+    pub fn new(north: BlockFace, up: BlockFace) -> Option<Self>
+    {
+        if north.axis().0 == up.axis().0
+        {
+            None
+        }
+        else
+        {
+            Some(Self { north, up })
+        }
+    }
+
+    /// rotation whose local north face points towards the
+    /// given world face. blocks facing up or down are tipped
+    /// over, so their local up face points south or north
+    pub fn facing(face: BlockFace) -> Self
+    {
+        match face
+        {
+            BlockFace::Up => Self { north: BlockFace::Up, up: BlockFace::South },
+            BlockFace::Down => Self { north: BlockFace::Down, up: BlockFace::North },
+            _ => Self { north: face, up: BlockFace::Up },
+        }
+    }
+
+    /// rotation whose local up face points along the given
+    /// axis
+    pub fn axis(axis: Axis) -> Self
+    {
+        match axis
+        {
+            Axis::X => Self { north: BlockFace::North, up: BlockFace::East },
+            Axis::Y => Self::IDENTITY,
+            Axis::Z => Self { north: BlockFace::Up, up: BlockFace::South },
+        }
+    }
+
+    /// is this the identity rotation?
+    pub fn is_identity(self) -> bool
+    {
+        self == Self::IDENTITY
+    }
+
+    /// rotate a direction from a block's local space to
+    /// world space
+    pub fn apply(self, v: int3) -> int3
+    {
+        let (x, y, z) = self.basis();
+
+        x * v.x + y * v.y + z * v.z
+    }
+
+    /// rotate a direction from world space to a block's
+    /// local space
+    pub fn invert(self, v: int3) -> int3
+    {
+        let (x, y, z) = self.basis();
+
+        int3::new(x.dot(&v), y.dot(&v), z.dot(&v))
+    }
+
+    /// world directions of the local x, y, and z axes
+    fn basis(self) -> (int3, int3, int3)
+    {
+        let y = self.up.normal();
+        let z = -self.north.normal();
+
+        (y.cross(&z), y, z)
+    }
+}
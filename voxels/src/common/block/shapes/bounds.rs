@@ -0,0 +1,104 @@
+use ezmath::*;
+
+use crate::common::block::{ BlockFace, Rotation };
+
+/// number of subdivisions of a block along each axis,
+/// for shapes that don't snap to the block grid. this
+/// is the "4th degree precision" of the fine vertex model,
+/// so nothing is thinner than a quarter block, and shapes
+/// centred in the block, like fence posts and panes, are
+/// half a block thick
+pub const SHAPE_PRECISION: u8 = 4;
+
+/// axis-aligned box within a single block, in units of
+/// 1/SHAPE_PRECISION blocks. block shapes are described
+/// as unions of these, for meshing, culling, and collision
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ShapeBox
+{
+    /// minimum corner, inclusive. each component is
+    /// within 0..=SHAPE_PRECISION
+    pub min: [u8; 3],
+    /// maximum corner, exclusive. each component is
+    /// within 0..=SHAPE_PRECISION
+    pub max: [u8; 3],
+}
+
+impl ShapeBox
+{
+    /// box that fills the entire block
+    pub const FULL: ShapeBox = ShapeBox::new([0; 3], [SHAPE_PRECISION; 3]);
+
+    /// create a new shape box, given its corners
+    pub const fn new(min: [u8; 3], max: [u8; 3]) -> Self
+    {
+        Self { min, max }
+    }
+
+    /// does this box touch the boundary of its block
+    /// on the given face?
+    pub fn touches(&self, face: BlockFace) -> bool
+    {
+        let (axis, positive) = face.axis();
+
+        if positive
+        {
+            self.max[axis] == SHAPE_PRECISION
+        }
+        else
+        {
+            self.min[axis] == 0
+        }
+    }
+
+    /// this box, rotated about the centre of its block
+    pub fn rotated(&self, rot: Rotation) -> ShapeBox
+    {
+        let centre = int3::repeat(SHAPE_PRECISION as i32 / 2);
+        let corner = |c: [u8; 3]|
+        {
+            rot.apply(int3::new(c[0] as i32, c[1] as i32, c[2] as i32) - centre) + centre
+        };
+
+        let a = corner(self.min);
+        let b = corner(self.max);
+
+        ShapeBox::new
+        (
+            [a.x.min(b.x) as u8, a.y.min(b.y) as u8, a.z.min(b.z) as u8],
+            [a.x.max(b.x) as u8, a.y.max(b.y) as u8, a.z.max(b.z) as u8],
+        )
+    }
+
+    /// coverage of this box on the given face of its block,
+    /// as a SHAPE_PRECISION x SHAPE_PRECISION bitmask. a
+    /// box that doesn't touch that face covers nothing
+    pub fn face_mask(&self, face: BlockFace) -> u16
+    {
+        if !self.touches(face)
+        {
+            return 0;
+        }
+
+        let (u, v) = face.plane();
+        let mut mask = 0;
+
+        for j in self.min[v]..self.max[v]
+        {
+            for i in self.min[u]..self.max[u]
+            {
+                mask |= 1 << (j * SHAPE_PRECISION + i);
+            }
+        }
+        mask
+    }
+}
+
+/// coverage of a union of boxes on the given face of their
+/// block. see `ShapeBox::face_mask`
+pub fn face_mask(boxes: &[ShapeBox], face: BlockFace) -> u16
+{
+    boxes
+        .iter()
+        .fold(0, |mask, b| mask | b.face_mask(face))
+}
@@ -0,0 +1,39 @@
+use crate::common::block::UnpackedBlock;
+
+use super::StairCorner;
+
+/// the blocks around a block, in world `BlockFace` order.
+/// a neighbour is missing if its chunk isn't loaded
+pub type Neighbors<'a> = [Option<UnpackedBlock<'a>>; 6];
+
+/// how a block connects to the blocks around it, in its
+/// local frame. only shapes whose boxes depend on their
+/// neighbours, like fences and stairs, look at this
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Connections
+{
+    /// whether the block connects to its neighbour on each
+    /// horizontal local face, in `BlockFace` order(north,
+    /// south, west, east)
+    pub sides: [bool; 4],
+    /// corner a stair forms with the stairs around it
+    pub corner: StairCorner,
+}
+
+impl Connections
+{
+    /// a block that isn't connected to anything
+    pub const NONE: Connections = Connections
+    {
+        sides: [false; 4],
+        corner: StairCorner::Straight,
+    };
+}
+
+impl Default for Connections
+{
+    fn default() -> Self
+    {
+        Self::NONE
+    }
+}
@@ -0,0 +1,55 @@
+use super::{ Connections, ShapeBox, SHAPE_PRECISION };
+
+/// get the boxes that make up a fence, in its local frame: a
+/// post, with two rails towards every connected side
+pub fn fence_boxes(conn: Connections) -> Vec<ShapeBox>
+{
+    let mut boxes = vec![post()];
+
+    for (i, _) in conn.sides.iter().enumerate().filter(|(_, c)| **c)
+    {
+        boxes.push(arm(i, 1, 2));
+        boxes.push(arm(i, 3, 4));
+    }
+    boxes
+}
+
+/// get the boxes that make up a pane, in its local frame: a
+/// post, stretched into a wall towards every connected side
+pub fn pane_boxes(conn: Connections) -> Vec<ShapeBox>
+{
+    let mut boxes = vec![post()];
+
+    for (i, _) in conn.sides.iter().enumerate().filter(|(_, c)| **c)
+    {
+        boxes.push(arm(i, 0, SHAPE_PRECISION));
+    }
+    boxes
+}
+
+/// the centre post of fences and panes. it's centred on the
+/// quarter-block grid, so it spans the middle two quarters
+fn post() -> ShapeBox
+{
+    const A: u8 = SHAPE_PRECISION / 4;
+    const B: u8 = SHAPE_PRECISION - A;
+
+    ShapeBox::new([A, 0, A], [B, SHAPE_PRECISION, B])
+}
+
+/// a box going from the post towards a horizontal side(in
+/// `BlockFace` order), within the given heights
+fn arm(side: usize, min_y: u8, max_y: u8) -> ShapeBox
+{
+    const P: u8 = SHAPE_PRECISION;
+    const A: u8 = SHAPE_PRECISION / 4;
+    const B: u8 = SHAPE_PRECISION - A;
+
+    match side
+    {
+        0 => ShapeBox::new([A, min_y, 0], [B, max_y, A]),     // north
+        1 => ShapeBox::new([A, min_y, B], [B, max_y, P]),     // south
+        2 => ShapeBox::new([0, min_y, A], [A, max_y, B]),     // west
+        _ => ShapeBox::new([B, min_y, A], [P, max_y, B]),     // east
+    }
+}
@@ -1,3 +1,6 @@
+use std::convert::TryFrom;
+
+use super::ShapeBox;
 
 /// gives english names to the 'variants' number of blocks
 /// of the half type
@@ -25,22 +28,54 @@ pub enum HalfBlockVariants
     DownUp = 8,
 }
 
-impl From<usize> for HalfBlockVariants
+impl TryFrom<u16> for HalfBlockVariants
 {
-    fn from(num: usize) -> Self
+    type Error = u16;
+
+    fn try_from(num: u16) -> Result<Self, Self::Error>
     {
         match num
         {
-            0 => Self::North,
-            1 => Self::South,
-            2 => Self::West,
-            3 => Self::East,
-            4 => Self::Down,
-            5 => Self::Up,
-            6 => Self::NorthSouth,
-            7 => Self::WestEast,
-            8 => Self::DownUp,
-            _ => Self::Down,
+            0 => Ok(Self::North),
+            1 => Ok(Self::South),
+            2 => Ok(Self::West),
+            3 => Ok(Self::East),
+            4 => Ok(Self::Down),
+            5 => Ok(Self::Up),
+            6 => Ok(Self::NorthSouth),
+            7 => Ok(Self::WestEast),
+            8 => Ok(Self::DownUp),
+            _ => Err(num),
+        }
+    }
+}
+
+impl HalfBlockVariants
+{
+    /// get the boxes that make up this half block variant.
+    /// the 'full' variants are made of two halves of the
+    /// same block, so they fill the entire block
+    pub fn boxes(self) -> &'static [ShapeBox]
+    {
+        const NORTH: [ShapeBox; 1] = [ShapeBox::new([0, 0, 0], [4, 4, 2])];
+        const SOUTH: [ShapeBox; 1] = [ShapeBox::new([0, 0, 2], [4, 4, 4])];
+        const WEST: [ShapeBox; 1] = [ShapeBox::new([0, 0, 0], [2, 4, 4])];
+        const EAST: [ShapeBox; 1] = [ShapeBox::new([2, 0, 0], [4, 4, 4])];
+        const DOWN: [ShapeBox; 1] = [ShapeBox::new([0, 0, 0], [4, 2, 4])];
+        const UP: [ShapeBox; 1] = [ShapeBox::new([0, 2, 0], [4, 4, 4])];
+        const FULL: [ShapeBox; 1] = [ShapeBox::FULL];
+
+        match self
+        {
+            Self::North => &NORTH,
+            Self::South => &SOUTH,
+            Self::West => &WEST,
+            Self::East => &EAST,
+            Self::Down => &DOWN,
+            Self::Up => &UP,
+            Self::NorthSouth => &FULL,
+            Self::WestEast => &FULL,
+            Self::DownUp => &FULL,
         }
     }
 }
\ No newline at end of file
@@ -1,8 +1,21 @@
+mod bounds;
 mod half;
+mod stairs;
+mod fence;
+mod connect;
 
+pub use bounds::*;
 pub use half::*;
+pub use stairs::*;
+pub use fence::*;
+pub use connect::*;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
+use super::PropertyKind;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, serde::Deserialize)]
 #[allow(dead_code)]
 pub enum BlockShapes
 {
@@ -17,7 +30,132 @@ pub enum BlockShapes
     Cube,
     /// half block shape, which can be rotated and stacked.
     /// it uses the 4th degree precesion vertex model,
-    /// which is 1 byte more than the vertex model used by
-    /// the cube shape
+    /// which is twice the size of the vertex model used
+    /// by the cube shape
     Half,
-}
\ No newline at end of file
+    /// stairs, made of a bottom slab and a step towards
+    /// their local north. they form corners with the stairs
+    /// around them, and should be paired with the horizontal
+    /// rotation mode to face any direction
+    Stairs,
+    /// two diagonal planes crossing through the block, for
+    /// grass and flowers. it has no collision, and never
+    /// hides the faces of its neighbours
+    Cross,
+    /// fence post, with rails towards the fences and full
+    /// blocks around it. posts are half a block thick, see
+    /// `SHAPE_PRECISION`
+    Fence,
+    /// pane, that stretches towards the panes and full blocks
+    /// around it, ie. glass panes. as thin as the fine vertex
+    /// allows, half a block, see `SHAPE_PRECISION`
+    Pane,
+    /// thin layer at the bottom of the block, one to four
+    /// quarters tall, ie. snow
+    Layer,
+}
+
+impl BlockShapes
+{
+    /// get the boxes that make up this shape, given the
+    /// variant of the block it belongs to and how it connects
+    /// to its neighbours, in the block's local frame. shape
+    /// properties always come first in the variant bits
+    pub fn boxes(self, variant: usize, conn: Connections) -> Cow<'static, [ShapeBox]>
+    {
+        const CUBE: [ShapeBox; 1] = [ShapeBox::FULL];
+        // selection box of a cross, which it doesn't
+        // collide with
+        const CROSS: [ShapeBox; 1] = [ShapeBox::new([1, 0, 1], [3, 3, 3])];
+
+        match self
+        {
+            BlockShapes::None => Cow::Borrowed(&[]),
+            BlockShapes::Cube => Cow::Borrowed(&CUBE),
+            BlockShapes::Half => match HalfBlockVariants::try_from(variant as u16 & 0b1111)
+            {
+                Ok(half) => Cow::Borrowed(half.boxes()),
+                Err(_) =>
+                {
+                    debug_assert!(false, "invalid half block variant {}!", variant);
+                    Cow::Borrowed(&CUBE)
+                }
+            },
+            BlockShapes::Stairs => Cow::Owned(stair_boxes(variant & 0b1 == 1, conn.corner)),
+            BlockShapes::Cross => Cow::Borrowed(&CROSS),
+            BlockShapes::Fence => Cow::Owned(fence_boxes(conn)),
+            BlockShapes::Pane => Cow::Owned(pane_boxes(conn)),
+            BlockShapes::Layer =>
+            {
+                let height = (variant as u8 & 0b11) + 1;
+
+                Cow::Owned(vec![ShapeBox::new([0; 3], [SHAPE_PRECISION, height, SHAPE_PRECISION])])
+            }
+        }
+    }
+
+    /// get the boxes of this shape that are there no matter
+    /// how it connects to its neighbours, in the block's local
+    /// frame. only these hide the faces of neighbouring blocks
+    pub fn core_boxes(self, variant: usize) -> Cow<'static, [ShapeBox]>
+    {
+        match self
+        {
+            BlockShapes::Stairs =>
+            {
+                let mut boxes = stair_boxes(variant & 0b1 == 1, StairCorner::Straight);
+                boxes.truncate(1);
+
+                Cow::Owned(boxes)
+            }
+            BlockShapes::Cross | BlockShapes::Fence | BlockShapes::Pane => Cow::Borrowed(&[]),
+            _ => self.boxes(variant, Connections::NONE),
+        }
+    }
+
+    /// get the block state properties this shape needs,
+    /// which are declared before any of the block's own
+    pub fn properties(self) -> &'static [(&'static str, PropertyKind)]
+    {
+        match self
+        {
+            BlockShapes::None => &[],
+            BlockShapes::Cube => &[],
+            BlockShapes::Half => &[("half", PropertyKind::Half)],
+            BlockShapes::Stairs => &[("top", PropertyKind::Bool)],
+            BlockShapes::Cross => &[],
+            BlockShapes::Fence => &[],
+            BlockShapes::Pane => &[],
+            BlockShapes::Layer => &[("layers", PropertyKind::Int { max: 3 })],
+        }
+    }
+
+    /// does this shape snap to the block grid? such shapes
+    /// can be meshed using the compact chunk vertex, while
+    /// others need the fine vertex
+    pub fn is_grid_aligned(self) -> bool
+    {
+        match self
+        {
+            BlockShapes::None => true,
+            BlockShapes::Cube => true,
+            BlockShapes::Cross => true,
+            _ => false,
+        }
+    }
+
+    /// can entities collide with this shape? shapes that
+    /// can't still have boxes, for selection
+    pub fn is_solid(self) -> bool
+    {
+        match self
+        {
+            BlockShapes::None => false,
+            BlockShapes::Cross => false,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
\ No newline at end of file
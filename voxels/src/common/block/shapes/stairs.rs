@@ -0,0 +1,88 @@
+use crate::common::block::{ UnpackedBlock, BlockFace };
+
+use super::{ BlockShapes, ShapeBox, SHAPE_PRECISION };
+
+/// corner a stair forms with the stairs behind or in front
+/// of it. west and east are in the stair's local frame,
+/// where its step rises towards the north
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StairCorner
+{
+    /// a regular stair
+    Straight,
+    /// only the western half of the step remains
+    OuterWest,
+    /// only the eastern half of the step remains
+    OuterEast,
+    /// the step wraps around the south-west quarter
+    InnerWest,
+    /// the step wraps around the south-east quarter
+    InnerEast,
+}
+
+/// get the boxes that make up a stair, in its local frame.
+/// upside down(`top`) stairs are mirrored vertically
+pub fn stair_boxes(top: bool, corner: StairCorner) -> Vec<ShapeBox>
+{
+    const P: u8 = SHAPE_PRECISION;
+    const H: u8 = SHAPE_PRECISION / 2;
+
+    let mut boxes = vec![ShapeBox::new([0, 0, 0], [P, H, P])];
+
+    match corner
+    {
+        StairCorner::Straight => boxes.push(ShapeBox::new([0, H, 0], [P, P, H])),
+        StairCorner::OuterWest => boxes.push(ShapeBox::new([0, H, 0], [H, P, H])),
+        StairCorner::OuterEast => boxes.push(ShapeBox::new([H, H, 0], [P, P, H])),
+        StairCorner::InnerWest =>
+        {
+            boxes.push(ShapeBox::new([0, H, 0], [P, P, H]));
+            boxes.push(ShapeBox::new([0, H, H], [H, P, P]));
+        }
+        StairCorner::InnerEast =>
+        {
+            boxes.push(ShapeBox::new([0, H, 0], [P, P, H]));
+            boxes.push(ShapeBox::new([H, H, H], [P, P, P]));
+        }
+    }
+
+    if top
+    {
+        for b in &mut boxes
+        {
+            *b = ShapeBox::new([b.min[0], P - b.max[1], b.min[2]], [b.max[0], P - b.min[1], b.max[2]]);
+        }
+    }
+    boxes
+}
+
+/// get the corner a stair forms with the blocks behind(local
+/// north) and in front(local south) of it. a stair on the
+/// same half facing sideways behind it makes an outer corner,
+/// and one in front of it an inner corner
+pub fn stair_corner(stair: &UnpackedBlock, back: Option<&UnpackedBlock>, front: Option<&UnpackedBlock>) -> StairCorner
+{
+    let rot = stair.rotation();
+    let top = stair.get_property::<bool>("top");
+
+    // local face another stair on the same half faces
+    let facing = |other: Option<&UnpackedBlock>|
+    {
+        other
+            .filter(|o| o.shape() == BlockShapes::Stairs && o.get_property::<bool>("top") == top)
+            .map(|o| BlockFace::North.to_world(o.rotation()).to_local(rot))
+    };
+
+    match facing(back)
+    {
+        Some(BlockFace::West) => return StairCorner::OuterWest,
+        Some(BlockFace::East) => return StairCorner::OuterEast,
+        _ => {}
+    }
+    match facing(front)
+    {
+        Some(BlockFace::West) => StairCorner::InnerWest,
+        Some(BlockFace::East) => StairCorner::InnerEast,
+        _ => StairCorner::Straight,
+    }
+}
@@ -0,0 +1,165 @@
+use crate::common::block::BlockFace;
+use super::*;
+
+/// every face of a block, in order
+fn faces() -> impl Iterator<Item = BlockFace>
+{
+    (0..6usize).map(BlockFace::from)
+}
+
+/// is `own`'s face covered by `other`, touching it on that
+/// face? this is the rule chunk meshing culls faces by
+fn covered(own: &[ShapeBox], other: &[ShapeBox], face: BlockFace) -> bool
+{
+    face_mask(own, face) & !face_mask(other, face.opposite()) == 0
+}
+
+fn half(variant: HalfBlockVariants) -> &'static [ShapeBox]
+{
+    variant.boxes()
+}
+
+#[test]
+fn test_face_mask_full()
+{
+    let full = (1u32 << (SHAPE_PRECISION * SHAPE_PRECISION)) - 1;
+
+    for face in faces()
+    {
+        assert_eq!(ShapeBox::FULL.face_mask(face) as u32, full, "{:?}", face);
+        assert!(ShapeBox::FULL.touches(face));
+    }
+    assert_eq!(face_mask(&[], BlockFace::Up), 0);
+}
+
+#[test]
+fn test_face_mask_half()
+{
+    let down = half(HalfBlockVariants::Down);
+
+    // lower two rows of the sides, all of the bottom, none of the top
+    assert_eq!(face_mask(down, BlockFace::North), 0x00ff);
+    assert_eq!(face_mask(down, BlockFace::East), 0x00ff);
+    assert_eq!(face_mask(down, BlockFace::Down), 0xffff);
+    assert_eq!(face_mask(down, BlockFace::Up), 0);
+
+    // western columns of the north face
+    assert_eq!(face_mask(half(HalfBlockVariants::West), BlockFace::North), 0x3333);
+}
+
+#[test]
+fn test_half_variants()
+{
+    use HalfBlockVariants::*;
+
+    // a half touches its own side fully, and never the opposite one
+    let halves = [(North, BlockFace::North), (South, BlockFace::South), (West, BlockFace::West), (East, BlockFace::East), (Down, BlockFace::Down), (Up, BlockFace::Up)];
+    for (variant, face) in halves.iter()
+    {
+        assert!(covered(&[ShapeBox::FULL], half(*variant), face.opposite()), "{:?}", variant);
+        assert_eq!(face_mask(half(*variant), face.opposite()), 0, "{:?}", variant);
+    }
+
+    // two halves make a cube
+    for variant in [NorthSouth, WestEast, DownUp].iter()
+    {
+        assert!(faces().all(|f| covered(&[ShapeBox::FULL], half(*variant), f)), "{:?}", variant);
+    }
+}
+
+#[test]
+fn test_half_culling()
+{
+    use HalfBlockVariants::*;
+
+    let cube = &[ShapeBox::FULL][..];
+
+    // halves side by side hide each other
+    assert!(covered(half(Down), half(Down), BlockFace::East));
+    assert!(covered(half(Down), half(Down), BlockFace::West));
+
+    // but not when one sits above the other's level
+    assert!(!covered(half(Down), half(Up), BlockFace::East));
+    assert!(!covered(half(Up), half(Down), BlockFace::North));
+
+    // a cube hides a half, not the other way around
+    assert!(covered(half(South), cube, BlockFace::East));
+    assert!(!covered(cube, half(South), BlockFace::East));
+
+    // halves facing each other hide each other
+    assert!(covered(half(North), half(South), BlockFace::North));
+    assert!(covered(half(Up), half(Down), BlockFace::Up));
+
+    // a half only hides a cube with its full side
+    assert!(covered(cube, half(Down), BlockFace::Up));
+    assert!(!covered(cube, half(Up), BlockFace::Up));
+}
+
+#[test]
+fn test_stair_masks()
+{
+    let straight = stair_boxes(false, StairCorner::Straight);
+
+    // the step rises towards the north
+    assert_eq!(face_mask(&straight, BlockFace::Down), 0xffff);
+    assert_eq!(face_mask(&straight, BlockFace::Up), 0x00ff);
+    assert_eq!(face_mask(&straight, BlockFace::North), 0xffff);
+    assert_eq!(face_mask(&straight, BlockFace::South), 0x00ff);
+    assert_eq!(face_mask(&straight, BlockFace::East), 0x33ff);
+
+    // upside down stairs are mirrored
+    let top = stair_boxes(true, StairCorner::Straight);
+    assert_eq!(face_mask(&top, BlockFace::Up), 0xffff);
+    assert_eq!(face_mask(&top, BlockFace::Down), 0x00ff);
+    assert_eq!(face_mask(&top, BlockFace::South), 0xff00);
+
+    // corners keep or add a quarter of the step
+    assert_eq!(face_mask(&stair_boxes(false, StairCorner::OuterWest), BlockFace::Up), 0x0033);
+    assert_eq!(face_mask(&stair_boxes(false, StairCorner::OuterEast), BlockFace::Up), 0x00cc);
+    assert_eq!(face_mask(&stair_boxes(false, StairCorner::InnerWest), BlockFace::Up), 0x33ff);
+    assert_eq!(face_mask(&stair_boxes(false, StairCorner::InnerEast), BlockFace::Up), 0xccff);
+}
+
+#[test]
+fn test_stair_culling()
+{
+    let cube = &[ShapeBox::FULL][..];
+    let stairs = stair_boxes(false, StairCorner::Straight);
+    let core = BlockShapes::Stairs.core_boxes(0);
+
+    // cubes hide stairs on every side
+    assert!(faces().all(|f| covered(&stairs, cube, f)));
+
+    // stairs only hide with their slab, since their step
+    // depends on their neighbours
+    assert!(covered(cube, &core, BlockFace::Up));
+    assert!(!covered(cube, &core, BlockFace::South));
+    assert!(covered(half(HalfBlockVariants::Down), &core, BlockFace::East));
+    assert!(!covered(&stairs, &core, BlockFace::East));
+}
+
+#[test]
+fn test_fence_masks()
+{
+    let north = Connections { sides: [true, false, false, false], ..Connections::NONE };
+
+    // a lone post touches the block above and below only,
+    // in the middle two quarters
+    let post = fence_boxes(Connections::NONE);
+    assert_eq!(face_mask(&post, BlockFace::Up), 0x0660);
+    assert!([BlockFace::North, BlockFace::South, BlockFace::West, BlockFace::East].iter().all(|f| face_mask(&post, *f) == 0));
+    assert_eq!(post, pane_boxes(Connections::NONE));
+
+    // rails and walls reach the connected side
+    assert_eq!(face_mask(&fence_boxes(north), BlockFace::North), 0x6060);
+    assert_eq!(face_mask(&fence_boxes(north), BlockFace::South), 0);
+    assert_eq!(face_mask(&pane_boxes(north), BlockFace::North), 0x6666);
+
+    // a cube hides where they connect, but neither hides
+    // their neighbours
+    assert!(covered(&pane_boxes(north), &[ShapeBox::FULL], BlockFace::North));
+    for shape in [BlockShapes::Fence, BlockShapes::Pane, BlockShapes::Cross].iter()
+    {
+        assert!(shape.core_boxes(0).is_empty(), "{:?}", shape);
+    }
+}
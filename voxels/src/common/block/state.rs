@@ -0,0 +1,219 @@
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+
+use super::shapes::HalfBlockVariants;
+use super::{ BlockFace, StateError };
+
+/// kind of values a block property can take, which
+/// determines how many variant bits it needs
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+pub enum PropertyKind
+{
+    /// true or false, ie. open/closed. 1 bit
+    Bool,
+    /// integer within 0..=max, ie. a crop's age
+    Int { max: u8 },
+    /// one of the six block faces, ie. a furnace's front
+    Facing,
+    /// one of the three axes, ie. a log's direction
+    Axis,
+    /// one of the four horizontal faces, ie. a stair's
+    /// front. read and written as a `BlockFace`
+    Horizontal,
+    /// one of the half block variants. declared by the
+    /// half block shape itself
+    Half,
+}
+
+/// a block property's value, untyped. use the typed
+/// getters and setters of `UnpackedBlock` instead,
+/// where possible
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PropertyValue
+{
+    Bool(bool),
+    Int(u8),
+    Facing(BlockFace),
+    Axis(Axis),
+    Half(HalfBlockVariants),
+}
+
+/// a three dimensional axis
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(u8)]
+pub enum Axis
+{
+    X = 0,
+    Y = 1,
+    Z = 2,
+}
+
+/// a named property of a block type, mapped onto a
+/// range of bits of a block's variant
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BlockProperty
+{
+    /// name of this property, in snakecase
+    pub name: String,
+    /// values this property can take
+    pub kind: PropertyKind,
+
+    /// first variant bit used by this property
+    pub(super) offset: u8,
+}
+
+/// types that can be stored in a block property
+pub trait PropertyType: Sized
+{
+    /// wrap self in an untyped property value
+    fn into_value(self) -> PropertyValue;
+    /// unwrap self from an untyped property value,
+    /// if it's of the right kind
+    fn from_value(val: PropertyValue) -> Option<Self>;
+}
+
+impl PropertyKind
+{
+    /// number of variant bits needed by this kind
+    pub fn bits(self) -> u8
+    {
+        let count = self.count() as u32;
+
+        (32 - (count - 1).leading_zeros()) as u8
+    }
+
+    /// number of distinct values of this kind
+    pub fn count(self) -> usize
+    {
+        match self
+        {
+            PropertyKind::Bool => 2,
+            PropertyKind::Int { max } => max as usize + 1,
+            PropertyKind::Facing => 6,
+            PropertyKind::Axis => 3,
+            PropertyKind::Horizontal => 4,
+            PropertyKind::Half => 9,
+        }
+    }
+
+    /// get the raw bits of a value of this kind. fails if
+    /// the value isn't of this kind or out of range
+    pub fn encode(self, val: PropertyValue) -> Option<u16>
+    {
+        match (self, val)
+        {
+            (PropertyKind::Bool, PropertyValue::Bool(b)) => Some(b as u16),
+            (PropertyKind::Int { max }, PropertyValue::Int(n)) if n <= max => Some(n as u16),
+            (PropertyKind::Facing, PropertyValue::Facing(f)) => Some(f as u16),
+            (PropertyKind::Axis, PropertyValue::Axis(a)) => Some(a as u16),
+            (PropertyKind::Horizontal, PropertyValue::Facing(f)) if f.axis().0 != 1 => Some(f as u16),
+            (PropertyKind::Half, PropertyValue::Half(h)) => Some(h as u16),
+            _ => None,
+        }
+    }
+
+    /// get the value of this kind from its raw bits. fails
+    /// if the bits don't represent a valid value
+    pub fn decode(self, raw: u16) -> Option<PropertyValue>
+    {
+        if raw as usize >= self.count()
+        {
+            return None;
+        }
+
+        Some(match self
+        {
+            PropertyKind::Bool => PropertyValue::Bool(raw == 1),
+            PropertyKind::Int { .. } => PropertyValue::Int(raw as u8),
+            PropertyKind::Facing => PropertyValue::Facing(BlockFace::from(raw)),
+            PropertyKind::Axis => PropertyValue::Axis(Axis::try_from(raw).ok()?),
+            PropertyKind::Horizontal => PropertyValue::Facing(BlockFace::from(raw)),
+            PropertyKind::Half => PropertyValue::Half(HalfBlockVariants::try_from(raw).ok()?),
+        })
+    }
+}
+
+impl BlockProperty
+{
+    /// mask of the variant bits used by this property
+    fn mask(&self) -> u16
+    {
+        ((1 << self.kind.bits()) - 1) << self.offset
+    }
+
+    /// read this property from a block variant
+    pub fn get(&self, variant: u16) -> Option<PropertyValue>
+    {
+        self.kind.decode((variant & self.mask()) >> self.offset)
+    }
+
+    /// write this property into a block variant, returning
+    /// the new variant
+    pub fn set(&self, variant: u16, val: PropertyValue) -> Result<u16, StateError>
+    {
+        let raw = self.kind
+            .encode(val)
+            .ok_or_else(|| StateError::InvalidValue { name: self.name.clone(), kind: self.kind, val })?;
+
+        Ok((variant & !self.mask()) | (raw << self.offset))
+    }
+}
+
+impl TryFrom<u16> for Axis
+{
+    type Error = u16;
+
+    fn try_from(num: u16) -> Result<Self, Self::Error>
+    {
+        match num
+        {
+            0 => Ok(Self::X),
+            1 => Ok(Self::Y),
+            2 => Ok(Self::Z),
+            _ => Err(num),
+        }
+    }
+}
+
+impl PropertyType for PropertyValue
+{
+    fn into_value(self) -> PropertyValue
+    {
+        self
+    }
+
+    fn from_value(val: PropertyValue) -> Option<Self>
+    {
+        Some(val)
+    }
+}
+
+macro_rules! impl_property_type
+{
+    ($ty:ty, $variant:ident) =>
+    {
+        impl PropertyType for $ty
+        {
+            fn into_value(self) -> PropertyValue
+            {
+                PropertyValue::$variant(self)
+            }
+
+            fn from_value(val: PropertyValue) -> Option<Self>
+            {
+                match val
+                {
+                    PropertyValue::$variant(v) => Some(v),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_property_type!(bool, Bool);
+impl_property_type!(u8, Int);
+impl_property_type!(BlockFace, Facing);
+impl_property_type!(Axis, Axis);
+impl_property_type!(HalfBlockVariants, Half);
@@ -0,0 +1,602 @@
+use std::convert::TryFrom;
+use std::path::Path;
+
+use ezmath::*;
+
+use crate::common::testing::{ self, sign };
+use super::*;
+
+/// write a block definition file
+fn def(dir: &Path, file: &str, id: &str, col: (f32, f32, f32, f32))
+{
+    let src = format!("(name: \"{}\", id: \"{}\", col: {:?})", file, id, col);
+
+    std::fs::write(dir.join(format!("{}.ron", file)), src).unwrap();
+}
+
+#[test]
+fn test_palette_load()
+{
+    let dir = testing::temp_dir("palette-load");
+
+    def(&dir, "b", "stone", (0.5, 0.5, 0.5, 1.0));
+    def(&dir, "a", "dirt", (0.2, 0.1, 0.0, 1.0));
+    std::fs::write(dir.join("notes.txt"), "not a block").unwrap();
+
+    let pal = RBlockPalette::load(&dir).unwrap();
+
+    // air first, then by file name
+    assert_eq!(pal.get(0).id, "air");
+    assert_eq!(pal.get(1).id, "dirt");
+    assert_eq!(pal.get(2).id, "stone");
+    assert!(matches!(pal.get(2).shape, shapes::BlockShapes::Cube));
+}
+
+#[test]
+fn test_palette_assets()
+{
+    let pal = testing::palette();
+
+    assert_eq!(pal.get(0).id, "air");
+    assert!(!pal.get(1).name.is_empty());
+}
+
+#[test]
+fn test_palette_duplicate_id()
+{
+    let dir = testing::temp_dir("palette-duplicate");
+
+    def(&dir, "a", "stone", (0.5, 0.5, 0.5, 1.0));
+    def(&dir, "b", "stone", (0.5, 0.5, 0.5, 1.0));
+
+    match RBlockPalette::load(&dir)
+    {
+        Err(PaletteError::DuplicateId { id, first, second }) =>
+        {
+            assert_eq!(id, "stone");
+            assert_eq!(first, dir.join("a.ron"));
+            assert_eq!(second, dir.join("b.ron"));
+        }
+        other => panic!("expected a duplicate ID, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_palette_invalid()
+{
+    let cases: &[(&str, (f32, f32, f32, f32))] =
+    &[
+        ("Stone", (0.5, 0.5, 0.5, 1.0)),
+        ("red stone", (0.5, 0.5, 0.5, 1.0)),
+        ("", (0.5, 0.5, 0.5, 1.0)),
+        ("stone", (0.5, 1.5, 0.5, 1.0)),
+        ("stone", (0.5, 0.5, 0.5, -0.1)),
+    ];
+
+    for (i, (id, col)) in cases.iter().enumerate()
+    {
+        let dir = testing::temp_dir(&format!("palette-invalid-{}", i));
+        def(&dir, "a", id, *col);
+
+        match RBlockPalette::load(&dir)
+        {
+            Err(PaletteError::Invalid { path, .. }) => assert_eq!(path, dir.join("a.ron")),
+            other => panic!("expected {:?} {:?} to be invalid, got {:?}", id, col, other),
+        }
+    }
+}
+
+#[test]
+fn test_palette_parse()
+{
+    let dir = testing::temp_dir("palette-parse");
+
+    std::fs::write(dir.join("a.ron"), "(name: \"A\", id: \"a\")").unwrap();
+
+    assert!(matches!(RBlockPalette::load(&dir), Err(PaletteError::Parse { .. })));
+    assert!(matches!(RBlockPalette::load(dir.join("missing")), Err(PaletteError::Io { .. })));
+}
+
+#[test]
+fn test_palette_too_many()
+{
+    let dir = testing::temp_dir("palette-too-many");
+
+    // air counts towards the limit
+    for i in 0..PackedBlock::MAX_BLOCKS
+    {
+        def(&dir, &format!("{:04}", i), &format!("block_{}", i), (0.5, 0.5, 0.5, 1.0));
+    }
+
+    match RBlockPalette::load(&dir)
+    {
+        Err(PaletteError::TooManyBlocks { count }) => assert_eq!(count, PackedBlock::MAX_BLOCKS + 1),
+        other => panic!("expected too many blocks, got {:?}", other),
+    }
+
+    std::fs::remove_file(dir.join("0000.ron")).unwrap();
+    assert!(RBlockPalette::load(&dir).is_ok());
+}
+
+#[test]
+fn test_palette_too_many_textures()
+{
+    let dir = testing::temp_dir("palette-too-many-textures");
+
+    for face in &["n", "s", "w", "e", "d", "u"]
+    {
+        std::fs::write(dir.join(format!("{}.png", face)), "").unwrap();
+    }
+
+    // the same textures in another colour take new tiles.
+    // sixteenths convert to distinct atlas colours
+    let block = |i: usize|
+    {
+        let col = ((i % 16) as f32 / 16.0, (i / 16 % 16) as f32 / 16.0, (i / 256) as f32 / 16.0, 1.0);
+        let src = format!
+        (
+            "(name: \"{0}\", id: \"block_{0}\", col: {1:?}, textures: Faces(north: \"n.png\", south: \"s.png\", west: \"w.png\", east: \"e.png\", down: \"d.png\", up: \"u.png\"))",
+            i, col
+        );
+        std::fs::write(dir.join(format!("{:04}.ron", i)), src).unwrap();
+    };
+
+    // 682 blocks and air take 4093 tiles
+    (0..682).for_each(block);
+    assert!(RBlockPalette::load(&dir).is_ok());
+
+    block(682);
+    match RBlockPalette::load(&dir)
+    {
+        Err(PaletteError::TooManyTextures { count }) => assert_eq!(count, 683 * 6 + 1),
+        other => panic!("expected too many textures, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_entities_alloc()
+{
+    let mut ents = BlockEntities::default();
+
+    let a = ents.alloc(sign("a")).unwrap();
+    let b = ents.alloc(sign("b")).unwrap();
+
+    assert_eq!(a.format(), PackedBlockFormat::Addr);
+    assert_ne!(a.addr(), b.addr());
+    assert_eq!(ents.len(), 2);
+    assert_eq!(ents.get(b.addr()).unwrap().data::<String>().unwrap(), "b");
+
+    ents.get_mut(a.addr()).unwrap().data_mut::<String>().unwrap().push('!');
+    assert_eq!(ents.get(a.addr()).unwrap().data::<String>().unwrap(), "a!");
+    assert!(ents.get(a.addr()).unwrap().data::<u32>().is_none());
+}
+
+#[test]
+fn test_entities_free()
+{
+    let mut ents = BlockEntities::default();
+
+    let a = ents.alloc(sign("a")).unwrap();
+    let b = ents.alloc(sign("b")).unwrap();
+
+    assert_eq!(ents.free(a.addr()).unwrap().data::<String>().unwrap(), "a");
+    assert!(ents.get(a.addr()).is_none());
+    assert!(ents.free(a.addr()).is_none());
+    assert_eq!(ents.len(), 1);
+
+    // freed slots are reused before growing
+    let c = ents.alloc(sign("c")).unwrap();
+
+    assert_eq!(c.addr(), a.addr());
+    assert_eq!(ents.iter().map(|(addr, _)| addr).collect::<Vec<_>>(), vec![a.addr(), b.addr()]);
+}
+
+#[test]
+fn test_entities_full()
+{
+    let mut ents = BlockEntities::default();
+
+    for _ in 0..BlockEntities::CAPACITY
+    {
+        ents.alloc(sign("")).unwrap();
+    }
+    let ent = ents.alloc(sign("full")).unwrap_err();
+
+    assert_eq!(ent.data::<String>().unwrap(), "full");
+    assert_eq!(ents.len(), BlockEntities::CAPACITY);
+
+    ents.free(7);
+    assert_eq!(ents.alloc(sign("")).unwrap().addr(), 7);
+}
+
+/// palette of blocks with one of each property kind, as
+/// (text ID, shape, properties)
+fn stateful() -> RBlockPalette
+{
+    let dir = testing::temp_dir("palette-state");
+    let blocks =
+    [
+        ("lever", "Cube", "(name: \"open\", kind: Bool), (name: \"age\", kind: Int(max: 4))"),
+        ("furnace", "Cube", "(name: \"facing\", kind: Facing), (name: \"lit\", kind: Bool)"),
+        ("log", "Cube", "(name: \"axis\", kind: Axis)"),
+        ("slab", "Half", ""),
+    ];
+
+    for (id, shape, props) in blocks.iter()
+    {
+        let src = format!("(name: \"{}\", id: \"{}\", col: (1.0, 1.0, 1.0, 1.0), shape: {}, properties: [{}])", id, id, shape, props);
+
+        std::fs::write(dir.join(format!("{}.ron", id)), src).unwrap();
+    }
+    RBlockPalette::load(&dir).unwrap()
+}
+
+/// unpack a block of the given text ID and variant, then
+/// give it to `f`
+fn with_block<T>(pal: &RBlockPalette, id: &str, variant: u16, f: impl FnOnce(UnpackedBlock) -> T) -> T
+{
+    let id = (1..).find(|i| pal.get(*i).id == id).unwrap();
+    let ents = BlockEntities::default();
+
+    f(UnpackedBlock::new(PackedBlock::from_data(id as u16, variant), int3::zeros(), pal, &ents))
+}
+
+/// set a property, then read it back from the packed block
+fn round_trip<T: PropertyType + Copy>(pal: &RBlockPalette, id: &str, variant: u16, name: &str, val: T) -> (Option<T>, u16)
+{
+    let packed = with_block(pal, id, variant, |b| b.with_property(name, val).unwrap());
+
+    with_block(pal, id, packed.variant(), |b| (b.get_property(name), packed.variant()))
+}
+
+#[test]
+fn test_property_round_trip()
+{
+    let pal = stateful();
+
+    for open in [false, true].iter()
+    {
+        assert_eq!(round_trip(&pal, "lever", 0, "open", *open).0, Some(*open));
+    }
+    for age in 0..=4u8
+    {
+        // neighbouring properties keep their bits
+        let (got, variant) = round_trip(&pal, "lever", 1, "age", age);
+
+        assert_eq!(got, Some(age));
+        assert_eq!(with_block(&pal, "lever", variant, |b| b.get_property::<bool>("open")), Some(true));
+    }
+    for f in 0..6usize
+    {
+        let (got, variant) = round_trip(&pal, "furnace", 0b1000, "facing", BlockFace::from(f));
+
+        assert_eq!(got, Some(BlockFace::from(f)));
+        assert_eq!(with_block(&pal, "furnace", variant, |b| b.get_property::<bool>("lit")), Some(true));
+    }
+    for axis in [Axis::X, Axis::Y, Axis::Z].iter()
+    {
+        assert_eq!(round_trip(&pal, "log", 0, "axis", *axis).0, Some(*axis));
+    }
+    for h in 0..9u16
+    {
+        let half = shapes::HalfBlockVariants::try_from(h).unwrap();
+
+        assert_eq!(round_trip(&pal, "slab", 0, "half", half), (Some(half), h));
+    }
+}
+
+#[test]
+fn test_property_errors()
+{
+    let pal = stateful();
+
+    with_block(&pal, "lever", 0, |b|
+    {
+        assert!(matches!(b.with_property("age", 5u8), Err(StateError::InvalidValue { .. })));
+        assert!(matches!(b.with_property("open", 1u8), Err(StateError::InvalidValue { .. })));
+        assert!(matches!(b.with_property("lit", true), Err(StateError::UnknownProperty { .. })));
+
+        assert_eq!(b.get_property::<u8>("open"), None);
+        assert_eq!(b.get_property::<bool>("lit"), None);
+    });
+
+    // bits that aren't a value of the property's kind
+    assert_eq!(with_block(&pal, "lever", 0b1011, |b| b.get_property::<u8>("age")), None);
+    assert_eq!(with_block(&pal, "furnace", 0b0111, |b| b.get_property::<BlockFace>("facing")), None);
+    assert_eq!(with_block(&pal, "log", 0b0011, |b| b.get_property::<Axis>("axis")), None);
+    assert_eq!(with_block(&pal, "slab", 9, |b| b.get_property::<shapes::HalfBlockVariants>("half")), None);
+}
+
+#[test]
+fn test_half_variants_out_of_range()
+{
+    for h in 9..16u16
+    {
+        assert_eq!(shapes::HalfBlockVariants::try_from(h), Err(h));
+        assert_eq!(PropertyKind::Half.decode(h), None);
+    }
+    assert_eq!(PropertyKind::Half.bits(), 4);
+}
+
+/// invalid half block variants still have a shape in release
+/// builds, but are caught in debug ones
+#[test]
+#[cfg_attr(debug_assertions, should_panic)]
+fn test_half_boxes_out_of_range()
+{
+    assert_eq!(&*shapes::BlockShapes::Half.boxes(9, shapes::Connections::NONE), &[shapes::ShapeBox::FULL]);
+}
+
+#[test]
+fn test_remap_reordered()
+{
+    let (old_dir, new_dir) = (testing::temp_dir("remap-old"), testing::temp_dir("remap-new"));
+
+    def(&old_dir, "a", "dirt", (0.2, 0.1, 0.0, 1.0));
+    def(&old_dir, "b", "grass", (0.0, 1.0, 0.0, 1.0));
+    def(&old_dir, "c", "stone", (0.5, 0.5, 0.5, 1.0));
+
+    // same blocks, another order, and one more
+    def(&new_dir, "a", "stone", (0.5, 0.5, 0.5, 1.0));
+    def(&new_dir, "b", "sand", (1.0, 1.0, 0.5, 1.0));
+    def(&new_dir, "c", "dirt", (0.2, 0.1, 0.0, 1.0));
+    def(&new_dir, "d", "grass", (0.0, 1.0, 0.0, 1.0));
+
+    let old = RBlockPalette::load(&old_dir).unwrap();
+    let new = RBlockPalette::load(&new_dir).unwrap();
+
+    let table = old.name_table();
+    let remap = new.remap(&table);
+
+    assert_eq!(table.0, vec!["air", "dirt", "grass", "stone"]);
+    for name in &["air", "dirt", "grass", "stone"]
+    {
+        assert_eq!(remap.id(old.id(name).unwrap()), new.id(name).unwrap(), "{}", name);
+    }
+    assert!(remap.missing().is_empty());
+    assert!(!remap.is_identity());
+    assert!(old.remap(&table).is_identity());
+
+    // variants are kept, address blocks are left to their entity
+    let grass = PackedBlock::from_data(old.id("grass").unwrap(), 5);
+    assert_eq!(remap.apply(grass), PackedBlock::from_data(new.id("grass").unwrap(), 5));
+
+    let mut ents = BlockEntities::default();
+    let addr = ents.alloc(sign("a")).unwrap();
+    assert_eq!(remap.apply(addr), addr);
+}
+
+#[test]
+fn test_remap_missing()
+{
+    let pal = testing::palette();
+    let table = BlockNameTable(vec!["air".into(), "marble".into(), "dirt".into()]);
+
+    let remap = pal.remap(&table);
+
+    assert_eq!(remap.missing(), &["marble".to_string()]);
+    assert!(!remap.is_identity());
+
+    // removed blocks and IDs past the old palette become
+    // plain air, without their old variant bits
+    assert_eq!(remap.apply(PackedBlock::from_data(1, 3)), PackedBlock::default());
+    assert_eq!(remap.apply(PackedBlock::from_data(9, 3)), PackedBlock::default());
+    assert_eq!(remap.apply(PackedBlock::from_data(2, 3)), PackedBlock::from_data(pal.id("dirt").unwrap(), 3));
+}
+
+/// every rotation of a cube
+fn rotations() -> Vec<Rotation>
+{
+    let faces = (0..6usize).map(BlockFace::from).collect::<Vec<_>>();
+
+    faces
+        .iter()
+        .flat_map(|n| faces.iter().filter_map(move |u| Rotation::new(*n, *u)))
+        .collect()
+}
+
+#[test]
+fn test_rotation_invert()
+{
+    let rots = rotations();
+    assert_eq!(rots.len(), 24);
+
+    let v = int3::new(3, -5, 7);
+    for rot in rots
+    {
+        assert_eq!(rot.invert(rot.apply(v)), v, "{:?}", rot);
+        assert_eq!(rot.apply(rot.invert(v)), v, "{:?}", rot);
+
+        for f in 0..6usize
+        {
+            let face = BlockFace::from(f);
+
+            assert_eq!(face.to_world(rot).to_local(rot), face, "{:?}", rot);
+            assert_eq!(face.to_world(rot).opposite(), face.opposite().to_world(rot), "{:?}", rot);
+        }
+
+        // never mirrored
+        let (x, y, z) = (BlockFace::East.to_world(rot), BlockFace::Up.to_world(rot), BlockFace::South.to_world(rot));
+        assert_eq!(x.normal(), y.normal().cross(&z.normal()), "{:?}", rot);
+    }
+}
+
+#[test]
+fn test_rotation_faces()
+{
+    for f in 0..6usize
+    {
+        let face = BlockFace::from(f);
+        let rot = Rotation::new(BlockFace::from(f), if face.axis().0 == 1 { BlockFace::North } else { BlockFace::Up }).unwrap();
+
+        assert_eq!(BlockFace::North.to_world(rot), face);
+        assert_eq!(BlockFace::from(f).to_local(rot), BlockFace::North);
+
+        // facing points the local north face there, upright
+        // unless it's tipped up or down
+        let facing = Rotation::facing(face);
+        assert_eq!(BlockFace::North.to_world(facing), face);
+        match face
+        {
+            BlockFace::Up => assert_eq!(BlockFace::Up.to_world(facing), BlockFace::South),
+            BlockFace::Down => assert_eq!(BlockFace::Up.to_world(facing), BlockFace::North),
+            _ => assert_eq!(BlockFace::Up.to_world(facing), BlockFace::Up),
+        }
+    }
+
+    // axis points the local up face along it
+    assert_eq!(BlockFace::Up.to_world(Rotation::axis(Axis::X)), BlockFace::East);
+    assert_eq!(BlockFace::Up.to_world(Rotation::axis(Axis::Y)), BlockFace::Up);
+    assert_eq!(BlockFace::Up.to_world(Rotation::axis(Axis::Z)), BlockFace::South);
+    assert!(Rotation::axis(Axis::Y).is_identity());
+
+    assert!(Rotation::new(BlockFace::North, BlockFace::South).is_none());
+}
+
+#[test]
+fn test_rotation_modes()
+{
+    let dir = testing::temp_dir("palette-rotation");
+    let blocks = [("log", "Axis"), ("furnace", "Facing"), ("stairs", "Horizontal"), ("stone", "None")];
+
+    for (id, mode) in blocks.iter()
+    {
+        let src = format!("(name: \"{}\", id: \"{}\", col: (1.0, 1.0, 1.0, 1.0), rotation: {})", id, id, mode);
+
+        std::fs::write(dir.join(format!("{}.ron", id)), src).unwrap();
+    }
+    let pal = RBlockPalette::load(&dir).unwrap();
+
+    // local up of a log follows its axis
+    for axis in [Axis::X, Axis::Y, Axis::Z].iter()
+    {
+        let packed = with_block(&pal, "log", 0, |b| b.with_property("axis", *axis).unwrap());
+        let rot = with_block(&pal, "log", packed.variant(), |b| b.rotation());
+
+        assert_eq!(rot, Rotation::axis(*axis));
+    }
+
+    // local north of a furnace follows its facing, stairs only
+    // face sideways
+    for f in 0..6usize
+    {
+        let face = BlockFace::from(f);
+
+        let packed = with_block(&pal, "furnace", 0, |b| b.with_property("facing", face).unwrap());
+        let rot = with_block(&pal, "furnace", packed.variant(), |b| b.rotation());
+        assert_eq!(BlockFace::North.to_world(rot), face);
+
+        let stairs = with_block(&pal, "stairs", 0, |b| b.with_property("facing", face));
+        match stairs
+        {
+            Ok(packed) =>
+            {
+                assert_ne!(face.axis().0, 1);
+
+                let rot = with_block(&pal, "stairs", packed.variant(), |b| b.rotation());
+                assert_eq!(BlockFace::North.to_world(rot), face);
+                assert_eq!(BlockFace::Up.to_world(rot), BlockFace::Up);
+            }
+            Err(_) => assert_eq!(face.axis().0, 1),
+        }
+    }
+
+    assert!(with_block(&pal, "stone", 0, |b| b.rotation()).is_identity());
+    assert_eq!(RotationMode::None.property(), None);
+    assert_eq!(RotationMode::Horizontal.property(), Some(("facing", PropertyKind::Horizontal)));
+}
+
+#[test]
+fn test_connections()
+{
+    let dir = testing::temp_dir("palette-connect");
+    let blocks =
+    [
+        ("fence", "Fence", "None"),
+        ("pane", "Pane", "None"),
+        ("stairs", "Stairs", "Horizontal"),
+        ("stone", "Cube", "None"),
+        ("tuft", "Cross", "None"),
+    ];
+
+    for (id, shape, rotation) in blocks.iter()
+    {
+        let src = format!("(name: \"{}\", id: \"{}\", col: (1.0, 1.0, 1.0, 1.0), shape: {}, rotation: {})", id, id, shape, rotation);
+
+        std::fs::write(dir.join(format!("{}.ron", id)), src).unwrap();
+    }
+    let pal = RBlockPalette::load(&dir).unwrap();
+    let ents = BlockEntities::default();
+
+    let block = |id: &str| pal.block(id).unwrap();
+    let unpack = |packed: PackedBlock| UnpackedBlock::new(packed, int3::zeros(), &pal, &ents);
+    let stairs = |facing: BlockFace, top: bool|
+    {
+        let packed = unpack(block("stairs")).with_property("facing", facing).unwrap();
+
+        unpack(packed).with_property("top", top).unwrap()
+    };
+
+    // fences and panes connect to their own kind and to cubes,
+    // not to other shapes or missing neighbours
+    let neighbors = [Some(unpack(block("fence"))), Some(unpack(block("stone"))), Some(unpack(block("pane"))), None, Some(unpack(block("fence"))), None];
+    assert_eq!(unpack(block("fence")).connections(&neighbors).sides, [true, true, false, false]);
+    assert_eq!(unpack(block("pane")).connections(&neighbors).sides, [false, true, true, false]);
+
+    let neighbors = [Some(unpack(block("tuft"))), Some(unpack(block("stairs"))), None, None, None, None];
+    assert_eq!(unpack(block("fence")).connections(&neighbors).sides, [false; 4]);
+
+    // stairs turn towards the stairs behind and in front of them
+    let corner = |back: PackedBlock, front: PackedBlock|
+    {
+        let neighbors = [Some(unpack(back)), Some(unpack(front)), None, None, None, None];
+
+        unpack(stairs(BlockFace::North, false)).connections(&neighbors).corner
+    };
+    let stone = block("stone");
+
+    assert_eq!(corner(stone, stone), shapes::StairCorner::Straight);
+    assert_eq!(corner(stairs(BlockFace::West, false), stone), shapes::StairCorner::OuterWest);
+    assert_eq!(corner(stairs(BlockFace::East, false), stone), shapes::StairCorner::OuterEast);
+    assert_eq!(corner(stone, stairs(BlockFace::West, false)), shapes::StairCorner::InnerWest);
+    assert_eq!(corner(stone, stairs(BlockFace::East, false)), shapes::StairCorner::InnerEast);
+
+    // but only on the same half
+    assert_eq!(corner(stairs(BlockFace::West, true), stone), shapes::StairCorner::Straight);
+}
+
+#[test]
+fn test_opacity_hides()
+{
+    let dir = testing::temp_dir("palette-opacity");
+    let blocks = [("stone", ""), ("leaves", "opacity: Cutout,"), ("glass", "opacity: Translucent,"), ("water", "opacity: Translucent,")];
+
+    for (id, opacity) in blocks.iter()
+    {
+        let src = format!("(name: \"{}\", id: \"{}\", col: (1.0, 1.0, 1.0, 1.0), {})", id, id, opacity);
+
+        std::fs::write(dir.join(format!("{}.ron", id)), src).unwrap();
+    }
+    let pal = RBlockPalette::load(&dir).unwrap();
+    let ents = BlockEntities::default();
+
+    let unpack = |id: &str| UnpackedBlock::new(pal.block(id).unwrap(), int3::zeros(), &pal, &ents);
+    let (stone, leaves, glass, water) = (unpack("stone"), unpack("leaves"), unpack("glass"), unpack("water"));
+
+    // blocks are opaque unless they say otherwise
+    assert_eq!(stone.opacity(), Opacity::Opaque);
+    assert_eq!(leaves.opacity(), Opacity::Cutout);
+    assert!(!glass.opacity().is_opaque());
+
+    // opaque blocks hide everything
+    assert!([&stone, &leaves, &glass, &water].iter().all(|b| stone.hides(b)));
+
+    // see-through blocks only hide their own kind
+    assert!(leaves.hides(&leaves));
+    assert!(glass.hides(&glass));
+    assert!(!leaves.hides(&stone));
+    assert!(!leaves.hides(&glass));
+    assert!(!glass.hides(&leaves));
+    assert!(!glass.hides(&water));
+    assert!(!water.hides(&glass));
+}
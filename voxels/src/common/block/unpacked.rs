@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use ezmath::*;
 
 use crate::common::block::*;
@@ -14,6 +16,7 @@ pub struct UnpackedBlock<'a>
     pos:        int3,
 
     pal: &'a    RBlockPalette,
+    ents: &'a   BlockEntities,
 }
 
 #[allow(dead_code)]
@@ -21,9 +24,9 @@ impl<'a> UnpackedBlock<'a>
 {
     // create a new unpacked block. this should not
     /// be called directly
-    pub fn new(packed: PackedBlock, pos: int3, pal: &'a RBlockPalette) -> Self
+    pub fn new(packed: PackedBlock, pos: int3, pal: &'a RBlockPalette, ents: &'a BlockEntities) -> Self
     {
-        Self { packed, pos, pal }
+        Self { packed, pos, pal, ents }
     }
 
     /// returns the packed version of self
@@ -36,12 +39,7 @@ impl<'a> UnpackedBlock<'a>
     /// range is within 0..2048
     pub fn id(&self) -> usize
     {
-        (match self.packed.format()
-        {
-            PackedBlockFormat::Data => self.packed.id(),
-            PackedBlockFormat::Addr => todo!()
-        })
-        as usize
+        self.resolve().id() as usize
     }
 
     /// get the variant ID of this block
@@ -49,12 +47,51 @@ impl<'a> UnpackedBlock<'a>
     /// orientation, texture changes, etc.
     pub fn variant(&self) -> usize
     {
-        (match self.packed.format()
+        self.resolve().variant() as usize
+    }
+
+    /// get this block as a data format block. address format
+    /// blocks whose block entity was freed read as air, and
+    /// trip an assertion in debug builds
+    fn resolve(&self) -> PackedBlock
+    {
+        match self.packed.format()
+        {
+            PackedBlockFormat::Data => self.packed,
+            PackedBlockFormat::Addr => self
+                .entity()
+                .map_or(PackedBlock::default(), |e| PackedBlock::from_data(e.id, e.variant)),
+        }
+    }
+
+    /// get the block entity this block points to, if it's
+    /// of the address format
+    pub fn entity(&self) -> Option<&'a BlockEntity>
+    {
+        match self.packed.format()
         {
-            PackedBlockFormat::Data => self.packed.variant(),
-            PackedBlockFormat::Addr => todo!()
-        })
-        as usize
+            PackedBlockFormat::Data => None,
+            PackedBlockFormat::Addr =>
+            {
+                let ent = self.ents.get(self.packed.addr());
+
+                debug_assert!
+                (
+                    ent.is_some(),
+                    "address format block at {:?} points to a freed block entity({})!",
+                    self.pos,
+                    self.packed.addr()
+                );
+                ent
+            }
+        }
+    }
+
+    /// get this block's block entity payload, if it has
+    /// one and it's of type T
+    pub fn data<T: BlockEntityData>(&self) -> Option<&'a T>
+    {
+        self.entity()?.data()
     }
 
     /// get the textual representation of this block
@@ -89,4 +126,168 @@ impl<'a> UnpackedBlock<'a>
     {
         self.pal.get(self.id()).shape
     }
+
+    /// get how much can be seen through this block
+    pub fn opacity(&self) -> Opacity
+    {
+        self.pal.get(self.id()).opacity
+    }
+
+    /// can this block hide the faces of the other block that
+    /// touches it? see-through blocks only hide their own
+    /// kind, ie. glass next to glass
+    pub fn hides(&self, other: &UnpackedBlock) -> bool
+    {
+        self.opacity().is_opaque() || self.id() == other.id()
+    }
+
+    /// get the block light level(0..16) this block emits
+    pub fn emission(&self) -> u8
+    {
+        self.pal.get(self.id()).emission
+    }
+
+    /// does light pass through this block? only opaque,
+    /// full cubes block it
+    pub fn transmits_light(&self) -> bool
+    {
+        !(self.opacity().is_opaque() && self.shape() == shapes::BlockShapes::Cube)
+    }
+
+    /// get this block's rotation, read from the state property
+    /// its block type's rotation mode stores it in
+    pub fn rotation(&self) -> Rotation
+    {
+        match self.pal.get(self.id()).rotation
+        {
+            RotationMode::None => Rotation::IDENTITY,
+            RotationMode::Axis => self
+                .get_property("axis")
+                .map_or(Rotation::IDENTITY, Rotation::axis),
+            RotationMode::Facing | RotationMode::Horizontal => self
+                .get_property("facing")
+                .map_or(Rotation::IDENTITY, Rotation::facing),
+        }
+    }
+
+    /// get the boxes that make up this block's shape, rotated
+    /// along with the block, as if it had no neighbours. see
+    /// `UnpackedBlock::boxes_in`
+    pub fn boxes(&self) -> Cow<'static, [shapes::ShapeBox]>
+    {
+        self.rotate(self.shape().boxes(self.variant(), shapes::Connections::NONE))
+    }
+
+    /// get the boxes that make up this block's shape, rotated
+    /// along with the block and connected to its neighbours
+    pub fn boxes_in(&self, neighbors: &shapes::Neighbors) -> Cow<'static, [shapes::ShapeBox]>
+    {
+        self.rotate(self.shape().boxes(self.variant(), self.connections(neighbors)))
+    }
+
+    /// get the boxes of this block's shape that don't depend
+    /// on its neighbours, rotated along with the block. these
+    /// are what hide the faces of neighbouring blocks
+    pub fn core_boxes(&self) -> Cow<'static, [shapes::ShapeBox]>
+    {
+        self.rotate(self.shape().core_boxes(self.variant()))
+    }
+
+    /// get the boxes entities collide with, connected to this
+    /// block's neighbours
+    pub fn collision_boxes(&self, neighbors: &shapes::Neighbors) -> Cow<'static, [shapes::ShapeBox]>
+    {
+        if self.shape().is_solid()
+        {
+            self.boxes_in(neighbors)
+        }
+        else
+        {
+            Cow::Borrowed(&[])
+        }
+    }
+
+    /// get how this block connects to the blocks around it,
+    /// in its local frame, given its neighbours
+    pub fn connections(&self, neighbors: &shapes::Neighbors) -> shapes::Connections
+    {
+        use shapes::BlockShapes;
+
+        let rot = self.rotation();
+        let shape = self.shape();
+        let neighbor = |local: BlockFace| neighbors[local.to_world(rot) as usize].as_ref();
+
+        let mut conn = shapes::Connections::NONE;
+        match shape
+        {
+            BlockShapes::Fence | BlockShapes::Pane =>
+            {
+                for (i, side) in conn.sides.iter_mut().enumerate()
+                {
+                    *side = neighbor(BlockFace::from(i))
+                        .map_or(false, |n| n.shape() == shape || n.shape() == BlockShapes::Cube);
+                }
+            }
+            BlockShapes::Stairs =>
+            {
+                conn.corner = shapes::stair_corner(self, neighbor(BlockFace::North), neighbor(BlockFace::South));
+            }
+            _ => {}
+        }
+        conn
+    }
+
+    /// rotate a shape's boxes along with this block
+    fn rotate(&self, boxes: Cow<'static, [shapes::ShapeBox]>) -> Cow<'static, [shapes::ShapeBox]>
+    {
+        let rot = self.rotation();
+
+        if rot.is_identity()
+        {
+            boxes
+        }
+        else
+        {
+            Cow::Owned(boxes.iter().map(|b| b.rotated(rot)).collect())
+        }
+    }
+
+    /// get the value of one of this block's state properties.
+    /// returns None if the block doesn't have that property,
+    /// it isn't of type T, or the variant bits are invalid
+    /// ```rust
+    /// let open: bool = door.get_property("open").unwrap();
+    /// let age: u8 = wheat.get_property("age").unwrap();
+    /// ```
+    pub fn get_property<T: PropertyType>(&self, name: &str) -> Option<T>
+    {
+        self.pal
+            .get(self.id())
+            .property(name)?
+            .get(self.variant() as u16)
+            .and_then(T::from_value)
+    }
+
+    /// get the packed version of this block, with one of
+    /// its state properties changed. the block itself isn't
+    /// modified
+    /// ```rust
+    /// let opened = door.with_property("open", true)?;
+    /// blocks.set_packed(door.r_pos(), opened);
+    /// ```
+    pub fn with_property<T: PropertyType>(&self, name: &str, val: T) -> Result<PackedBlock, StateError>
+    {
+        if self.packed.format() == PackedBlockFormat::Addr
+        {
+            return Err(StateError::AddressFormat);
+        }
+
+        let variant = self.pal
+            .get(self.id())
+            .property(name)
+            .ok_or_else(|| StateError::UnknownProperty { name: name.into() })?
+            .set(self.variant() as u16, val.into_value())?;
+
+        Ok(PackedBlock::from_data(self.id() as u16, variant))
+    }
 }
\ No newline at end of file
@@ -1,15 +1,20 @@
 use ezmath::*;
 
-use crate::common::block::{ PackedBlock, UnpackedBlock, RBlockPalette };
-use crate::common::{ CHUNK_SIZE, CHUNK_LAYER, CHUNK_VOLUME };
+use crate::common::block::{ PackedBlock, PackedBlockFormat, UnpackedBlock, RBlockPalette, BlockEntity, BlockEntities, BlockRemap };
+use super::storage::BlockStorage;
+use super::ChunkPos;
 
 /// component that stores a buffer of blocks,
 /// typically associated with a CChunk component.
+/// blocks are compressed, see `BlockStorage`
 #[derive(Clone)]
 pub struct CBlockBuffer
 {
-    /// raw blocks storage
-    blocks: Box<[PackedBlock; CHUNK_VOLUME]>,
+    /// blocks storage
+    blocks: BlockStorage,
+    /// big-block data, pointed to by address
+    /// format blocks
+    entities: BlockEntities,
 }
 
 /// trait that provides overridable methods for
@@ -23,11 +28,32 @@ pub trait BlockBufferIndex<T>
     /// get an unpacked block given a relative position
     fn get<'a>(&'a self, pos: T, pal: &'a RBlockPalette) -> UnpackedBlock;
 
+    /// get a packed block given a relative position, in
+    /// the data format. address format blocks are read as
+    /// their block entity's type
+    fn get_data(&self, pos: T) -> PackedBlock;
+
     /// set a packed given a relative position, giving
     /// direct access to the low-level chunk storage.
     /// this should only be used if you know what you're
-    /// doing
+    /// doing. overwriting an address format block frees
+    /// its block entity, and writing one copies the block
+    /// entity it points to in this chunk, so no two blocks
+    /// share one
     fn set_packed(&mut self, pos: T, val: PackedBlock);
+
+    /// place a block entity at a relative position, which
+    /// allocates it in this chunk's block entities. the
+    /// block entity is given back if the chunk is full
+    fn set_entity(&mut self, pos: T, ent: BlockEntity) -> Result<(), BlockEntity>;
+
+    /// get the block entity at a relative position, if
+    /// the block there is of the address format
+    fn get_entity(&self, pos: T) -> Option<&BlockEntity>;
+
+    /// get the block entity at a relative position mutably,
+    /// if the block there is of the address format
+    fn get_entity_mut(&mut self, pos: T) -> Option<&mut BlockEntity>;
 }
 
 impl CBlockBuffer
@@ -37,9 +63,136 @@ impl CBlockBuffer
     {
         Self
         {
-            blocks: Box::new([PackedBlock::default(); CHUNK_VOLUME])
+            blocks: BlockStorage::Uniform(PackedBlock::default()),
+            entities: BlockEntities::default(),
         }
     }
+
+    /// get this chunk's block entities table
+    pub fn entities(&self) -> &BlockEntities
+    {
+        &self.entities
+    }
+
+    /// get the block every block of this buffer is, if
+    /// they're all the same. this is cheap to check
+    pub fn uniform(&self) -> Option<PackedBlock>
+    {
+        match self.blocks
+        {
+            BlockStorage::Uniform(block) => Some(block),
+            _ => None,
+        }
+    }
+
+    /// remap every block in this buffer, including block
+    /// entities, from an old palette to the current one
+    pub fn remap(&mut self, remap: &BlockRemap)
+    {
+        if remap.is_identity()
+        {
+            return;
+        }
+
+        self.blocks.map(|block| remap.apply(block));
+        for (_, ent) in self.entities.iter_mut()
+        {
+            ent.id = remap.id(ent.id);
+        }
+    }
+
+    /// flatten a relative position into an index, see
+    /// `ChunkPos::index`
+    fn index(x: usize, y: usize, z: usize) -> usize
+    {
+        ChunkPos::index(int3::new(x as i32, y as i32, z as i32))
+    }
+
+    /// get the packed block at a flat index, in the
+    /// data format
+    fn data_at(&self, i: usize) -> PackedBlock
+    {
+        let block = self.blocks.get(i);
+
+        match block.format()
+        {
+            PackedBlockFormat::Data => block,
+            PackedBlockFormat::Addr => self
+                .entity_at(i)
+                .map_or(PackedBlock::default(), |e| PackedBlock::from_data(e.id, e.variant)),
+        }
+    }
+
+    /// set the packed block at a flat index. address format
+    /// blocks other than the one already there are copies,
+    /// so their block entity is copied too
+    fn set_packed_at(&mut self, i: usize, val: PackedBlock)
+    {
+        let val = if val == self.blocks.get(i) || val.format() == PackedBlockFormat::Data
+        {
+            val
+        }
+        else
+        {
+            let ent = self.entities.get(val.addr()).cloned();
+            debug_assert!(ent.is_some(), "writing an address format block that points to a freed block entity({})!", val.addr());
+
+            match ent.map(|ent| self.entities.alloc(ent))
+            {
+                Some(Ok(val)) => val,
+                // chunk is full, keep the block's type
+                Some(Err(ent)) => PackedBlock::from_data(ent.id, ent.variant),
+                None => PackedBlock::default(),
+            }
+        };
+        self.write_at(i, val);
+    }
+
+    /// write the packed block at a flat index as is, freeing
+    /// the block entity it overwrites, if any
+    fn write_at(&mut self, i: usize, val: PackedBlock)
+    {
+        let old = self.blocks.set(i, val);
+
+        if old != val && old.format() == PackedBlockFormat::Addr
+        {
+            self.entities.free(old.addr());
+        }
+    }
+
+    /// get the block entity at a flat index
+    fn entity_at(&self, i: usize) -> Option<&BlockEntity>
+    {
+        let block = self.blocks.get(i);
+
+        match block.format()
+        {
+            PackedBlockFormat::Data => None,
+            PackedBlockFormat::Addr => self.entities.get(block.addr()),
+        }
+    }
+
+    /// get the block entity at a flat index mutably
+    fn entity_at_mut(&mut self, i: usize) -> Option<&mut BlockEntity>
+    {
+        let block = self.blocks.get(i);
+
+        match block.format()
+        {
+            PackedBlockFormat::Data => None,
+            PackedBlockFormat::Addr => self.entities.get_mut(block.addr()),
+        }
+    }
+
+    /// allocate a block entity and place it at a flat index
+    fn set_entity_at(&mut self, i: usize, ent: BlockEntity) -> Result<(), BlockEntity>
+    {
+        let packed = self.entities.alloc(ent)?;
+
+        self.write_at(i, packed);
+
+        Ok(())
+    }
 }
 
 macro_rules! impl_index
@@ -50,12 +203,7 @@ macro_rules! impl_index
         {
             fn get_packed(&self, pos: $index_ty) -> PackedBlock
             {
-                self.blocks
-                [
-                    (pos.$x as usize              ) +
-                    (pos.$y as usize * CHUNK_SIZE ) +
-                    (pos.$z as usize * CHUNK_LAYER)
-                ]
+                self.blocks.get(Self::index(pos.$x as usize, pos.$y as usize, pos.$z as usize))
             }
 
             fn get<'a>(&'a self, pos: $index_ty, pal: &'a RBlockPalette) -> UnpackedBlock
@@ -67,18 +215,35 @@ macro_rules! impl_index
                     // local pos
                     int3::new(pos.$x as i32, pos.$y as i32, pos.$z as i32),
                     // palette
-                    pal
+                    pal,
+                    // block entities
+                    &self.entities
                 )
             }
 
+            fn get_data(&self, pos: $index_ty) -> PackedBlock
+            {
+                self.data_at(Self::index(pos.$x as usize, pos.$y as usize, pos.$z as usize))
+            }
+
             fn set_packed(&mut self, pos: $index_ty, val: PackedBlock)
             {
-                self.blocks
-                [
-                    (pos.$x as usize              ) +
-                    (pos.$y as usize * CHUNK_SIZE ) +
-                    (pos.$z as usize * CHUNK_LAYER)
-                ] = val;
+                self.set_packed_at(Self::index(pos.$x as usize, pos.$y as usize, pos.$z as usize), val);
+            }
+
+            fn set_entity(&mut self, pos: $index_ty, ent: BlockEntity) -> Result<(), BlockEntity>
+            {
+                self.set_entity_at(Self::index(pos.$x as usize, pos.$y as usize, pos.$z as usize), ent)
+            }
+
+            fn get_entity(&self, pos: $index_ty) -> Option<&BlockEntity>
+            {
+                self.entity_at(Self::index(pos.$x as usize, pos.$y as usize, pos.$z as usize))
+            }
+
+            fn get_entity_mut(&mut self, pos: $index_ty) -> Option<&mut BlockEntity>
+            {
+                self.entity_at_mut(Self::index(pos.$x as usize, pos.$y as usize, pos.$z as usize))
             }
         }
     };
@@ -0,0 +1,80 @@
+use ezmath::*;
+
+use crate::common::CHUNK_VOLUME;
+use super::ChunkPos;
+
+/// component that stores the light level of every block
+/// in a chunk, next to its CBlockBuffer. each block has
+/// 4 bits of sky light and 4 bits of block light, packed
+/// in a byte as `sky << 4 | block`
+#[derive(Clone)]
+pub struct CLightBuffer
+{
+    light: Box<[u8; CHUNK_VOLUME]>,
+}
+
+/// one of the two kinds of light, which spread separately
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum LightChannel
+{
+    /// light coming from the sky, which travels straight
+    /// down without dimming
+    Sky,
+    /// light emitted by blocks, ie. torches
+    Block,
+}
+
+impl CLightBuffer
+{
+    /// maximum light level of either channel
+    pub const MAX: u8 = 15;
+
+    /// create a new, fully dark, light buffer
+    pub fn new() -> Self
+    {
+        Self { light: Box::new([0; CHUNK_VOLUME]) }
+    }
+
+    /// get the packed light of a block given its relative
+    /// position
+    pub fn get(&self, pos: int3) -> u8
+    {
+        self.light[ChunkPos::index(pos)]
+    }
+
+    /// get the sky light of a block given its relative
+    /// position
+    pub fn sky(&self, pos: int3) -> u8
+    {
+        self.channel(LightChannel::Sky, ChunkPos::index(pos))
+    }
+
+    /// get the block light of a block given its relative
+    /// position
+    pub fn block(&self, pos: int3) -> u8
+    {
+        self.channel(LightChannel::Block, ChunkPos::index(pos))
+    }
+
+    /// get one channel of the light at a flat index
+    pub fn channel(&self, ch: LightChannel, i: usize) -> u8
+    {
+        match ch
+        {
+            LightChannel::Sky => self.light[i] >> 4,
+            LightChannel::Block => self.light[i] & 0b1111,
+        }
+    }
+
+    /// set one channel of the light at a flat index
+    pub fn set_channel(&mut self, ch: LightChannel, i: usize, level: u8)
+    {
+        debug_assert!(level <= Self::MAX, "light level cannot exceed 0..16 range!");
+
+        self.light[i] = match ch
+        {
+            LightChannel::Sky => (self.light[i] & 0b1111) | (level << 4),
+            LightChannel::Block => (self.light[i] & 0b1111_0000) | level,
+        };
+    }
+}
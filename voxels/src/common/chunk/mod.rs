@@ -1,18 +1,32 @@
 mod c_blockbuf;
+mod c_lightbuf;
 mod c_chunk;
 mod t_chunk;
 mod r_cache;
+mod r_workers;
 mod s_load;
+mod s_unload;
 mod s_gen;
 mod pos;
+mod write;
+mod world;
+mod storage;
 
 pub use self::
 {
     c_blockbuf::*,
+    c_lightbuf::*,
     c_chunk::*,
     t_chunk::*,
     r_cache::*,
+    r_workers::*,
     s_load::*,
+    s_unload::*,
     s_gen::*,
     pos::*,
-};
\ No newline at end of file
+    write::*,
+    world::*,
+};
+
+#[cfg(test)]
+mod tests;
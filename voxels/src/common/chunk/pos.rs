@@ -16,4 +16,48 @@ impl ChunkPos
         pos.y -= pos.y.rem_euclid(SIZE);
         pos.z -= pos.z.rem_euclid(SIZE);
     }
+
+    /// get the position of the chunk containing an
+    /// arbitrary world point
+    pub fn containing(pos: float3) -> int3
+    {
+        let mut chunk = int3::new(pos.x.floor() as i32, pos.y.floor() as i32, pos.z.floor() as i32);
+        Self::adjust(&mut chunk);
+
+        chunk
+    }
+
+    /// split a global block position into the position of
+    /// its chunk, and its position relative to that chunk
+    pub fn split(pos: int3) -> (int3, int3)
+    {
+        const SIZE: i32 = crate::common::CHUNK_SIZE as i32;
+
+        let local = pos.map(|c| c.rem_euclid(SIZE));
+
+        (pos - local, local)
+    }
+
+    /// flatten a position relative to its chunk into an
+    /// index, in the order chunk buffers store blocks
+    pub fn index(local: int3) -> usize
+    {
+        use crate::common::{ CHUNK_SIZE, CHUNK_LAYER };
+
+        local.x as usize + (local.y as usize * CHUNK_SIZE) + (local.z as usize * CHUNK_LAYER)
+    }
+
+    /// get the position relative to its chunk of a
+    /// flattened index, see `ChunkPos::index`
+    pub fn local(index: usize) -> int3
+    {
+        use crate::common::{ CHUNK_SIZE, CHUNK_LAYER };
+
+        int3::new
+        (
+            (index % CHUNK_SIZE) as i32,
+            ((index / CHUNK_SIZE) % CHUNK_SIZE) as i32,
+            (index / CHUNK_LAYER) as i32,
+        )
+    }
 }
\ No newline at end of file
@@ -1,21 +1,27 @@
-use std::collections::HashMap;
+use std::collections::{ HashMap, HashSet };
 
 use ezgame::ecs::*;
 use ezmath::*;
 
-/// caches chunk using a hashmap
+/// caches chunk using a hashmap. also keeps track of which
+/// loaded chunks have their blocks
 #[derive(Debug)]
 pub struct RChunkCache
 {
     map: HashMap<int3, Entity>,
+    generated: HashSet<int3>,
 }
 
 impl RChunkCache
 {
     /// create a new chunk cache resource
-    pub(super) fn new() -> Self
+    pub(crate) fn new() -> Self
     {
-        Self { map: Default::default() }
+        Self
+        {
+            map: Default::default(),
+            generated: Default::default(),
+        }
     }
 
     /// stores a chunk in the cache. position
@@ -34,6 +40,30 @@ impl RChunkCache
         super::ChunkPos::adjust(&mut pos);
 
         self.map.remove(&pos);
+        self.generated.remove(&pos);
+    }
+
+    /// mark a loaded chunk as having its blocks. position
+    /// is adjusted to chunk grid
+    pub fn mark_generated(&mut self, mut pos: int3)
+    {
+        super::ChunkPos::adjust(&mut pos);
+
+        self.generated.insert(pos);
+    }
+
+    /// get the chunk where the block at pos is located, only
+    /// if it's loaded and has its blocks. position is adjusted
+    /// to chunk grid
+    pub fn generated_at(&self, mut pos: int3) -> Option<&Entity>
+    {
+        super::ChunkPos::adjust(&mut pos);
+
+        if !self.generated.contains(&pos)
+        {
+            return None;
+        }
+        self.map.get(&pos)
     }
 
     /// get the chunk where the block at pos is
@@ -46,6 +76,23 @@ impl RChunkCache
         self.map.get(&pos)
     }
 
+    /// get the chunks that share a face with the chunk at pos,
+    /// only those that are loaded and have their blocks. position
+    /// is adjusted to chunk grid
+    pub fn generated_neighbors(&self, mut pos: int3) -> impl Iterator<Item = Entity> + '_
+    {
+        use crate::common::block::BlockFace;
+
+        super::ChunkPos::adjust(&mut pos);
+
+        (0..6usize).filter_map(move |f|
+        {
+            let neighbor = pos + BlockFace::from(f).normal() * crate::common::CHUNK_SIZE as i32;
+
+            self.generated_at(neighbor).copied()
+        })
+    }
+
     /// utility function to safely load a chunk
     /// and store it in the cache. nothing is done
     /// is the cache deems the chunk as already loaded.
@@ -69,7 +116,7 @@ impl RChunkCache
             (TUngenerated,),
             vec!
             [
-                (CChunk::new(pos), CBlockBuffer::new())
+                (CChunk::new(pos), CBlockBuffer::new(), CLightBuffer::new())
             ],
         )[0];
         
@@ -0,0 +1,116 @@
+use std::sync::mpsc::{ self, Sender, Receiver };
+use std::sync::{ Arc, Mutex };
+use std::panic::{ self, AssertUnwindSafe };
+use std::any::Any;
+use std::thread;
+
+use ezgame::ecs::Entity;
+use ezmath::*;
+
+use super::CBlockBuffer;
+
+/// work given to the chunk workers: a chunk's position and
+/// entity, and what fills in its blocks
+type Job = (int3, Entity, Box<dyn FnOnce() -> CBlockBuffer + Send>);
+
+/// what a job gives back: the chunk's blocks, or the message
+/// it panicked with
+type Output = Result<CBlockBuffer, String>;
+
+/// resource that owns a pool of background threads, which
+/// fill in chunks' blocks off the frame loop. threads exit
+/// once this is dropped
+pub struct RChunkWorkers
+{
+    jobs: Mutex<Sender<Job>>,
+    results: Mutex<Receiver<(int3, Entity, Output)>>,
+
+    /// maximum number of finished chunks taken per frame
+    pub budget: usize,
+}
+
+impl RChunkWorkers
+{
+    /// spawn a new pool of `threads` workers
+    pub fn new(threads: usize) -> Self
+    {
+        let (jobs, job_rx) = mpsc::channel::<Job>();
+        let (res_tx, results) = mpsc::channel();
+
+        let job_rx = Arc::new(Mutex::new(job_rx));
+
+        for i in 0..threads.max(1)
+        {
+            let job_rx = job_rx.clone();
+            let res_tx = res_tx.clone();
+
+            thread::Builder::new()
+                .name(format!("chunk-worker-{}", i))
+                .spawn(move || loop
+                {
+                    // lock only while waiting for a job
+                    let job = job_rx.lock().unwrap().recv();
+
+                    match job
+                    {
+                        Ok((pos, ent, job)) =>
+                        {
+                            // a panicking job fails its chunk, not the worker
+                            let res = panic::catch_unwind(AssertUnwindSafe(job)).map_err(|e| message(&*e));
+
+                            if res_tx.send((pos, ent, res)).is_err()
+                            {
+                                break;
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                })
+                .expect("couldn't spawn chunk worker thread!");
+        }
+
+        Self
+        {
+            jobs: Mutex::new(jobs),
+            results: Mutex::new(results),
+            budget: 16,
+        }
+    }
+
+    /// queue the blocks of the chunk at `pos` to be filled in
+    /// by `job`, on whichever worker is free first. the chunk's
+    /// entity comes back with the result, since the chunk may
+    /// be unloaded and loaded again in the meantime
+    pub fn submit(&mut self, pos: int3, ent: Entity, job: impl FnOnce() -> CBlockBuffer + Send + 'static)
+    {
+        // workers only stop once this is dropped
+        let _ = self.jobs
+            .get_mut()
+            .unwrap()
+            .send((pos, ent, Box::new(job)));
+    }
+
+    /// take the chunks the workers finished, and the entities
+    /// they were submitted for, up to the budget, without waiting
+    /// for more. chunks whose job panicked come back as errors
+    pub fn finished(&mut self) -> impl Iterator<Item = (int3, Entity, Output)> + '_
+    {
+        let budget = self.budget;
+
+        self.results
+            .get_mut()
+            .unwrap()
+            .try_iter()
+            .take(budget)
+    }
+}
+
+/// the message a job panicked with, if it's text
+fn message(payload: &(dyn Any + Send)) -> String
+{
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
\ No newline at end of file
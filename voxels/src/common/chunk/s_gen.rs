@@ -1,12 +1,12 @@
 use ezgame::time::evt;
 use ezgame::ecs::*;
 
-use super::{ CChunk, CBlockBuffer, BlockBufferIndex, TUngenerated, TUpdated };
-use crate::common::block::PackedBlock;
-use crate::common::CHUNK_SIZE;
+use super::{ CChunk, RChunkCache, RChunkWorkers, TUngenerated, TGenerating, TUnlit, TUpdated };
+use crate::common::gen::RWorldGenerator;
 
 /// system that generates chunks'
-/// terrain
+/// terrain, on the chunk workers,
+/// using the world generator
 pub struct SChunkGen;
 
 impl System for SChunkGen
@@ -16,6 +16,11 @@ impl System for SChunkGen
 
     const FLUSH: bool = true;
 
+    fn prepare(res: &mut Resources)
+    {
+        res.insert(RChunkWorkers::new(3))
+    }
+
     fn exe() -> Job
     {
         // begin...
@@ -23,61 +28,63 @@ impl System for SChunkGen
         // components...
         .with_query
         (
-            <(Read<CChunk>, Write<CBlockBuffer>)>::query()
+            <Read<CChunk>>::query()
                 .filter(tag::<TUngenerated>())
         )
-        .build(|cmd, world, _, q_chunks|
+        // resources...
+        .read_resource::<RWorldGenerator>()
+        .write_resource::<RChunkCache>()
+        .write_resource::<RChunkWorkers>()
+        // system...
+        .build(|cmd, world, (r_gen, r_cache, r_workers), q_chunks|
         {
-            /// sea level at which terrain is generated
-            const SEA_LEVEL: f64 = 10.0;
-            /// up and down delta from sea level at which terrain is generated
-            const TERRAIN_DELTA: f64 = 5.0;
+            // hand new chunks to the workers
+            for (ent, chunk) in q_chunks.iter_entities(world)
+            {
+                let pos = chunk.pos();
+                let gen = RWorldGenerator::clone(&r_gen);
 
-            use noise::*;
+                r_workers.submit(pos, ent, move || gen.generate(pos));
 
-            let perlin = Perlin::new().set_seed(12345);
+                cmd.remove_tag::<TUngenerated>(ent);
+                cmd.add_tag(ent, TGenerating);
+            }
 
-            for (ent, (chunk, mut blocks)) in q_chunks.iter_entities_mut(world)
+            // apply what they finished, unless it was unloaded since.
+            // a chunk loaded again gets a new entity and job, so the
+            // old job's result is dropped
+            for (pos, ent, blocks) in r_workers.finished()
             {
-                // go through horizontal plane
-                for rx in 0..CHUNK_SIZE as u32
+                if r_cache.at(pos) != Some(&ent) || r_cache.generated_at(pos).is_some()
                 {
-                    for rz in 0..CHUNK_SIZE as u32
-                    {
-                        // global block
-                        let x = (rx as i32 + chunk.pos().x) as f64;
-                        let z = (rz as i32 + chunk.pos().z) as f64;
+                    continue;
+                }
 
-                        // global height
-                        let h = (perlin.get([x / 15.0, z / 15.0]) * TERRAIN_DELTA + SEA_LEVEL) as i32;
-                        
-                        // relative height
-                        let rh = h - chunk.pos().y;
+                match blocks
+                {
+                    Ok(blocks) =>
+                    {
+                        println!("generated chunk!");
 
-                        // fill all 0..32 or none 0..-n blocks
-                        for ry in 0..rh.min(CHUNK_SIZE as i32)
-                        {   
-                            blocks.set_packed((rx, ry, rz),
-                            {
-                                if ry == rh - 1
-                                {
-                                    PackedBlock::new(0b0000_0000_0001_0000)
-                                }
-                                else
-                                {
-                                    PackedBlock::new(0b0000_0000_0010_0000)
-                                }
-                            });
-                        }
+                        cmd.add_component(ent, blocks);
                     }
+                    // leave it empty, rather than generating forever
+                    Err(e) => println!("[error] couldn't generate chunk at {:?}, it's left empty: {}", pos, e),
                 }
-
-                println!("generated chunk!");
+                r_cache.mark_generated(pos);
 
                 // remove and set tags
-                cmd.remove_tag::<TUngenerated>(ent);
+                cmd.remove_tag::<TGenerating>(ent);
+                cmd.add_tag(ent, TUnlit);
                 cmd.add_tag(ent, TUpdated);
+
+                // neighbors meshed their border as if this chunk
+                // was missing
+                for n in r_cache.generated_neighbors(pos)
+                {
+                    cmd.add_tag(n, TUpdated);
+                }
             }
         })
     }
-}
\ No newline at end of file
+}
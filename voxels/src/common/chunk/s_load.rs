@@ -1,40 +1,141 @@
+use ezgame::time::evt;
 use ezgame::ecs::*;
-use ezgame::*;
 use ezmath::*;
 
+use crate::common::transform::CTranslation;
 use crate::common::CHUNK_SIZE;
 use super::*;
 
-/// chunk loading system
+/// chunk streaming system, which loads the chunks within
+/// view distance of the chunk loader, nearest first. see
+/// `TChunkLoader`
 pub struct SChunkLoad;
 
+/// resource that configures how far around the chunk loader
+/// chunks are loaded, and how fast
+#[derive(Debug, Clone)]
+pub struct RViewDistance
+{
+    /// radius, in chunks, along the x and z axes
+    pub horizontal: u32,
+    /// radius, in chunks, along the y axis
+    pub vertical: u32,
+    /// maximum number of chunks requested per frame
+    pub budget: usize,
+    /// extra radius, in chunks, that loaded chunks may be
+    /// before they're unloaded. this keeps chunks at the
+    /// edge from being reloaded as the loader moves back
+    /// and forth
+    pub margin: u32,
+}
+
+impl Default for RViewDistance
+{
+    fn default() -> Self
+    {
+        Self { horizontal: 8, vertical: 4, budget: 8, margin: 2 }
+    }
+}
+
+impl RViewDistance
+{
+    /// positions of every chunk within view distance of the
+    /// chunk at `center`, nearest first
+    pub fn chunks(&self, center: int3) -> Vec<int3>
+    {
+        let h = self.horizontal as i32;
+        let v = self.vertical as i32;
+
+        let mut chunks = (-h..=h)
+            .flat_map(|x| (-v..=v).map(move |y| (x, y)))
+            .flat_map(|(x, y)| (-h..=h).map(move |z| int3::new(x, y, z)))
+            .filter(|d| Self::within(*d, h, v))
+            .collect::<Vec<_>>();
+
+        chunks.sort_by_key(|d| d.dot(d));
+        chunks
+            .into_iter()
+            .map(|d| center + d * CHUNK_SIZE as i32)
+            .collect()
+    }
+
+    /// should the chunk at `chunk` stay loaded, with the loader
+    /// in the chunk at `center`? see `RViewDistance::margin`
+    pub fn keeps(&self, center: int3, chunk: int3) -> bool
+    {
+        let h = (self.horizontal + self.margin) as i32;
+        let v = (self.vertical + self.margin) as i32;
+
+        Self::within((chunk - center) / CHUNK_SIZE as i32, h, v)
+    }
+
+    /// is a chunk offset, in chunks, within a radius?
+    fn within(d: int3, h: i32, v: i32) -> bool
+    {
+        d.x * d.x + d.z * d.z <= h * h && d.y.abs() <= v
+    }
+}
+
 impl System for SChunkLoad
 {
-    const EVENT: Event = evt::START;    // temporary
-    const ORDER: Order = ord::MID;
-    
+    const EVENT: Event = evt::UPDATE;
+    const ORDER: Order = ord::MID + 1;
+
+    const FLUSH: bool = true;
+
     fn prepare(res: &mut Resources)
     {
-        res.insert(RChunkCache::new())
+        res.insert(RChunkCache::new());
+        res.insert(RViewDistance::default());
     }
 
     fn exe() -> Job
     {
+        // chunk the loader was last in, and the chunks around it
+        // that were missing then, farthest first
+        let mut center: Option<int3> = None;
+        let mut missing: Vec<int3> = Vec::new();
+
         // begin...
         sys("chunk_loading_system")
+        // components...
+        .with_query(<Read<CTranslation>>::query().filter(tag::<TChunkLoader>()))
         // resources...
         .write_resource::<RChunkCache>()
+        .read_resource::<RViewDistance>()
         // system...
-        .build(|cmd, _, r_cache, _|
+        .build(move |cmd, world, (r_cache, r_view), q_loader|
         {
-            // temporary load on start
-            (0..5)
-                .flat_map(|x| (0..5).map(move |z| (x, z)))
-                .flat_map(|(x, z)| (-2..2).map(move |y| (x, y, z)))
-                .for_each(|(x, y, z)|
+            let pos = match q_loader.iter(world).next()
+            {
+                Some(c_pos) => c_pos.0,
+                None => return,
+            };
+
+            // chunk the loader is in
+            let chunk = ChunkPos::containing(pos);
+
+            // crossed into another chunk, find what's missing
+            if center != Some(chunk)
+            {
+                center = Some(chunk);
+                missing = r_view
+                    .chunks(chunk)
+                    .into_iter()
+                    .rev()
+                    .filter(|c| r_cache.at(*c).is_none())
+                    .collect();
+            }
+
+            // request the nearest, within budget
+            for _ in 0..r_view.budget
+            {
+                match missing.pop()
                 {
-                    r_cache.load(cmd, int3::new(x, y, z) * CHUNK_SIZE as i32);
-                });
+                    Some(c) => r_cache.load(cmd, c),
+                    None => break,
+                }
+            }
         })
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,66 @@
+use ezgame::time::evt;
+use ezgame::ecs::*;
+
+use crate::common::transform::CTranslation;
+use crate::common::save::RWorldSave;
+use super::*;
+
+/// chunk unloading system, which deletes the chunks that
+/// left the chunk loader's view distance, saving them first
+/// if they changed
+pub struct SChunkUnload;
+
+impl System for SChunkUnload
+{
+    const EVENT: Event = evt::UPDATE;
+    const ORDER: Order = SChunkLoad::ORDER + 1;
+
+    const FLUSH: bool = true;
+
+    fn exe() -> Job
+    {
+        // begin...
+        sys("chunk_unloading_system")
+        // components...
+        .with_query(<Read<CTranslation>>::query().filter(tag::<TChunkLoader>()))
+        .with_query(<Read<CChunk>>::query())
+        .with_query(<(Read<CChunk>, Read<CBlockBuffer>)>::query().filter(tag::<TUnsaved>()))
+        // resources...
+        .write_resource::<RChunkCache>()
+        .write_resource::<RWorldSave>()
+        .read_resource::<RViewDistance>()
+        // system...
+        .build(|cmd, world, (r_cache, r_save, r_view), (q_loader, q_chunks, q_unsaved)|
+        {
+            let center = match q_loader.iter(world).next()
+            {
+                Some(c_pos) => ChunkPos::containing(c_pos.0),
+                None => return,
+            };
+
+            // save hook, for chunks that changed
+            for (ent, (chunk, blocks)) in q_unsaved.iter_entities(world)
+            {
+                if r_view.keeps(center, chunk.pos())
+                {
+                    continue;
+                }
+                if let Err(e) = r_save.save_chunk(chunk.pos(), &blocks)
+                {
+                    println!("[error] couldn't save unloaded chunk: {}", e);
+                }
+                cmd.remove_tag::<TUnsaved>(ent);
+            }
+
+            for (ent, chunk) in q_chunks.iter_entities(world)
+            {
+                if r_view.keeps(center, chunk.pos())
+                {
+                    continue;
+                }
+                r_cache.release(chunk.pos());
+                cmd.delete(ent);
+            }
+        })
+    }
+}
@@ -0,0 +1,274 @@
+use crate::common::block::PackedBlock;
+use crate::common::CHUNK_VOLUME;
+
+/// how a CBlockBuffer stores its blocks. the representation
+/// changes by itself as blocks are set:
+///     - uniform: every block is the same, ie. all air. no
+///       allocation at all
+///     - paletted: a local palette of the distinct blocks, and
+///       a bit-packed index into it for every block. 1, 2, 4
+///       or 8 bits per block, depending on the palette's size
+///     - direct: every packed block as is, for chunks with
+///       more than 256 distinct blocks. this is never undone,
+///       as chunks this busy rarely become simple again
+#[derive(Debug, Clone)]
+pub(super) enum BlockStorage
+{
+    Uniform(PackedBlock),
+    Paletted(PalettedBlocks),
+    Direct(Box<[PackedBlock; CHUNK_VOLUME]>),
+}
+
+/// blocks stored as indices into a local palette
+#[derive(Debug, Clone)]
+pub(super) struct PalettedBlocks
+{
+    /// distinct blocks of the chunk. entries no block uses
+    /// anymore are recycled
+    palette: Vec<PackedBlock>,
+    /// number of blocks using each palette entry
+    counts: Vec<u16>,
+
+    /// bits per index, a power of two so that indices never
+    /// straddle two words
+    bits: usize,
+    /// bit-packed indices, in flat index order
+    words: Box<[u64]>,
+}
+
+impl BlockStorage
+{
+    /// get the packed block at a flat index
+    pub fn get(&self, i: usize) -> PackedBlock
+    {
+        match self
+        {
+            BlockStorage::Uniform(block) => *block,
+            BlockStorage::Paletted(blocks) => blocks.get(i),
+            BlockStorage::Direct(blocks) => blocks[i],
+        }
+    }
+
+    /// set the packed block at a flat index, returning the
+    /// block it replaced. this may change representation
+    pub fn set(&mut self, i: usize, val: PackedBlock) -> PackedBlock
+    {
+        if let BlockStorage::Uniform(block) = *self
+        {
+            if block == val
+            {
+                return block;
+            }
+            *self = BlockStorage::Paletted(PalettedBlocks::new(block));
+        }
+
+        match self
+        {
+            BlockStorage::Uniform(_) => unreachable!(),
+            BlockStorage::Paletted(blocks) => match blocks.set(i, val)
+            {
+                Some(old) =>
+                {
+                    if let Some(block) = blocks.uniform()
+                    {
+                        *self = BlockStorage::Uniform(block);
+                    }
+                    old
+                }
+                // palette is full, fall back to storing blocks as is
+                None =>
+                {
+                    let mut direct = Box::new([PackedBlock::default(); CHUNK_VOLUME]);
+                    for (j, block) in direct.iter_mut().enumerate()
+                    {
+                        *block = blocks.get(j);
+                    }
+                    let old = std::mem::replace(&mut direct[i], val);
+
+                    *self = BlockStorage::Direct(direct);
+                    old
+                }
+            },
+            BlockStorage::Direct(blocks) => std::mem::replace(&mut blocks[i], val),
+        }
+    }
+
+    /// replace every block by the result of `f`. paletted
+    /// blocks that become the same are merged, which may make
+    /// the storage uniform
+    pub fn map(&mut self, f: impl Fn(PackedBlock) -> PackedBlock)
+    {
+        match self
+        {
+            BlockStorage::Uniform(block) => *block = f(*block),
+            BlockStorage::Paletted(blocks) =>
+            {
+                blocks.map(f);
+
+                if let Some(block) = blocks.uniform()
+                {
+                    *self = BlockStorage::Uniform(block);
+                }
+            }
+            BlockStorage::Direct(blocks) => blocks.iter_mut().for_each(|b| *b = f(*b)),
+        }
+    }
+}
+
+impl PalettedBlocks
+{
+    /// largest bits per index before falling back to the
+    /// direct representation
+    const MAX_BITS: usize = 8;
+
+    /// create paletted blocks where every block is the same
+    fn new(block: PackedBlock) -> Self
+    {
+        Self
+        {
+            palette: vec![block],
+            counts: vec![CHUNK_VOLUME as u16],
+            bits: 1,
+            words: vec![0; CHUNK_VOLUME / 64].into_boxed_slice(),
+        }
+    }
+
+    /// get the packed block at a flat index
+    fn get(&self, i: usize) -> PackedBlock
+    {
+        self.palette[self.index(i)]
+    }
+
+    /// set the packed block at a flat index, returning the
+    /// block it replaced. fails if the palette is full
+    fn set(&mut self, i: usize, val: PackedBlock) -> Option<PackedBlock>
+    {
+        let old = self.index(i);
+        if self.palette[old] == val
+        {
+            return Some(val);
+        }
+
+        let new = match self.palette.iter().position(|b| *b == val)
+        {
+            Some(new) => new,
+            None =>
+            {
+                // recycle an unused entry, or make room for one
+                let new = match self.counts.iter().position(|c| *c == 0)
+                {
+                    Some(new) => new,
+                    None if self.palette.len() < 1 << self.bits =>
+                    {
+                        self.palette.push(val);
+                        self.counts.push(0);
+                        self.palette.len() - 1
+                    }
+                    None if self.bits < Self::MAX_BITS =>
+                    {
+                        self.grow();
+                        self.palette.push(val);
+                        self.counts.push(0);
+                        self.palette.len() - 1
+                    }
+                    None => return None,
+                };
+                self.palette[new] = val;
+                new
+            }
+        };
+
+        self.counts[old] -= 1;
+        self.counts[new] += 1;
+        self.set_index(i, new);
+
+        Some(self.palette[old])
+    }
+
+    /// replace every palette entry by the result of `f`, then
+    /// point every block to the first entry of its block, so
+    /// that no two entries in use are the same
+    fn map(&mut self, f: impl Fn(PackedBlock) -> PackedBlock)
+    {
+        self.palette.iter_mut().for_each(|b| *b = f(*b));
+
+        let first = self.palette
+            .iter()
+            .map(|b| self.palette.iter().position(|o| o == b).unwrap())
+            .collect::<Vec<_>>();
+
+        if first.iter().enumerate().all(|(i, j)| i == *j)
+        {
+            return;
+        }
+
+        for (i, j) in first.iter().enumerate()
+        {
+            if i != *j
+            {
+                self.counts[*j] += std::mem::take(&mut self.counts[i]);
+            }
+        }
+        for i in 0..CHUNK_VOLUME
+        {
+            let index = first[self.index(i)];
+
+            self.set_index(i, index);
+        }
+    }
+
+    /// get the only block in use, if every block is the same
+    fn uniform(&self) -> Option<PackedBlock>
+    {
+        let mut used = self.counts
+            .iter()
+            .zip(self.palette.iter())
+            .filter(|(c, _)| **c > 0);
+
+        match (used.next(), used.next())
+        {
+            (Some((_, block)), None) => Some(*block),
+            _ => None,
+        }
+    }
+
+    /// double the bits per index
+    fn grow(&mut self)
+    {
+        let bits = self.bits * 2;
+        let old = std::mem::replace(self, Self
+        {
+            palette: Vec::new(),
+            counts: Vec::new(),
+            bits,
+            words: vec![0; CHUNK_VOLUME * bits / 64].into_boxed_slice(),
+        });
+
+        for i in 0..CHUNK_VOLUME
+        {
+            self.set_index(i, old.index(i));
+        }
+        self.palette = old.palette;
+        self.counts = old.counts;
+    }
+
+    /// get the palette index at a flat index
+    fn index(&self, i: usize) -> usize
+    {
+        let per = 64 / self.bits;
+        let mask = (1 << self.bits) - 1;
+
+        ((self.words[i / per] >> ((i % per) * self.bits)) & mask) as usize
+    }
+
+    /// set the palette index at a flat index
+    fn set_index(&mut self, i: usize, index: usize)
+    {
+        let per = 64 / self.bits;
+        let shift = (i % per) * self.bits;
+        let mask = ((1u64 << self.bits) - 1) << shift;
+
+        let word = &mut self.words[i / per];
+        *word = (*word & !mask) | ((index as u64) << shift);
+    }
+}
@@ -2,7 +2,27 @@
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct TUngenerated;
 
+/// tag to mark a chunk as generated, but not lit yet
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TUnlit;
+
 /// tag to mark a chunk that had a block updated
 /// in the past frame.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
-pub struct TUpdated;
\ No newline at end of file
+pub struct TUpdated;
+
+/// tag to mark a chunk whose blocks changed since
+/// it was last saved
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TUnsaved;
+
+/// tag to mark a chunk whose terrain is being generated
+/// by the chunk workers
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TGenerating;
+
+/// tag to mark the entity chunks are loaded around, ie.
+/// the main camera. it needs a CTranslation component, and
+/// only the first such entity is used
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TChunkLoader;
\ No newline at end of file
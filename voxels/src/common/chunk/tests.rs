@@ -0,0 +1,434 @@
+use ezgame::ecs::*;
+use ezmath::*;
+
+use crate::common::block::{ PackedBlock, PackedBlockFormat, BlockEntities };
+use crate::common::gen::{ RWorldGenerator, VoidGenerator, Feature, Decoration, Rng };
+use crate::common::testing::{ sign, TestWorld };
+use crate::common::CHUNK_VOLUME;
+use super::storage::BlockStorage;
+use super::*;
+
+fn text(blocks: &CBlockBuffer, pos: int3) -> Option<&str>
+{
+    blocks.get_entity(pos)?.data::<String>().map(|s| s.as_str())
+}
+
+#[test]
+fn test_blockbuf_entities()
+{
+    let mut blocks = CBlockBuffer::new();
+    let (a, b) = (int3::new(1, 2, 3), int3::new(4, 5, 6));
+
+    blocks.set_entity(a, sign("a")).unwrap();
+    blocks.set_entity(b, sign("b")).unwrap();
+
+    assert_eq!(text(&blocks, a), Some("a"));
+    assert_eq!(blocks.get_data(a), PackedBlock::from_data(3, 2));
+    assert_eq!(blocks.entities().len(), 2);
+
+    // rewriting the same block keeps its entity
+    blocks.set_packed(a, blocks.get_packed(a));
+    assert_eq!(text(&blocks, a), Some("a"));
+
+    // overwriting frees it
+    blocks.set_packed(a, PackedBlock::from_data(1, 0));
+    assert!(blocks.get_entity(a).is_none());
+    assert_eq!(blocks.entities().len(), 1);
+    assert_eq!(text(&blocks, b), Some("b"));
+}
+
+#[test]
+fn test_blockbuf_entity_copy()
+{
+    let mut blocks = CBlockBuffer::new();
+    let (a, b) = (int3::new(0, 0, 0), int3::new(31, 0, 0));
+
+    blocks.set_entity(a, sign("a")).unwrap();
+
+    // copying an address format block copies its entity
+    blocks.set_packed(b, blocks.get_packed(a));
+    assert_ne!(blocks.get_packed(a), blocks.get_packed(b));
+    assert_eq!(blocks.entities().len(), 2);
+
+    blocks.get_entity_mut(b).unwrap().data_mut::<String>().unwrap().push('!');
+    blocks.set_packed(a, PackedBlock::default());
+
+    assert_eq!(text(&blocks, a), None);
+    assert_eq!(text(&blocks, b), Some("a!"));
+}
+
+#[test]
+fn test_blockbuf_entities_full()
+{
+    let mut blocks = CBlockBuffer::new();
+
+    for i in 0..BlockEntities::CAPACITY
+    {
+        blocks.set_entity(ChunkPos::local(i), sign("")).unwrap();
+    }
+    assert_eq!(blocks.entities().len(), BlockEntities::CAPACITY);
+
+    let pos = int3::new(2, 2, 2);
+    let ent = blocks.set_entity(pos, sign("full")).unwrap_err();
+    assert_eq!(ent.data::<String>().unwrap(), "full");
+
+    // copies that don't fit keep their type
+    blocks.set_packed(int3::new(5, 5, 5), blocks.get_packed(pos));
+    assert_eq!(blocks.get_packed(int3::new(5, 5, 5)).format(), PackedBlockFormat::Data);
+    assert_eq!(blocks.get_data(int3::new(5, 5, 5)), PackedBlock::from_data(3, 2));
+}
+
+/// check every block of a storage against the expected ones
+fn assert_blocks(storage: &BlockStorage, expected: &[PackedBlock])
+{
+    for (i, block) in expected.iter().enumerate()
+    {
+        assert_eq!(storage.get(i), *block, "blocks differ at index {}", i);
+    }
+}
+
+fn is_paletted(storage: &BlockStorage) -> bool
+{
+    matches!(storage, BlockStorage::Paletted(_))
+}
+
+/// the `n`th distinct block
+fn nth(n: usize) -> PackedBlock
+{
+    PackedBlock::from_data(1 + n as u16 / 16, n as u16 % 16)
+}
+
+#[test]
+fn test_storage_uniform()
+{
+    let mut storage = BlockStorage::Uniform(PackedBlock::default());
+
+    assert_eq!(storage.set(7, PackedBlock::default()), PackedBlock::default());
+    assert!(matches!(storage, BlockStorage::Uniform(_)));
+
+    assert_eq!(storage.set(7, nth(0)), PackedBlock::default());
+    assert!(is_paletted(&storage));
+    assert_eq!(storage.get(7), nth(0));
+    assert_eq!(storage.get(8), PackedBlock::default());
+
+    // back to a single block
+    assert_eq!(storage.set(7, PackedBlock::default()), nth(0));
+    assert!(matches!(storage, BlockStorage::Uniform(b) if b == PackedBlock::default()));
+}
+
+#[test]
+fn test_storage_paletted()
+{
+    let mut storage = BlockStorage::Uniform(PackedBlock::default());
+    let mut expected = vec![PackedBlock::default(); CHUNK_VOLUME];
+
+    // every block count, crossing 1, 2, 4 and 8 bits per block
+    for n in 0..255
+    {
+        for i in (n..CHUNK_VOLUME).step_by(255 + n % 7)
+        {
+            let old = storage.set(i, nth(n));
+
+            assert_eq!(old, expected[i]);
+            expected[i] = nth(n);
+        }
+
+        assert!(is_paletted(&storage));
+        if [1, 2, 3, 4, 15, 16, 17, 254].contains(&n)
+        {
+            assert_blocks(&storage, &expected);
+        }
+    }
+    assert_blocks(&storage, &expected);
+
+    // unused entries are recycled rather than growing
+    let i = expected.iter().position(|b| *b == nth(254)).unwrap();
+    for (j, block) in expected.iter_mut().enumerate()
+    {
+        if *block == nth(254)
+        {
+            storage.set(j, nth(0));
+            *block = nth(0);
+        }
+    }
+    storage.set(i, nth(300));
+    expected[i] = nth(300);
+
+    assert!(is_paletted(&storage));
+    assert_blocks(&storage, &expected);
+}
+
+#[test]
+fn test_storage_direct()
+{
+    let mut storage = BlockStorage::Uniform(PackedBlock::default());
+    let mut expected = vec![PackedBlock::default(); CHUNK_VOLUME];
+
+    for n in 0..256
+    {
+        storage.set(n * 100, nth(n));
+        expected[n * 100] = nth(n);
+    }
+    assert!(matches!(storage, BlockStorage::Direct(_)));
+    assert_blocks(&storage, &expected);
+
+    // every block distinct
+    for (i, block) in expected.iter_mut().enumerate()
+    {
+        *block = PackedBlock::new(i as u16);
+        storage.set(i, *block);
+    }
+    assert_blocks(&storage, &expected);
+
+    storage.map(|b| PackedBlock::new(b.raw() ^ 1));
+    assert_eq!(storage.get(6), PackedBlock::new(7));
+}
+
+#[test]
+fn test_storage_map()
+{
+    let mut storage = BlockStorage::Uniform(PackedBlock::default());
+
+    storage.set(7, nth(0));
+    storage.set(8, nth(1));
+
+    // blocks mapped to the same block share their count
+    storage.map(|b| if b == nth(1) { nth(0) } else { b });
+    assert_eq!(storage.get(8), nth(0));
+
+    storage.set(7, PackedBlock::default());
+    assert!(is_paletted(&storage));
+    assert_eq!(storage.get(8), nth(0));
+
+    storage.set(8, PackedBlock::default());
+    assert!(matches!(storage, BlockStorage::Uniform(b) if b == PackedBlock::default()));
+
+    // and a chunk mapped to a single block is uniform
+    storage.set(9, nth(2));
+    storage.map(|_| nth(3));
+    assert!(matches!(storage, BlockStorage::Uniform(b) if b == nth(3)));
+}
+
+#[test]
+fn test_workers_panic()
+{
+    let mut world = TestWorld::new();
+    let ent = world.chunk(int3::zeros(), CBlockBuffer::new());
+
+    let mut workers = RChunkWorkers::new(1);
+    let (a, b) = (int3::zeros(), int3::new(32, 0, 0));
+
+    workers.submit(a, ent, || panic!("bad terrain"));
+    workers.submit(b, ent, CBlockBuffer::new);
+
+    // the same worker finishes both
+    let mut results = Vec::new();
+    for _ in 0..1000
+    {
+        results.extend(workers.finished());
+
+        if results.len() == 2
+        {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    assert_eq!(results.len(), 2);
+    assert!(matches!(&results[0], (pos, _, Err(msg)) if *pos == a && msg == "bad terrain"));
+    assert!(matches!(&results[1], (pos, _, Ok(_)) if *pos == b));
+}
+
+/// feature where each of the given chunks places its block
+/// in the same cell, to see which one gets it
+struct Contest
+{
+    cell: int3,
+    blocks: Vec<(int3, PackedBlock)>,
+}
+
+impl Feature for Contest
+{
+    fn decorate(&self, _: &mut Rng, deco: &mut Decoration)
+    {
+        for (pos, block) in &self.blocks
+        {
+            if *pos == deco.pos()
+            {
+                deco.place(self.cell, *block);
+            }
+        }
+    }
+}
+
+/// add a chunk that doesn't have its blocks yet, then run
+/// the chunk gen system until it does
+fn generate(world: &mut TestWorld, pos: int3) -> Entity
+{
+    let ent = world.registry.insert((TUngenerated,), vec![(CChunk::new(pos), CBlockBuffer::new(), CLightBuffer::new())])[0];
+    world.resources.get_mut::<RChunkCache>().unwrap().store(pos, ent);
+
+    for _ in 0..1000
+    {
+        world.run(SChunkGen::exe());
+
+        if world.resources.get::<RChunkCache>().unwrap().generated_at(pos).is_some()
+        {
+            return ent;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    panic!("chunk at {:?} wasn't generated", pos);
+}
+
+/// world generator where chunks A(0, 0, 0) and C(64, 0, 0)
+/// both spill a block into the same cell of X, between them
+fn contested(cell: int3) -> RWorldGenerator
+{
+    let contest = Contest
+    {
+        cell,
+        blocks: vec![(int3::new(64, 0, 0), PackedBlock::from_data(3, 0)), (int3::new(0, 0, 0), PackedBlock::from_data(1, 0))],
+    };
+
+    RWorldGenerator::new(0, VoidGenerator).with_features(vec![Box::new(contest)])
+}
+
+#[test]
+fn test_gen_order()
+{
+    let (a, x, c) = (int3::new(0, 0, 0), int3::new(32, 0, 0), int3::new(64, 0, 0));
+    let cell = int3::new(40, 5, 5);
+
+    let orders = vec![[a, x, c], [a, c, x], [x, a, c], [x, c, a], [c, a, x], [c, x, a]];
+    let results = orders
+        .iter()
+        .map(|order|
+        {
+            // a fresh generator, so nothing is cached from
+            // the other orders
+            let mut world = TestWorld::new();
+            world.resources.insert(contested(cell));
+            SChunkGen::prepare(&mut world.resources);
+
+            let ents = order
+                .iter()
+                .map(|pos| generate(&mut world, *pos))
+                .collect::<Vec<_>>();
+
+            ents.iter()
+                .zip(order.iter())
+                .map(|(ent, pos)|
+                {
+                    let blocks = world.registry.get_component::<CBlockBuffer>(*ent).unwrap();
+                    let blocks = (0..CHUNK_VOLUME).map(|i| blocks.get_packed(ChunkPos::local(i))).collect::<Vec<_>>();
+
+                    (pos.x, blocks)
+                })
+                .collect::<std::collections::BTreeMap<_, _>>()
+        })
+        .collect::<Vec<_>>();
+
+    // same chunks whatever the order, and the neighbour with
+    // the lowest position gets the cell
+    assert!(results.iter().all(|r| *r == results[0]));
+    assert_eq!(results[0][&x.x][ChunkPos::index(cell - x)], PackedBlock::from_data(1, 0));
+}
+
+#[test]
+fn test_gen_no_respill()
+{
+    let (a, x) = (int3::new(0, 0, 0), int3::new(32, 0, 0));
+    let cell = int3::new(40, 5, 5);
+
+    let mut world = TestWorld::new();
+    world.resources.insert(contested(cell));
+    SChunkGen::prepare(&mut world.resources);
+
+    let ent_a = generate(&mut world, a);
+    let ent_x = generate(&mut world, x);
+
+    // break what A placed in X
+    {
+        let mut blocks = world.registry.get_component_mut::<CBlockBuffer>(ent_x).unwrap();
+
+        assert_eq!(blocks.get_packed(cell - x), PackedBlock::from_data(1, 0));
+        blocks.set_packed(cell - x, PackedBlock::default());
+    }
+
+    // A is unloaded, then generated again
+    world.registry.delete(ent_a);
+    world.resources.get_mut::<RChunkCache>().unwrap().release(a);
+    generate(&mut world, a);
+
+    let blocks = world.registry.get_component::<CBlockBuffer>(ent_x).unwrap();
+    assert_eq!(blocks.get_packed(cell - x), PackedBlock::default());
+}
+
+#[test]
+fn test_set_region()
+{
+    let (stone, dirt) = (PackedBlock::from_data(1, 0), PackedBlock::from_data(2, 0));
+    let (min, max) = (int3::new(30, 30, 0), int3::new(33, 31, 1));
+
+    let mut world = TestWorld::new();
+
+    // a and b share the box, c is above a and d is beside it
+    let chunks = [int3::new(0, 0, 0), int3::new(32, 0, 0), int3::new(0, 32, 0), int3::new(-32, 0, 0)];
+    let ents = chunks
+        .iter()
+        .map(|&pos|
+        {
+            let mut blocks = CBlockBuffer::new();
+            for i in 0..CHUNK_VOLUME
+            {
+                blocks.set_packed(ChunkPos::local(i), stone);
+            }
+            if pos == chunks[0]
+            {
+                blocks.set_entity(int3::new(31, 31, 0), sign("a")).unwrap();
+            }
+            if pos == chunks[1]
+            {
+                blocks.set_packed(int3::new(1, 30, 1), dirt);
+            }
+
+            world.chunk(pos, blocks)
+        })
+        .collect::<Vec<_>>();
+
+    // blocks read across chunks, in data format, and none
+    // where chunks aren't loaded
+    let read = world.edit(move |blocks|
+    {
+        let mut read = Vec::new();
+        blocks.get_region(int3::new(31, 31, 0), int3::new(32, 31, 0), |pos, block| read.push((pos, block)));
+
+        (read, blocks.get_block(int3::new(0, 64, 0)))
+    });
+    assert_eq!(read.0, vec![(int3::new(31, 31, 0), Some(PackedBlock::from_data(3, 2))), (int3::new(32, 31, 0), Some(stone))]);
+    assert_eq!(read.1, None);
+
+    // stone becomes dirt, and signs glass
+    let changed = world.edit(move |blocks| blocks.set_region(min, max, |_, old| match old
+    {
+        b if b == PackedBlock::from_data(3, 2) => Some(PackedBlock::from_data(4, 0)),
+        b if b == stone => Some(dirt),
+        b => Some(b),
+    }));
+
+    // every block of the box but the one that was already dirt
+    assert_eq!(changed, 15);
+
+    let a = world.registry.get_component::<CBlockBuffer>(ents[0]).unwrap();
+    assert_eq!(a.get_packed(int3::new(31, 31, 0)), PackedBlock::from_data(4, 0));
+    assert_eq!(a.get_packed(int3::new(30, 30, 1)), dirt);
+    assert_eq!(a.entities().len(), 0);
+    drop(a);
+
+    let updated = ents.iter().map(|ent| world.registry.get_tag::<TUpdated>(*ent).is_some()).collect::<Vec<_>>();
+    let unsaved = ents.iter().map(|ent| world.registry.get_tag::<TUnsaved>(*ent).is_some()).collect::<Vec<_>>();
+
+    assert_eq!(updated, vec![true, true, true, false]);
+    assert_eq!(unsaved, vec![true, true, false, false]);
+}
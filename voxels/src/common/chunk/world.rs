@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+
+use ezgame::ecs::*;
+use ezmath::*;
+
+use crate::common::block::{ PackedBlock, BlockFace, BlockEntity };
+use crate::common::light::RLightQueue;
+use crate::common::CHUNK_SIZE;
+use super::{ CBlockBuffer, BlockBufferIndex, RChunkCache, ChunkPos, TUpdated, TUnsaved };
+
+/// access to the blocks of every loaded chunk, on global
+/// positions, from within a system. setting blocks relights
+/// them, and marks their chunk, and the neighbours bordering
+/// them, to be remeshed. systems using this must write the
+/// `CBlockBuffer` component
+/// ```rust
+/// .write_component::<CBlockBuffer>()
+/// .read_resource::<RChunkCache>()
+/// .write_resource::<RLightQueue>()
+/// .build(|cmd, world, (r_cache, r_light), _|
+/// {
+///     let mut blocks = WorldBlocks::new(world, cmd, &r_cache, &mut r_light);
+///
+///     blocks.set_block(int3::new(0, 10, 0), stone);
+/// })
+/// ```
+pub struct WorldBlocks<'a>
+{
+    world: &'a mut SubRegistry,
+    cmd: &'a mut Cmd,
+    cache: &'a RChunkCache,
+    light: &'a mut RLightQueue,
+}
+
+impl<'a> WorldBlocks<'a>
+{
+    /// access the blocks of the world within a system
+    pub fn new(world: &'a mut SubRegistry, cmd: &'a mut Cmd, cache: &'a RChunkCache, light: &'a mut RLightQueue) -> Self
+    {
+        Self { world, cmd, cache, light }
+    }
+
+    /// get the block at a global position, if its chunk is
+    /// loaded and generated. address format blocks are read
+    /// as the data format block of their entity's type, since
+    /// their address means nothing outside their chunk
+    pub fn get_block(&self, pos: int3) -> Option<PackedBlock>
+    {
+        let (chunk, local) = ChunkPos::split(pos);
+        let ent = *self.cache.generated_at(chunk)?;
+
+        self.world
+            .get_component::<CBlockBuffer>(ent)
+            .map(|blocks| blocks.get_data(local))
+    }
+
+    /// set the block at a global position, if its chunk is
+    /// loaded and generated. returns the block it replaced,
+    /// read like `get_block`
+    pub fn set_block(&mut self, pos: int3, block: PackedBlock) -> Option<PackedBlock>
+    {
+        let old = self.get_block(pos)?;
+
+        self.set_region(pos, pos, |_, _| Some(block));
+
+        Some(old)
+    }
+
+    /// read every block of a box, given its min and max global
+    /// corners(inclusive), one chunk at a time. `f` gets each
+    /// block's global position, and the block, or none if its
+    /// chunk isn't loaded and generated. address format blocks
+    /// are read as the data format block of their entity's type
+    pub fn get_region(&self, min: int3, max: int3, mut f: impl FnMut(int3, Option<PackedBlock>))
+    {
+        for (chunk, lo, hi) in Self::slices(min, max)
+        {
+            let blocks = self.cache
+                .generated_at(chunk)
+                .and_then(|ent| self.world.get_component::<CBlockBuffer>(*ent));
+
+            for_each(lo, hi, |local| f(chunk + local, blocks.as_ref().map(|blocks| blocks.get_data(local))));
+        }
+    }
+
+    /// read the block entities of a box, given its min and max
+    /// global corners(inclusive). `f` gets each one's global
+    /// position. chunks that aren't loaded and generated are
+    /// skipped
+    pub fn get_entities(&self, min: int3, max: int3, mut f: impl FnMut(int3, &BlockEntity))
+    {
+        for (chunk, lo, hi) in Self::slices(min, max)
+        {
+            let blocks = match self.cache.generated_at(chunk).and_then(|ent| self.world.get_component::<CBlockBuffer>(*ent))
+            {
+                Some(blocks) => blocks,
+                None => continue,
+            };
+            if blocks.entities().is_empty()
+            {
+                continue;
+            }
+
+            for_each(lo, hi, |local| if let Some(ent) = blocks.get_entity(local)
+            {
+                f(chunk + local, ent);
+            });
+        }
+    }
+
+    /// place a block entity at a global position, along with
+    /// its block, if its chunk is loaded and generated. the
+    /// block entity is given back if it isn't, or if the chunk
+    /// is full
+    pub fn set_entity(&mut self, pos: int3, ent: BlockEntity) -> Result<(), BlockEntity>
+    {
+        let (chunk, local) = ChunkPos::split(pos);
+        let id = match self.cache.generated_at(chunk)
+        {
+            Some(id) => *id,
+            None => return Err(ent),
+        };
+
+        // relights and tags the block, if it changed
+        let block = PackedBlock::from_data(ent.id, ent.variant);
+        let old = self.set_block(pos, block);
+
+        match self.world.get_component_mut::<CBlockBuffer>(id)
+        {
+            Some(mut blocks) => blocks.set_entity(local, ent)?,
+            None => return Err(ent),
+        }
+        if old == Some(block)
+        {
+            self.cmd.add_tag(id, TUnsaved);
+        }
+        Ok(())
+    }
+
+    /// rewrite every block of a box, given its min and max global
+    /// corners(inclusive), one chunk at a time. `f` gets each
+    /// block's global position and current block, read like
+    /// `WorldBlocks::get_block`, and gives the block to write
+    /// there, if any. chunks that aren't loaded and generated
+    /// are skipped. every chunk that changed, and the neighbours
+    /// bordering the changes, are tagged once.
+    /// returns the number of blocks changed
+    pub fn set_region(&mut self, min: int3, max: int3, mut f: impl FnMut(int3, PackedBlock) -> Option<PackedBlock>) -> usize
+    {
+        let mut changed = 0;
+        let mut updated = HashSet::new();
+        let mut unsaved = HashSet::new();
+
+        for (chunk, lo, hi) in Self::slices(min, max)
+        {
+            let ent = match self.cache.generated_at(chunk)
+            {
+                Some(ent) => *ent,
+                None => continue,
+            };
+            let mut blocks = match self.world.get_component_mut::<CBlockBuffer>(ent)
+            {
+                Some(blocks) => blocks,
+                None => continue,
+            };
+
+            let cache = self.cache;
+            let light = &mut *self.light;
+            let before = changed;
+
+            for_each(lo, hi, |local|
+            {
+                let pos = chunk + local;
+                let old = blocks.get_data(local);
+
+                match f(pos, old)
+                {
+                    Some(block) if block != old => blocks.set_packed(local, block),
+                    _ => return,
+                }
+                changed += 1;
+                light.push(pos);
+
+                // neighbours see this block's faces too
+                for face in 0..6usize
+                {
+                    let normal = BlockFace::from(face).normal();
+                    let edge = (0..3).any(|a| match normal[a]
+                    {
+                        -1 => local[a] == 0,
+                        1 => local[a] == CHUNK_SIZE as i32 - 1,
+                        _ => false,
+                    });
+
+                    if !edge
+                    {
+                        continue;
+                    }
+                    if let Some(neighbor) = cache.at(chunk + normal * CHUNK_SIZE as i32)
+                    {
+                        updated.insert(*neighbor);
+                    }
+                }
+            });
+
+            if changed > before
+            {
+                updated.insert(ent);
+                unsaved.insert(ent);
+            }
+        }
+
+        for ent in updated
+        {
+            self.cmd.add_tag(ent, TUpdated);
+        }
+        for ent in unsaved
+        {
+            self.cmd.add_tag(ent, TUnsaved);
+        }
+        changed
+    }
+
+    /// split a box, given its min and max global corners, into
+    /// the chunks it overlaps, as (chunk, local min, local max)
+    fn slices(min: int3, max: int3) -> Vec<(int3, int3, int3)>
+    {
+        const SIZE: i32 = CHUNK_SIZE as i32;
+
+        let (lo, _) = ChunkPos::split(min);
+        let (hi, _) = ChunkPos::split(max);
+
+        let mut slices = Vec::new();
+        for cx in (lo.x..=hi.x).step_by(CHUNK_SIZE)
+        {
+            for cy in (lo.y..=hi.y).step_by(CHUNK_SIZE)
+            {
+                for cz in (lo.z..=hi.z).step_by(CHUNK_SIZE)
+                {
+                    let chunk = int3::new(cx, cy, cz);
+
+                    let a = (min - chunk).map(|c| c.max(0));
+                    let b = (max - chunk).map(|c| c.min(SIZE - 1));
+
+                    slices.push((chunk, a, b));
+                }
+            }
+        }
+        slices
+    }
+}
+
+/// call `f` on every position of a box, given its min and
+/// max corners(inclusive)
+fn for_each(min: int3, max: int3, mut f: impl FnMut(int3))
+{
+    for z in min.z..=max.z
+    {
+        for y in min.y..=max.y
+        {
+            for x in min.x..=max.x
+            {
+                f(int3::new(x, y, z));
+            }
+        }
+    }
+}
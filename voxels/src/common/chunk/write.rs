@@ -0,0 +1,43 @@
+use ezmath::*;
+
+use crate::common::block::PackedBlock;
+use super::{ CBlockBuffer, BlockBufferIndex, ChunkPos };
+
+/// a block to be written at a global position, but only
+/// over the block it expects to find there. world features
+/// use these to reach into neighbouring chunks without
+/// overwriting what's already been placed
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BlockWrite
+{
+    /// global position of the block
+    pub pos: int3,
+    /// block to write
+    pub block: PackedBlock,
+    /// block that must be there to be overwritten
+    pub replaces: PackedBlock,
+}
+
+impl BlockWrite
+{
+    /// get the position of the chunk this write lands in
+    pub fn chunk(&self) -> int3
+    {
+        ChunkPos::split(self.pos).0
+    }
+
+    /// apply this write to the blocks of the chunk it lands
+    /// in. returns whether the block was written
+    pub fn apply(&self, blocks: &mut CBlockBuffer) -> bool
+    {
+        let (_, local) = ChunkPos::split(self.pos);
+
+        if blocks.get_packed(local) != self.replaces
+        {
+            return false;
+        }
+        blocks.set_packed(local, self.block);
+
+        true
+    }
+}
@@ -0,0 +1,152 @@
+use ezmath::*;
+
+use crate::common::block::{ PackedBlock, PackedBlockFormat, UnpackedBlock, RBlockPalette, BlockEntities, BlockFace, Axis };
+
+/// blocks copied out of the world, to be pasted elsewhere.
+/// positions are relative to the copy's min corner. blocks
+/// that weren't loaded when copied are none, and aren't
+/// pasted
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clipboard
+{
+    size: int3,
+    blocks: Vec<Option<PackedBlock>>,
+}
+
+impl Clipboard
+{
+    /// create a new clipboard, filled with none
+    pub fn new(size: int3) -> Self
+    {
+        let size = size.map(|c| c.max(0));
+
+        Self
+        {
+            size,
+            blocks: vec![None; (size.x * size.y * size.z) as usize],
+        }
+    }
+
+    /// number of blocks along each axis
+    pub fn size(&self) -> int3
+    {
+        self.size
+    }
+
+    /// get the block at a relative position, none if it
+    /// wasn't copied or is out of bounds
+    pub fn get(&self, pos: int3) -> Option<PackedBlock>
+    {
+        self.index(pos).and_then(|i| self.blocks[i])
+    }
+
+    /// set the block at a relative position. does nothing
+    /// if it's out of bounds
+    pub fn set(&mut self, pos: int3, block: Option<PackedBlock>)
+    {
+        if let Some(i) = self.index(pos)
+        {
+            self.blocks[i] = block;
+        }
+    }
+
+    /// iterate every relative position and its block
+    pub fn iter(&self) -> impl Iterator<Item = (int3, Option<PackedBlock>)> + '_
+    {
+        let size = self.size;
+
+        self.blocks
+            .iter()
+            .enumerate()
+            .map(move |(i, block)|
+            {
+                let i = i as i32;
+                let pos = int3::new(i % size.x, (i / size.x) % size.y, i / (size.x * size.y));
+
+                (pos, *block)
+            })
+    }
+
+    /// rotate the clipboard by quarter turns around the y axis,
+    /// counter-clockwise seen from above, along with the
+    /// blocks' facing and axis
+    pub fn rotate(&self, turns: i32, pal: &RBlockPalette) -> Self
+    {
+        let mut out = self.clone();
+
+        for _ in 0..turns.rem_euclid(4)
+        {
+            let src = out;
+            let size = int3::new(src.size.z, src.size.y, src.size.x);
+
+            out = Self::new(size);
+            for (pos, block) in src.iter()
+            {
+                let to = int3::new(src.size.z - 1 - pos.z, pos.y, pos.x);
+
+                out.set(to, block.map(|b| orient(b, pal, |d| int3::new(-d.z, d.y, d.x))));
+            }
+        }
+        out
+    }
+
+    /// mirror the clipboard along an axis(0 = x, 1 = y, 2 = z),
+    /// along with the blocks' facing
+    pub fn mirror(&self, axis: usize, pal: &RBlockPalette) -> Self
+    {
+        let mut out = Self::new(self.size);
+
+        for (mut pos, block) in self.iter()
+        {
+            pos[axis] = self.size[axis] - 1 - pos[axis];
+
+            out.set(pos, block.map(|b| orient(b, pal, |mut d| { d[axis] = -d[axis]; d })));
+        }
+        out
+    }
+
+    /// flatten a relative position into an index, if it's
+    /// in bounds
+    fn index(&self, pos: int3) -> Option<usize>
+    {
+        if (0..3).any(|a| pos[a] < 0 || pos[a] >= self.size[a])
+        {
+            return None;
+        }
+        Some((pos.x + pos.y * self.size.x + pos.z * self.size.x * self.size.y) as usize)
+    }
+}
+
+/// turn a block's facing and axis state properties, if it
+/// has any, by a direction mapping
+fn orient(block: PackedBlock, pal: &RBlockPalette, map: impl Fn(int3) -> int3) -> PackedBlock
+{
+    if block.format() == PackedBlockFormat::Addr
+    {
+        return block;
+    }
+    let ents = BlockEntities::default();
+    let unpacked = UnpackedBlock::new(block, int3::zeros(), pal, &ents);
+
+    if let Some(face) = unpacked.get_property::<BlockFace>("facing")
+    {
+        if let Some(face) = BlockFace::from_normal(map(face.normal()))
+        {
+            return unpacked.with_property("facing", face).unwrap_or(block);
+        }
+    }
+    if let Some(axis) = unpacked.get_property::<Axis>("axis")
+    {
+        let mut dir = int3::zeros();
+        dir[axis as usize] = 1;
+
+        let axis = match map(dir).iamax()
+        {
+            0 => Axis::X,
+            1 => Axis::Y,
+            _ => Axis::Z,
+        };
+        return unpacked.with_property("axis", axis).unwrap_or(block);
+    }
+    block
+}
@@ -0,0 +1,13 @@
+mod region;
+mod clipboard;
+mod ops;
+
+pub use self::
+{
+    region::*,
+    clipboard::*,
+    ops::*,
+};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,75 @@
+use ezmath::*;
+
+use crate::common::chunk::WorldBlocks;
+use crate::common::block::PackedBlock;
+use super::{ BlockRegion, Clipboard };
+
+/// bulk edits of the world's blocks. each is written one
+/// chunk at a time, see `WorldBlocks::set_region`, and gives
+/// back the number of blocks changed
+/// ```rust
+/// let arena = BlockRegion::new(int3::new(-16, 0, -16), int3::new(16, 8, 16));
+///
+/// blocks.hollow(arena, stone);
+/// blocks.replace(arena, stone, glass);
+///
+/// let copy = blocks.copy(arena).rotate(1, &r_pal);
+/// blocks.paste(&copy, int3::new(64, 0, 0));
+/// ```
+impl<'a> WorldBlocks<'a>
+{
+    /// set every block of a region
+    pub fn fill(&mut self, region: BlockRegion, block: PackedBlock) -> usize
+    {
+        self.set_region(region.min, region.max, |_, _| Some(block))
+    }
+
+    /// set the outer shell of a region, leaving its inside
+    /// untouched
+    pub fn hollow(&mut self, region: BlockRegion, block: PackedBlock) -> usize
+    {
+        self.set_region(region.min, region.max, |pos, _| if region.is_shell(pos) { Some(block) } else { None })
+    }
+
+    /// replace every block of a region that's `from` by `to`
+    pub fn replace(&mut self, region: BlockRegion, from: PackedBlock, to: PackedBlock) -> usize
+    {
+        self.set_region(region.min, region.max, |_, old| if old == from { Some(to) } else { None })
+    }
+
+    /// set every block whose centre is within a sphere
+    pub fn sphere(&mut self, centre: int3, radius: f32, block: PackedBlock) -> usize
+    {
+        let reach = int3::repeat(radius.ceil() as i32);
+
+        self.set_region(centre - reach, centre + reach, |pos, _|
+        {
+            let d = pos - centre;
+            let d = float3::new(d.x as f32, d.y as f32, d.z as f32);
+
+            if d.dot(&d) <= radius * radius { Some(block) } else { None }
+        })
+    }
+
+    /// copy the blocks of a region
+    pub fn copy(&self, region: BlockRegion) -> Clipboard
+    {
+        let mut clip = Clipboard::new(region.size());
+
+        self.get_region(region.min, region.max, |pos, block| clip.set(pos - region.min, block));
+
+        clip
+    }
+
+    /// paste copied blocks, with their min corner at `at`
+    pub fn paste(&mut self, clip: &Clipboard, at: int3) -> usize
+    {
+        if clip.size().iter().any(|c| *c <= 0)
+        {
+            return 0;
+        }
+        let max = at + clip.size() - int3::repeat(1);
+
+        self.set_region(at, max, |pos, _| clip.get(pos - at))
+    }
+}
@@ -0,0 +1,57 @@
+use ezmath::*;
+
+/// box of blocks, on global positions, given its min
+/// and max corners(inclusive)
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct BlockRegion
+{
+    pub min: int3,
+    pub max: int3,
+}
+
+impl BlockRegion
+{
+    /// create a new region between two opposite corners,
+    /// in any order
+    pub fn new(a: int3, b: int3) -> Self
+    {
+        Self
+        {
+            min: a.zip_map(&b, |a, b| a.min(b)),
+            max: a.zip_map(&b, |a, b| a.max(b)),
+        }
+    }
+
+    /// create a new region from its min corner and size
+    pub fn sized(min: int3, size: int3) -> Self
+    {
+        Self::new(min, min + size - int3::repeat(1))
+    }
+
+    /// number of blocks along each axis
+    pub fn size(&self) -> int3
+    {
+        self.max - self.min + int3::repeat(1)
+    }
+
+    /// number of blocks in this region
+    pub fn volume(&self) -> usize
+    {
+        let size = self.size();
+
+        size.x as usize * size.y as usize * size.z as usize
+    }
+
+    /// is a global position within this region?
+    pub fn contains(&self, pos: int3) -> bool
+    {
+        (0..3).all(|a| pos[a] >= self.min[a] && pos[a] <= self.max[a])
+    }
+
+    /// is a global position on the outer shell of this
+    /// region?
+    pub fn is_shell(&self, pos: int3) -> bool
+    {
+        self.contains(pos) && (0..3).any(|a| pos[a] == self.min[a] || pos[a] == self.max[a])
+    }
+}
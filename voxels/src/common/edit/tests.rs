@@ -0,0 +1,78 @@
+use ezmath::*;
+
+use crate::common::block::{ PackedBlock, RBlockPalette, UnpackedBlock, BlockEntities, Axis };
+use crate::common::testing::palette;
+use super::*;
+
+/// clipboard with a distinct block at every position, out of
+/// blocks without state properties
+fn numbered(size: int3, pal: &RBlockPalette) -> Clipboard
+{
+    let ids: Vec<u16> = ["dirt", "stone", "sand", "snow"]
+        .iter()
+        .map(|id| pal.id(id).unwrap())
+        .collect();
+    let mut clip = Clipboard::new(size);
+
+    for (i, (pos, _)) in Clipboard::new(size).iter().enumerate()
+    {
+        clip.set(pos, Some(PackedBlock::from_data(ids[i % 4], (i / 4) as u16)));
+    }
+    clip
+}
+
+#[test]
+fn test_region()
+{
+    let region = BlockRegion::new(int3::new(3, -1, 0), int3::new(-2, 1, 2));
+
+    assert_eq!(region.min, int3::new(-2, -1, 0));
+    assert_eq!(region.max, int3::new(3, 1, 2));
+    assert_eq!(region.size(), int3::new(6, 3, 3));
+    assert_eq!(region.volume(), 54);
+    assert_eq!(BlockRegion::sized(region.min, region.size()), region);
+
+    assert!(region.is_shell(int3::new(-2, 0, 1)));
+    assert!(!region.is_shell(int3::new(0, 0, 1)));
+    assert!(!region.is_shell(int3::new(4, 0, 1)));
+}
+
+#[test]
+fn test_clipboard_rotate()
+{
+    let pal = palette();
+    let clip = numbered(int3::new(4, 2, 3), &pal);
+
+    let once = clip.rotate(1, &pal);
+    assert_eq!(once.size(), int3::new(3, 2, 4));
+    assert_eq!(once.get(int3::new(2, 0, 0)), clip.get(int3::zeros()));
+    assert_eq!(once.get(int3::new(0, 1, 3)), clip.get(int3::new(3, 1, 2)));
+
+    assert_eq!(clip.rotate(4, &pal), clip);
+    assert_eq!(clip.rotate(-1, &pal), clip.rotate(3, &pal));
+    assert_eq!(clip.mirror(0, &pal).mirror(0, &pal), clip);
+    assert_eq!(clip.mirror(2, &pal).get(int3::zeros()), clip.get(int3::new(0, 0, 2)));
+}
+
+#[test]
+fn test_clipboard_orient()
+{
+    let pal = palette();
+    let ents = BlockEntities::default();
+
+    let log = pal.block("log").unwrap();
+    let log = UnpackedBlock::new(log, int3::zeros(), &pal, &ents)
+        .with_property("axis", Axis::X)
+        .unwrap();
+
+    let mut clip = Clipboard::new(int3::repeat(1));
+    clip.set(int3::zeros(), Some(log));
+
+    let turned = clip.rotate(1, &pal).get(int3::zeros()).unwrap();
+    let axis: Axis = UnpackedBlock::new(turned, int3::zeros(), &pal, &ents)
+        .get_property("axis")
+        .unwrap();
+
+    assert_eq!(axis, Axis::Z);
+    assert_eq!(clip.rotate(2, &pal), clip);
+}
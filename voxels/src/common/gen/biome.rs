@@ -0,0 +1,178 @@
+use ezmath::*;
+use noise::{ NoiseFn, Perlin, Seedable };
+
+use crate::common::chunk::{ CBlockBuffer, BlockBufferIndex };
+use crate::common::block::PackedBlock;
+use crate::common::CHUNK_SIZE;
+use super::WorldGenerator;
+
+/// a kind of terrain, picked by the climate of its columns
+#[derive(Debug, Clone)]
+pub struct Biome
+{
+    /// name of this biome, for display
+    pub name: String,
+
+    /// temperature(-1..1) this biome thrives in
+    pub temperature: f64,
+    /// humidity(-1..1) this biome thrives in
+    pub humidity: f64,
+
+    /// average height of the surface
+    pub height: f64,
+    /// up and down delta from the average height
+    pub amplitude: f64,
+    /// horizontal size, in blocks, of the terrain's features
+    pub frequency: f64,
+
+    /// block at the surface, ie. grass
+    pub surface: PackedBlock,
+    /// blocks right under the surface, ie. dirt
+    pub subsurface: PackedBlock,
+    /// number of subsurface blocks
+    pub depth: i32,
+    /// blocks below the subsurface, ie. stone
+    pub filler: PackedBlock,
+}
+
+/// generator for a world of biomes. each column's climate,
+/// its temperature and humidity, comes from low frequency
+/// noise, and picks the biome closest to it. heights are
+/// a weighted mix of every biome's, so they blend smoothly
+/// where biomes meet
+pub struct BiomeGenerator
+{
+    /// biomes that can be generated, there must be at
+    /// least one
+    pub biomes: Vec<Biome>,
+    /// horizontal size, in blocks, of climate regions
+    pub climate_frequency: f64,
+    /// distance in climate over which biome heights blend
+    pub blend: f64,
+
+    temperature: Perlin,
+    humidity: Perlin,
+    terrain: Perlin,
+}
+
+impl BiomeGenerator
+{
+    /// create a new biome generator, given its seed and biomes
+    pub fn new(seed: u32, biomes: Vec<Biome>) -> Self
+    {
+        assert!(!biomes.is_empty(), "biome generator needs at least one biome!");
+
+        Self
+        {
+            biomes,
+            climate_frequency: 300.0,
+            blend: 0.2,
+
+            temperature: Perlin::new().set_seed(seed.wrapping_add(1)),
+            humidity: Perlin::new().set_seed(seed.wrapping_add(2)),
+            terrain: Perlin::new().set_seed(seed),
+        }
+    }
+
+    /// get the temperature and humidity of a global column
+    pub fn climate(&self, x: i32, z: i32) -> (f64, f64)
+    {
+        let p = [x as f64 / self.climate_frequency, z as f64 / self.climate_frequency];
+
+        (self.temperature.get(p), self.humidity.get(p))
+    }
+
+    /// get the biome of a global column
+    pub fn biome_at(&self, x: i32, z: i32) -> &Biome
+    {
+        let (t, h) = self.climate(x, z);
+
+        self.biomes
+            .iter()
+            .min_by(|a, b| a.distance(t, h).partial_cmp(&b.distance(t, h)).unwrap())
+            .unwrap()
+    }
+
+    /// height of the surface of a global column, blended
+    /// between the biomes whose climate is nearby
+    pub fn height(&self, x: i32, z: i32) -> i32
+    {
+        let (t, h) = self.climate(x, z);
+
+        let mut sum = 0.0;
+        let mut total = 0.0;
+        for biome in &self.biomes
+        {
+            let d = biome.distance(t, h) / self.blend;
+            let w = (-d * d).exp();
+
+            let n = self.terrain.get([x as f64 / biome.frequency, z as f64 / biome.frequency]);
+
+            sum += w * (biome.height + n * biome.amplitude);
+            total += w;
+        }
+
+        // far from every biome, fall back to the closest
+        if total < 1e-9
+        {
+            let biome = self.biome_at(x, z);
+
+            return biome.height as i32;
+        }
+        (sum / total) as i32
+    }
+}
+
+impl Biome
+{
+    /// distance from this biome's ideal climate
+    fn distance(&self, temperature: f64, humidity: f64) -> f64
+    {
+        let t = self.temperature - temperature;
+        let h = self.humidity - humidity;
+
+        (t * t + h * h).sqrt()
+    }
+}
+
+impl WorldGenerator for BiomeGenerator
+{
+    fn generate(&self, pos: int3, blocks: &mut CBlockBuffer)
+    {
+        for rx in 0..CHUNK_SIZE as i32
+        {
+            for rz in 0..CHUNK_SIZE as i32
+            {
+                let (x, z) = (rx + pos.x, rz + pos.z);
+
+                let biome = self.biome_at(x, z);
+
+                // relative height
+                let rh = self.height(x, z) - pos.y;
+
+                for ry in 0..rh.min(CHUNK_SIZE as i32)
+                {
+                    let block = if ry == rh - 1
+                    {
+                        biome.surface
+                    }
+                    else if ry >= rh - 1 - biome.depth
+                    {
+                        biome.subsurface
+                    }
+                    else
+                    {
+                        biome.filler
+                    };
+
+                    blocks.set_packed((rx, ry, rz), block);
+                }
+            }
+        }
+    }
+
+    fn biome(&self, x: i32, z: i32) -> Option<&Biome>
+    {
+        Some(self.biome_at(x, z))
+    }
+}
@@ -0,0 +1,146 @@
+use ezmath::*;
+
+use crate::common::chunk::{ CBlockBuffer, BlockBufferIndex };
+use crate::common::block::PackedBlock;
+use crate::common::CHUNK_SIZE;
+use super::Rng;
+
+/// carves worm-like cave tunnels out of generated terrain.
+/// the world is split into cubic cells, each of which may
+/// start a tunnel. a tunnel depends only on the seed and its
+/// cell, so every chunk it crosses carves the same tunnel
+#[derive(Debug, Clone)]
+pub struct CaveCarver
+{
+    /// size of a cell, in blocks per axis
+    pub cell: i32,
+    /// chance(0..1) of a cell starting a tunnel
+    pub chance: f64,
+    /// number of one block steps in a tunnel
+    pub length: u32,
+    /// smallest and biggest radius of a tunnel
+    pub radius: (f64, f64),
+    /// tunnels only start below this height
+    pub max_y: i32,
+
+    seed: u32,
+}
+
+impl CaveCarver
+{
+    /// create a new cave carver given its seed, with the
+    /// default tunnel shape
+    pub fn new(seed: u32) -> Self
+    {
+        Self
+        {
+            cell: 64,
+            chance: 0.6,
+            length: 96,
+            radius: (1.5, 3.5),
+            max_y: 0,
+            seed,
+        }
+    }
+
+    /// carve the tunnels crossing the chunk at `pos` out of
+    /// its blocks
+    pub fn carve(&self, pos: int3, blocks: &mut CBlockBuffer)
+    {
+        // farthest a tunnel can reach from its start
+        let reach = self.length as i32 + self.radius.1.ceil() as i32;
+
+        let min = (pos - int3::repeat(reach)).map(|c| c.div_euclid(self.cell));
+        let max = (pos + int3::repeat(CHUNK_SIZE as i32 + reach)).map(|c| c.div_euclid(self.cell));
+
+        for cx in min.x..=max.x
+        {
+            for cy in min.y..=max.y
+            {
+                for cz in min.z..=max.z
+                {
+                    self.tunnel(int3::new(cx, cy, cz), pos, blocks);
+                }
+            }
+        }
+    }
+
+    /// walk the tunnel of a cell, if it has one, and carve
+    /// the part of it that's in the chunk at `pos`
+    fn tunnel(&self, cell: int3, pos: int3, blocks: &mut CBlockBuffer)
+    {
+        let mut rng = Rng::new(self.seed, cell);
+        if rng.next() > self.chance
+        {
+            return;
+        }
+
+        // start somewhere in the cell
+        let mut p = float3::new
+        (
+            ((cell.x * self.cell) as f64 + rng.next() * self.cell as f64) as f32,
+            ((cell.y * self.cell) as f64 + rng.next() * self.cell as f64) as f32,
+            ((cell.z * self.cell) as f64 + rng.next() * self.cell as f64) as f32,
+        );
+        if p.y as i32 > self.max_y
+        {
+            return;
+        }
+
+        let mut yaw = rng.next() * std::f64::consts::PI * 2.0;
+        let mut pitch = (rng.next() - 0.5) * 0.5;
+
+        let chunk_min = float3::new(pos.x as f32, pos.y as f32, pos.z as f32);
+        let chunk_max = chunk_min + float3::repeat(CHUNK_SIZE as f32);
+
+        for step in 0..self.length
+        {
+            // radius swells in the middle of the tunnel
+            let t = step as f64 / self.length as f64;
+            let r = (self.radius.0 + (self.radius.1 - self.radius.0) * (t * std::f64::consts::PI).sin()) as f32;
+
+            // only carve where the sphere touches the chunk
+            if (0..3).all(|a| p[a] + r >= chunk_min[a] && p[a] - r < chunk_max[a])
+            {
+                carve_sphere(p - chunk_min, r, blocks);
+            }
+
+            // wander around, mostly horizontally
+            yaw += (rng.next() - 0.5) * 0.6;
+            pitch = (pitch + (rng.next() - 0.5) * 0.3) * 0.9;
+
+            p += float3::new
+            (
+                (yaw.cos() * pitch.cos()) as f32,
+                pitch.sin() as f32,
+                (yaw.sin() * pitch.cos()) as f32,
+            );
+        }
+    }
+}
+
+/// set every block within a sphere to air, given its centre
+/// relative to the chunk
+fn carve_sphere(centre: float3, r: f32, blocks: &mut CBlockBuffer)
+{
+    const SIZE: i32 = CHUNK_SIZE as i32;
+
+    let lo = |c: f32| ((c - r).floor() as i32).max(0);
+    let hi = |c: f32| ((c + r).ceil() as i32).min(SIZE - 1);
+
+    for x in lo(centre.x)..=hi(centre.x)
+    {
+        for y in lo(centre.y)..=hi(centre.y)
+        {
+            for z in lo(centre.z)..=hi(centre.z)
+            {
+                let d = float3::new(x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5) - centre;
+
+                if d.dot(&d) <= r * r
+                {
+                    blocks.set_packed((x, y, z), PackedBlock::default());
+                }
+            }
+        }
+    }
+}
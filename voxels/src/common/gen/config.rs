@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+use ezmath::*;
+
+use crate::common::block::{ RBlockPalette, PackedBlock, UnpackedBlock, BlockEntities, Axis };
+use crate::common::schematic::{ Schematic, VoxFile };
+use super::*;
+
+/// description of a world, loaded from a RON file so it can
+/// be tuned without recompiling: its base terrain, then the
+/// features decorating it, in order. blocks are referred to
+/// by their text ID
+/// ```ron
+/// (terrain: Void, features: [Tree(log: "log", leaves: "leaves", ...)])
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorldConfig
+{
+    terrain: GeneratorConfig,
+    #[serde(default)]
+    features: Vec<FeatureConfig>,
+}
+
+/// description of the base terrain of a world
+/// ```ron
+/// Noise(sea_level: 10.0, delta: 5.0, frequency: 15.0, top: "grass", fill: "dirt")
+/// Biomes(climate_frequency: 300.0, blend: 0.2, biomes: [(name: "plains", ...)])
+/// Density(amplitude: 12.0, frequency: 24.0, falloff: 1.0, caves: Some((...)), climate_frequency: 300.0, ...)
+/// Flat(layers: ["dirt", "dirt", "grass"])
+/// Void
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub enum GeneratorConfig
+{
+    /// see `NoiseGenerator`
+    Noise { sea_level: f64, delta: f64, frequency: f64, top: String, fill: String },
+    /// see `BiomeGenerator`
+    Biomes { climate_frequency: f64, blend: f64, biomes: Vec<BiomeConfig> },
+    /// see `DensityGenerator`
+    Density
+    {
+        amplitude: f64,
+        frequency: f64,
+        falloff: f64,
+        #[serde(default)]
+        caves: Option<CaveConfig>,
+
+        climate_frequency: f64,
+        blend: f64,
+        biomes: Vec<BiomeConfig>,
+    },
+    /// see `FlatGenerator`
+    Flat { layers: Vec<String> },
+    /// see `VoidGenerator`
+    Void,
+}
+
+/// description of a world feature
+/// ```ron
+/// Tree(log: "log", leaves: "leaves", on: ["grass"], tries: 4, chance: 0.3, height: (4, 6), radius: 2)
+/// Ore(ore: "coal_ore", host: "stone", veins: 6, size: 8, min_y: -64, max_y: 16)
+/// Boulder(block: "stone", on: ["grass"], chance: 0.05, radius: (1.5, 2.5))
+/// Structure(path: "structures/hut.vxs", on: ["grass"], chance: 0.02, sink: 1)
+/// Structure(path: "structures/rock.vox", on: ["grass"], chance: 0.01, sink: 0, model: 0, mapping: { 1: "stone" })
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub enum FeatureConfig
+{
+    /// see `TreeFeature`
+    Tree { log: String, leaves: String, on: Vec<String>, tries: u32, chance: f64, height: (i32, i32), radius: i32 },
+    /// see `OreFeature`
+    Ore { ore: String, host: String, veins: u32, size: u32, min_y: i32, max_y: i32 },
+    /// see `BoulderFeature`
+    Boulder { block: String, on: Vec<String>, chance: f64, radius: (f64, f64) },
+    /// see `StructureFeature`. the schematic's path is relative
+    /// to the configuration file. MagicaVoxel models(.vox) are
+    /// imported too: `model` picks one out of the file, and its
+    /// colours become the block with the nearest colour, unless
+    /// `mapping` names a block for their palette index
+    Structure
+    {
+        path: String,
+        on: Vec<String>,
+        chance: f64,
+        sink: i32,
+        #[serde(default)]
+        model: usize,
+        #[serde(default)]
+        mapping: HashMap<u8, String>,
+    },
+}
+
+/// description of a biome, see `Biome`
+#[derive(Debug, Clone, Deserialize)]
+pub struct BiomeConfig
+{
+    name: String,
+    temperature: f64,
+    humidity: f64,
+    height: f64,
+    amplitude: f64,
+    frequency: f64,
+    surface: String,
+    subsurface: String,
+    depth: i32,
+    filler: String,
+}
+
+/// description of cave tunnels, see `CaveCarver`. cells must
+/// be at least one block wide, and the radius goes from
+/// smallest to biggest
+#[derive(Debug, Clone, Deserialize)]
+pub struct CaveConfig
+{
+    cell: i32,
+    chance: f64,
+    length: u32,
+    radius: (f64, f64),
+    max_y: i32,
+}
+
+impl RWorldGenerator
+{
+    /// load the world generator described by a configuration
+    /// file, see `WorldConfig`
+    pub fn load<P: AsRef<Path>>(path: P, seed: u32, pal: &RBlockPalette) -> Result<Self, GenError>
+    {
+        let path = path.as_ref();
+
+        let src = std::fs::read_to_string(path)
+            .map_err(|err| GenError::Io { path: path.to_path_buf(), err })?;
+        let config: WorldConfig = ron::de::from_str(&src)
+            .map_err(|e| GenError::Parse { path: path.to_path_buf(), msg: e.to_string() })?;
+
+        let block = |id: &str| -> Result<PackedBlock, GenError>
+        {
+            pal.block(id).ok_or_else(|| GenError::UnknownBlock { path: path.to_path_buf(), id: id.to_string() })
+        };
+        // values that are divided by must be positive
+        let positive = |field: &str, value: f64| -> Result<f64, GenError>
+        {
+            if value > 0.0
+            {
+                Ok(value)
+            }
+            else
+            {
+                Err(GenError::Invalid { path: path.to_path_buf(), field: field.to_string(), value })
+            }
+        };
+        let blocks = |ids: &[String]| -> Result<Vec<PackedBlock>, GenError>
+        {
+            ids.iter().map(|id| block(id)).collect()
+        };
+        let biomes = |climate_frequency: f64, blend: f64, biomes: Vec<BiomeConfig>| -> Result<BiomeGenerator, GenError>
+        {
+            if biomes.is_empty()
+            {
+                return Err(GenError::Parse { path: path.to_path_buf(), msg: "there must be at least one biome".to_string() });
+            }
+
+            let biomes = biomes
+                .into_iter()
+                .map(|b| Ok(Biome
+                {
+                    surface: block(&b.surface)?,
+                    subsurface: block(&b.subsurface)?,
+                    filler: block(&b.filler)?,
+                    frequency: positive(&format!("{}.frequency", b.name), b.frequency)?,
+
+                    name: b.name,
+                    temperature: b.temperature,
+                    humidity: b.humidity,
+                    height: b.height,
+                    amplitude: b.amplitude,
+                    depth: b.depth,
+                }))
+                .collect::<Result<_, GenError>>()?;
+
+            let mut gen = BiomeGenerator::new(seed, biomes);
+
+            gen.climate_frequency = positive("climate_frequency", climate_frequency)?;
+            gen.blend = positive("blend", blend)?;
+
+            Ok(gen)
+        };
+
+        let gen = match config.terrain
+        {
+            GeneratorConfig::Noise { sea_level, delta, frequency, top, fill } =>
+            {
+                let mut gen = NoiseGenerator::new(seed, block(&top)?, block(&fill)?);
+
+                gen.sea_level = sea_level;
+                gen.delta = delta;
+                gen.frequency = frequency;
+
+                Self::new(seed, gen)
+            }
+            GeneratorConfig::Biomes { climate_frequency, blend, biomes: b } =>
+            {
+                Self::new(seed, biomes(climate_frequency, blend, b)?)
+            }
+            GeneratorConfig::Density { amplitude, frequency, falloff, caves, climate_frequency, blend, biomes: b } =>
+            {
+                let mut gen = DensityGenerator::new(seed, biomes(climate_frequency, blend, b)?);
+
+                gen.amplitude = amplitude;
+                gen.frequency = frequency;
+                gen.falloff = falloff;
+                gen.caves = match caves
+                {
+                    Some(c) if c.cell <= 0 =>
+                    {
+                        return Err(GenError::Parse { path: path.to_path_buf(), msg: format!("cave cells must be at least one block wide, not {}", c.cell) });
+                    }
+                    Some(c) if c.radius.0 > c.radius.1 =>
+                    {
+                        return Err(GenError::Parse { path: path.to_path_buf(), msg: format!("cave radius {:?} must be (smallest, biggest)", c.radius) });
+                    }
+                    Some(c) =>
+                    {
+                        let mut caves = CaveCarver::new(seed);
+
+                        caves.cell = c.cell;
+                        caves.chance = c.chance;
+                        caves.length = c.length;
+                        caves.radius = c.radius;
+                        caves.max_y = c.max_y;
+                        Some(caves)
+                    }
+                    None => None,
+                };
+
+                Self::new(seed, gen)
+            }
+            GeneratorConfig::Flat { layers } =>
+            {
+                let layers = layers
+                    .iter()
+                    .map(|id| block(id))
+                    .collect::<Result<_, _>>()?;
+
+                Self::new(seed, FlatGenerator { layers })
+            }
+            GeneratorConfig::Void => Self::new(seed, VoidGenerator),
+        };
+
+        let features = config.features
+            .into_iter()
+            .map(|f| Ok(match f
+            {
+                FeatureConfig::Tree { log, leaves, on, tries, chance, height, radius } =>
+                {
+                    // trunks stand upright, if the log can rotate
+                    let log = block(&log)?;
+                    let log = UnpackedBlock::new(log, int3::zeros(), pal, &BlockEntities::default())
+                        .with_property("axis", Axis::Y)
+                        .unwrap_or(log);
+
+                    Box::new(TreeFeature { log, leaves: block(&leaves)?, on: blocks(&on)?, tries, chance, height, radius }) as Box<dyn Feature>
+                }
+                FeatureConfig::Ore { ore, host, veins, size, min_y, max_y } =>
+                {
+                    Box::new(OreFeature { ore: block(&ore)?, host: block(&host)?, veins, size, min_y, max_y })
+                }
+                FeatureConfig::Boulder { block: b, on, chance, radius } =>
+                {
+                    Box::new(BoulderFeature { block: block(&b)?, on: blocks(&on)?, chance, radius })
+                }
+                FeatureConfig::Structure { path: file, on, chance, sink, model, mapping } =>
+                {
+                    let file = path.parent().unwrap_or_else(|| Path::new("")).join(file);
+                    let parse = |msg: String| GenError::Parse { path: path.to_path_buf(), msg };
+
+                    let clip = if file.extension().map_or(false, |ext| ext == "vox")
+                    {
+                        let mapping = mapping
+                            .iter()
+                            .map(|(index, id)| Ok((*index, block(id)?)))
+                            .collect::<Result<_, GenError>>()?;
+
+                        VoxFile::load(&file)
+                            .map_err(|e| parse(e.to_string()))?
+                            .to_clipboard(model, pal, &mapping)
+                            .ok_or_else(|| parse(format!("{} has no model {}", file.display(), model)))?
+                    }
+                    else
+                    {
+                        Schematic::load(&file)
+                            .map_err(|e| parse(e.to_string()))?
+                            .to_clipboard(pal)
+                    };
+
+                    let rotations = (0..4).map(|turns| clip.rotate(turns, pal)).collect();
+
+                    Box::new(StructureFeature { rotations, on: blocks(&on)?, chance, sink })
+                }
+            }))
+            .collect::<Result<_, GenError>>()?;
+
+        Ok(gen.with_features(features))
+    }
+}
@@ -0,0 +1,122 @@
+use ezmath::*;
+
+use crate::common::chunk::{ CBlockBuffer, BlockBufferIndex, BlockWrite };
+use crate::common::block::PackedBlock;
+use crate::common::edit::Clipboard;
+use crate::common::CHUNK_SIZE;
+use super::Rng;
+
+/// a world feature, placed over the base terrain after it's
+/// generated, like trees or ore veins. features decorate one
+/// chunk at a time, on the chunk workers, but may reach into
+/// its neighbours. blocks placed further away are dropped
+pub trait Feature: Send + Sync
+{
+    /// decorate a chunk. `rng` is seeded from the chunk and
+    /// the feature, so decorations don't depend on the order
+    /// chunks are generated in
+    fn decorate(&self, rng: &mut Rng, deco: &mut Decoration);
+}
+
+/// blocks of a chunk being decorated. blocks written outside
+/// of it are kept as block writes, for the neighbour they land
+/// in to apply when it's generated
+pub struct Decoration<'a>
+{
+    pos: int3,
+    blocks: &'a mut CBlockBuffer,
+    spilled: Vec<BlockWrite>,
+}
+
+impl<'a> Decoration<'a>
+{
+    /// decorate the blocks of the chunk at `pos`
+    pub fn new(pos: int3, blocks: &'a mut CBlockBuffer) -> Self
+    {
+        Self { pos, blocks, spilled: Vec::new() }
+    }
+
+    /// get the position of the chunk being decorated
+    pub fn pos(&self) -> int3
+    {
+        self.pos
+    }
+
+    /// is a global position within the chunk being decorated?
+    pub fn contains(&self, pos: int3) -> bool
+    {
+        let r = pos - self.pos;
+
+        (0..3).all(|a| r[a] >= 0 && r[a] < CHUNK_SIZE as i32)
+    }
+
+    /// get a block of the chunk being decorated, given its
+    /// global position. blocks outside of it can't be known
+    pub fn get(&self, pos: int3) -> Option<PackedBlock>
+    {
+        if !self.contains(pos)
+        {
+            return None;
+        }
+        Some(self.blocks.get_packed(pos - self.pos))
+    }
+
+    /// write a block at a global position, only over the block
+    /// `replaces`. writes outside of the chunk are spilled
+    pub fn replace(&mut self, pos: int3, replaces: PackedBlock, block: PackedBlock)
+    {
+        let write = BlockWrite { pos, block, replaces };
+
+        if self.contains(pos)
+        {
+            write.apply(self.blocks);
+        }
+        else
+        {
+            self.spilled.push(write);
+        }
+    }
+
+    /// write a block at a global position, only over air
+    pub fn place(&mut self, pos: int3, block: PackedBlock)
+    {
+        self.replace(pos, PackedBlock::default(), block)
+    }
+
+    /// place copied blocks, with their min corner at a global
+    /// position, only over air. air and blocks that weren't
+    /// copied are skipped
+    pub fn paste(&mut self, clip: &Clipboard, at: int3)
+    {
+        for (pos, block) in clip.iter()
+        {
+            match block
+            {
+                Some(block) if block != PackedBlock::default() => self.place(at + pos, block),
+                _ => {},
+            }
+        }
+    }
+
+    /// find the surface of a column of the chunk, given its
+    /// position relative to the chunk: the global position of
+    /// the highest block with air above it, if it's one of `on`
+    pub fn surface(&self, rx: i32, rz: i32, on: &[PackedBlock]) -> Option<int3>
+    {
+        let air = PackedBlock::default();
+
+        // the block above the top layer is in another chunk
+        (0..CHUNK_SIZE as i32 - 1)
+            .rev()
+            .map(|ry| self.pos + int3::new(rx, ry, rz))
+            .find(|p| self.get(*p) != Some(air) && self.get(*p + int3::new(0, 1, 0)) == Some(air))
+            .filter(|p| self.get(*p).map_or(false, |b| on.contains(&b)))
+    }
+
+    /// done decorating, get the block writes that landed in
+    /// other chunks
+    pub fn finish(self) -> Vec<BlockWrite>
+    {
+        self.spilled
+    }
+}
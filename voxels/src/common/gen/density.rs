@@ -0,0 +1,121 @@
+use ezmath::*;
+use noise::{ NoiseFn, Perlin, Seedable };
+
+use crate::common::chunk::{ CBlockBuffer, BlockBufferIndex };
+use crate::common::CHUNK_SIZE;
+use super::{ WorldGenerator, BiomeGenerator, Biome, CaveCarver };
+
+/// generator for 3D terrain, with cliffs and overhangs. a
+/// block is solid where its density is positive:
+/// ```text
+/// density = noise(x, y, z) * amplitude + (height - y) * falloff
+/// ```
+/// where `height` is the surface of the biome generator's
+/// heightmap, which also picks each column's blocks. caves
+/// are carved out afterwards, if any
+pub struct DensityGenerator
+{
+    /// heights and blocks of the terrain
+    pub biomes: BiomeGenerator,
+    /// tunnels carved out of the terrain
+    pub caves: Option<CaveCarver>,
+
+    /// strength of the 3D noise
+    pub amplitude: f64,
+    /// size, in blocks, of the 3D noise's features
+    pub frequency: f64,
+    /// how fast density drops with height above the
+    /// heightmap. lower values give wilder terrain
+    pub falloff: f64,
+
+    noise: Perlin,
+}
+
+impl DensityGenerator
+{
+    /// create a new density generator over biomes, given its
+    /// seed, with the default shape and no caves
+    pub fn new(seed: u32, biomes: BiomeGenerator) -> Self
+    {
+        Self
+        {
+            biomes,
+            caves: None,
+            amplitude: 12.0,
+            frequency: 24.0,
+            falloff: 1.0,
+            noise: Perlin::new().set_seed(seed.wrapping_add(3)),
+        }
+    }
+
+    /// density of a global block, given the height of its
+    /// column
+    pub fn density(&self, x: i32, y: i32, z: i32, height: i32) -> f64
+    {
+        let p = [x as f64 / self.frequency, y as f64 / self.frequency, z as f64 / self.frequency];
+
+        self.noise.get(p) * self.amplitude + (height - y) as f64 * self.falloff
+    }
+}
+
+impl WorldGenerator for DensityGenerator
+{
+    fn generate(&self, pos: int3, blocks: &mut CBlockBuffer)
+    {
+        const SIZE: i32 = CHUNK_SIZE as i32;
+
+        for rx in 0..SIZE
+        {
+            for rz in 0..SIZE
+            {
+                let (x, z) = (rx + pos.x, rz + pos.z);
+
+                let biome = self.biomes.biome_at(x, z);
+                let height = self.biomes.height(x, z);
+
+                // scan down from above the chunk, so blocks near its
+                // top know how deep they are under the surface
+                let top = SIZE + biome.depth + 1;
+                let mut depth = biome.depth + 2;
+
+                for ry in (0..top).rev()
+                {
+                    if self.density(x, ry + pos.y, z, height) <= 0.0
+                    {
+                        depth = 0;
+                        continue;
+                    }
+                    depth += 1;
+
+                    if ry < SIZE
+                    {
+                        let block = if depth == 1
+                        {
+                            biome.surface
+                        }
+                        else if depth <= biome.depth + 1
+                        {
+                            biome.subsurface
+                        }
+                        else
+                        {
+                            biome.filler
+                        };
+
+                        blocks.set_packed((rx, ry, rz), block);
+                    }
+                }
+            }
+        }
+
+        if let Some(caves) = &self.caves
+        {
+            caves.carve(pos, blocks);
+        }
+    }
+
+    fn biome(&self, x: i32, z: i32) -> Option<&Biome>
+    {
+        Some(self.biomes.biome_at(x, z))
+    }
+}
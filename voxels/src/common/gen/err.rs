@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+use std::fmt;
+
+/// errors that can occur while loading the world
+/// generator's configuration
+#[derive(Debug)]
+pub enum GenError
+{
+    /// the configuration file couldn't be read
+    Io { path: PathBuf, err: std::io::Error },
+    /// the configuration isn't valid RON, or is
+    /// missing fields
+    Parse { path: PathBuf, msg: String },
+    /// the configuration names a block the palette
+    /// doesn't have
+    UnknownBlock { path: PathBuf, id: String },
+    /// a value in the configuration is out of range, ie.
+    /// a frequency that isn't positive
+    Invalid { path: PathBuf, field: String, value: f64 },
+}
+
+impl fmt::Display for GenError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            GenError::Io { path, err } =>
+            {
+                write!(f, "couldn't read {}: {}", path.display(), err)
+            }
+            GenError::Parse { path, msg } =>
+            {
+                write!(f, "couldn't parse {}: {}", path.display(), msg)
+            }
+            GenError::UnknownBlock { path, id } =>
+            {
+                write!(f, "{} uses block \"{}\", which doesn't exist", path.display(), id)
+            }
+            GenError::Invalid { path, field, value } =>
+            {
+                write!(f, "{} has {} = {}, which must be greater than zero", path.display(), field, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GenError
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)>
+    {
+        match self
+        {
+            GenError::Io { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
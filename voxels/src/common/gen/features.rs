@@ -0,0 +1,221 @@
+use ezmath::*;
+
+use crate::common::block::PackedBlock;
+use crate::common::edit::Clipboard;
+use crate::common::CHUNK_SIZE;
+use super::{ Feature, Decoration, Rng };
+
+/// trees, grown on the surface. leaves may spill into the
+/// chunks around the trunk's
+#[derive(Debug, Clone)]
+pub struct TreeFeature
+{
+    /// block of the trunk, should be upright
+    pub log: PackedBlock,
+    /// block of the canopy
+    pub leaves: PackedBlock,
+    /// blocks trees grow on
+    pub on: Vec<PackedBlock>,
+    /// number of columns tried per chunk
+    pub tries: u32,
+    /// chance(0..1) of a tried column growing a tree
+    pub chance: f64,
+    /// shortest and tallest trunk
+    pub height: (i32, i32),
+    /// radius of the canopy
+    pub radius: i32,
+}
+
+/// veins of ore, which replace a host block underground
+#[derive(Debug, Clone)]
+pub struct OreFeature
+{
+    /// block of the ore
+    pub ore: PackedBlock,
+    /// block the ore replaces
+    pub host: PackedBlock,
+    /// number of veins per chunk
+    pub veins: u32,
+    /// number of blocks in a vein
+    pub size: u32,
+    /// veins only start within these heights
+    pub min_y: i32,
+    pub max_y: i32,
+}
+
+/// boulders, lying on the surface
+#[derive(Debug, Clone)]
+pub struct BoulderFeature
+{
+    /// block of the boulders
+    pub block: PackedBlock,
+    /// blocks boulders lie on
+    pub on: Vec<PackedBlock>,
+    /// chance(0..1) of a chunk having a boulder
+    pub chance: f64,
+    /// smallest and biggest radius of a boulder
+    pub radius: (f64, f64),
+}
+
+/// prefab structures, ie. loaded from schematics, placed
+/// on the surface in a random rotation
+#[derive(Debug, Clone)]
+pub struct StructureFeature
+{
+    /// the structure, in each of its four rotations around
+    /// the y axis
+    pub rotations: Vec<Clipboard>,
+    /// blocks structures are placed on
+    pub on: Vec<PackedBlock>,
+    /// chance(0..1) of a chunk having a structure
+    pub chance: f64,
+    /// how many blocks deep structures are sunk into the
+    /// ground
+    pub sink: i32,
+}
+
+impl Feature for TreeFeature
+{
+    fn decorate(&self, rng: &mut Rng, deco: &mut Decoration)
+    {
+        for _ in 0..self.tries
+        {
+            // always roll, so every try uses the same numbers
+            let rx = rng.range(0, CHUNK_SIZE as i32 - 1);
+            let rz = rng.range(0, CHUNK_SIZE as i32 - 1);
+            let height = rng.range(self.height.0, self.height.1);
+
+            if rng.next() > self.chance
+            {
+                continue;
+            }
+            let base = match deco.surface(rx, rz, &self.on)
+            {
+                Some(p) => p + int3::new(0, 1, 0),
+                None => continue,
+            };
+
+            // trunk
+            for y in 0..height
+            {
+                deco.place(base + int3::new(0, y, 0), self.log);
+            }
+
+            // canopy, a rough ball around the top of the trunk
+            let top = base + int3::new(0, height - 1, 0);
+            let r = self.radius;
+
+            for x in -r..=r
+            {
+                for y in -1..=r
+                {
+                    for z in -r..=r
+                    {
+                        let d = x * x + y * y + z * z;
+
+                        // round off the corners, a bit randomly
+                        if d > r * r + 1 || (d == r * r + 1 && rng.next() < 0.5)
+                        {
+                            continue;
+                        }
+                        deco.place(top + int3::new(x, y, z), self.leaves);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Feature for OreFeature
+{
+    fn decorate(&self, rng: &mut Rng, deco: &mut Decoration)
+    {
+        let pos = deco.pos();
+
+        for _ in 0..self.veins
+        {
+            let mut p = pos + int3::new
+            (
+                rng.range(0, CHUNK_SIZE as i32 - 1),
+                rng.range(0, CHUNK_SIZE as i32 - 1),
+                rng.range(0, CHUNK_SIZE as i32 - 1),
+            );
+            if p.y < self.min_y || p.y > self.max_y
+            {
+                continue;
+            }
+
+            // random walk, which may wander out of the chunk
+            for _ in 0..self.size
+            {
+                deco.replace(p, self.host, self.ore);
+
+                let axis = rng.range(0, 2) as usize;
+                p[axis] += if rng.next() < 0.5 { -1 } else { 1 };
+            }
+        }
+    }
+}
+
+impl Feature for BoulderFeature
+{
+    fn decorate(&self, rng: &mut Rng, deco: &mut Decoration)
+    {
+        let rx = rng.range(0, CHUNK_SIZE as i32 - 1);
+        let rz = rng.range(0, CHUNK_SIZE as i32 - 1);
+        let r = (self.radius.0 + rng.next() * (self.radius.1 - self.radius.0)) as f32;
+
+        if rng.next() > self.chance
+        {
+            return;
+        }
+        let centre = match deco.surface(rx, rz, &self.on)
+        {
+            Some(p) => p,
+            None => return,
+        };
+
+        let reach = r.ceil() as i32;
+        for x in -reach..=reach
+        {
+            for y in -reach..=reach
+            {
+                for z in -reach..=reach
+                {
+                    let d = float3::new(x as f32, y as f32, z as f32);
+
+                    if d.dot(&d) <= r * r
+                    {
+                        deco.place(centre + int3::new(x, y, z), self.block);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Feature for StructureFeature
+{
+    fn decorate(&self, rng: &mut Rng, deco: &mut Decoration)
+    {
+        let rx = rng.range(0, CHUNK_SIZE as i32 - 1);
+        let rz = rng.range(0, CHUNK_SIZE as i32 - 1);
+        let turn = rng.range(0, self.rotations.len() as i32 - 1);
+
+        if self.rotations.is_empty() || rng.next() > self.chance
+        {
+            return;
+        }
+        let ground = match deco.surface(rx, rz, &self.on)
+        {
+            Some(p) => p,
+            None => return,
+        };
+
+        // centred on the column, standing on the ground
+        let clip = &self.rotations[turn as usize];
+        let size = clip.size();
+
+        deco.paste(clip, ground + int3::new(-size.x / 2, 1 - self.sink, -size.z / 2));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,38 @@
+use ezmath::*;
+
+use crate::common::chunk::{ CBlockBuffer, BlockBufferIndex };
+use crate::common::block::PackedBlock;
+use crate::common::CHUNK_SIZE;
+use super::WorldGenerator;
+
+/// generator for a superflat world: the same layers of
+/// blocks everywhere, starting at y = 0, and air above
+#[derive(Debug, Clone, Default)]
+pub struct FlatGenerator
+{
+    /// blocks of each layer, bottom to top
+    pub layers: Vec<PackedBlock>,
+}
+
+impl WorldGenerator for FlatGenerator
+{
+    fn generate(&self, pos: int3, blocks: &mut CBlockBuffer)
+    {
+        for ry in 0..CHUNK_SIZE as i32
+        {
+            let block = match self.layers.get((pos.y + ry) as usize)
+            {
+                Some(block) if pos.y + ry >= 0 => *block,
+                _ => continue,
+            };
+
+            for rx in 0..CHUNK_SIZE as i32
+            {
+                for rz in 0..CHUNK_SIZE as i32
+                {
+                    blocks.set_packed((rx, ry, rz), block);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::{ HashMap, VecDeque };
+use std::sync::{ Arc, Mutex };
+
+use ezmath::*;
+
+use crate::common::chunk::{ CBlockBuffer, BlockWrite };
+use crate::common::CHUNK_SIZE;
+use super::{ Biome, Feature, Decoration, Rng };
+
+/// fills in the blocks of new chunks. generators run on the
+/// chunk workers, so they must be thread-safe and shouldn't
+/// depend on the order chunks are generated in
+pub trait WorldGenerator: Send + Sync
+{
+    /// fill in the blocks of the chunk at `pos`, the position
+    /// of its min block. `blocks` starts out as all air
+    fn generate(&self, pos: int3, blocks: &mut CBlockBuffer);
+
+    /// get the biome of a global column, for generators
+    /// that have biomes
+    fn biome(&self, _x: i32, _z: i32) -> Option<&Biome>
+    {
+        None
+    }
+}
+
+/// resource for the generator of the world, chosen at startup:
+/// the base terrain, then the features decorating it. clones
+/// share their features and spilled blocks
+#[derive(Clone)]
+pub struct RWorldGenerator
+{
+    terrain: Arc<dyn WorldGenerator>,
+    features: Arc<[Box<dyn Feature>]>,
+    seed: u32,
+
+    spills: Arc<Mutex<SpillCache>>,
+}
+
+/// blocks the features of recently decorated chunks spilled
+/// into their neighbours. every chunk needs those of the 26
+/// around it, so they're kept rather than decorated again,
+/// up to a limit
+#[derive(Default)]
+struct SpillCache
+{
+    map: HashMap<int3, Arc<[BlockWrite]>>,
+    order: VecDeque<int3>,
+}
+
+impl RWorldGenerator
+{
+    /// create a new world generator resource, given the
+    /// world's seed, without features
+    pub fn new(seed: u32, terrain: impl WorldGenerator + 'static) -> Self
+    {
+        Self
+        {
+            terrain: Arc::new(terrain),
+            features: Vec::new().into(),
+            seed,
+
+            spills: Default::default(),
+        }
+    }
+
+    /// decorate the terrain with features, in order
+    pub fn with_features(mut self, features: Vec<Box<dyn Feature>>) -> Self
+    {
+        self.features = features.into();
+        self
+    }
+
+    /// generate the chunk at `pos`, the position of its min
+    /// block. its own features go first, then the blocks the
+    /// features of the 26 chunks around it spilled into it, in
+    /// order of their position. a block is only written over
+    /// the one it expects, so the first write wins. chunks are
+    /// the same whatever order they're generated in, and blocks
+    /// spilled further than a chunk away are dropped
+    pub fn generate(&self, pos: int3) -> CBlockBuffer
+    {
+        let (mut blocks, spilled) = self.decorate(pos);
+
+        if self.features.is_empty()
+        {
+            return blocks;
+        }
+        self.spills.lock().unwrap().insert(pos, spilled.into());
+
+        let size = CHUNK_SIZE as i32;
+        for x in -1..=1
+        {
+            for y in -1..=1
+            {
+                for z in -1..=1
+                {
+                    if (x, y, z) == (0, 0, 0)
+                    {
+                        continue;
+                    }
+                    let spilled = self.spilled(pos + int3::new(x, y, z) * size);
+
+                    for write in spilled.iter().filter(|w| w.chunk() == pos)
+                    {
+                        write.apply(&mut blocks);
+                    }
+                }
+            }
+        }
+        blocks
+    }
+
+    /// generate and decorate the chunk at `pos`, without what
+    /// other chunks spill into it. returns its blocks, and the
+    /// blocks its features placed in other chunks
+    fn decorate(&self, pos: int3) -> (CBlockBuffer, Vec<BlockWrite>)
+    {
+        let mut blocks = CBlockBuffer::new();
+        self.terrain.generate(pos, &mut blocks);
+
+        let mut deco = Decoration::new(pos, &mut blocks);
+        for (i, feature) in self.features.iter().enumerate()
+        {
+            // each feature rolls its own numbers
+            let mut rng = Rng::new(self.seed.wrapping_add(i as u32).wrapping_mul(0x9E37_79B9), pos);
+
+            feature.decorate(&mut rng, &mut deco);
+        }
+        let spilled = deco.finish();
+
+        (blocks, spilled)
+    }
+
+    /// get the blocks the chunk at `pos` spills into the ones
+    /// around it, decorating it again if they aren't cached
+    fn spilled(&self, pos: int3) -> Arc<[BlockWrite]>
+    {
+        if let Some(spilled) = self.spills.lock().unwrap().get(pos)
+        {
+            return spilled;
+        }
+
+        // not locked while decorating, other workers may need it
+        let spilled: Arc<[BlockWrite]> = self.decorate(pos).1.into();
+        self.spills.lock().unwrap().insert(pos, spilled.clone());
+
+        spilled
+    }
+
+    /// get the biome of a global column, if the world
+    /// has biomes
+    pub fn biome(&self, x: i32, z: i32) -> Option<&Biome>
+    {
+        self.terrain.biome(x, z)
+    }
+}
+
+impl SpillCache
+{
+    /// maximum number of chunks whose spilled blocks are kept
+    const CAPACITY: usize = 4096;
+
+    fn get(&self, pos: int3) -> Option<Arc<[BlockWrite]>>
+    {
+        self.map.get(&pos).cloned()
+    }
+
+    /// keep the blocks a chunk spilled, forgetting the oldest
+    /// chunk's if there are too many
+    fn insert(&mut self, pos: int3, spilled: Arc<[BlockWrite]>)
+    {
+        if self.map.insert(pos, spilled).is_some()
+        {
+            return;
+        }
+        self.order.push_back(pos);
+
+        if self.order.len() > Self::CAPACITY
+        {
+            let old = self.order.pop_front().unwrap();
+            self.map.remove(&old);
+        }
+    }
+}
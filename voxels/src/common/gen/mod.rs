@@ -0,0 +1,31 @@
+mod generator;
+mod config;
+mod terrain;
+mod biome;
+mod density;
+mod caves;
+mod decorate;
+mod features;
+mod rng;
+mod flat;
+mod void;
+mod err;
+
+pub use self::
+{
+    generator::*,
+    config::*,
+    terrain::*,
+    biome::*,
+    density::*,
+    caves::*,
+    decorate::*,
+    features::*,
+    rng::*,
+    flat::*,
+    void::*,
+    err::*,
+};
+
+#[cfg(test)]
+mod tests;
\ No newline at end of file
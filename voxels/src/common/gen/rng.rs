@@ -0,0 +1,46 @@
+use ezmath::*;
+
+/// small deterministic random number generator(splitmix64).
+/// generation must come out the same on every machine and in
+/// every order, so each cell or chunk seeds its own
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng
+{
+    /// seed a generator from the world seed and a position,
+    /// ie. a cell or a chunk
+    pub fn new(seed: u32, pos: int3) -> Self
+    {
+        let mut rng = Self(seed as u64);
+
+        for c in &[pos.x, pos.y, pos.z]
+        {
+            rng.0 ^= *c as u32 as u64;
+            rng.step();
+        }
+        rng
+    }
+
+    /// next random number within 0..1
+    pub fn next(&mut self) -> f64
+    {
+        (self.step() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// next random integer within min..=max
+    pub fn range(&mut self, min: i32, max: i32) -> i32
+    {
+        min + (self.next() * (max - min + 1) as f64) as i32
+    }
+
+    fn step(&mut self) -> u64
+    {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
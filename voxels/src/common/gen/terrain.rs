@@ -0,0 +1,75 @@
+use ezmath::*;
+use noise::{ NoiseFn, Perlin, Seedable };
+
+use crate::common::chunk::{ CBlockBuffer, BlockBufferIndex };
+use crate::common::block::PackedBlock;
+use crate::common::CHUNK_SIZE;
+use super::WorldGenerator;
+
+/// generator for rolling hills, from a 2D perlin heightmap
+#[derive(Clone)]
+pub struct NoiseGenerator
+{
+    /// height around which terrain is generated
+    pub sea_level: f64,
+    /// up and down delta from sea level at which terrain
+    /// is generated
+    pub delta: f64,
+    /// horizontal size, in blocks, of the noise's features
+    pub frequency: f64,
+
+    /// block at the surface, ie. grass
+    pub top: PackedBlock,
+    /// block under the surface, ie. dirt
+    pub fill: PackedBlock,
+
+    perlin: Perlin,
+}
+
+impl NoiseGenerator
+{
+    /// create a new noise generator, given its seed and
+    /// surface blocks, with the default shape
+    pub fn new(seed: u32, top: PackedBlock, fill: PackedBlock) -> Self
+    {
+        Self
+        {
+            sea_level: 10.0,
+            delta: 5.0,
+            frequency: 15.0,
+            top,
+            fill,
+            perlin: Perlin::new().set_seed(seed),
+        }
+    }
+
+    /// height of the terrain's surface at a global column
+    pub fn height(&self, x: i32, z: i32) -> i32
+    {
+        let n = self.perlin.get([x as f64 / self.frequency, z as f64 / self.frequency]);
+
+        (n * self.delta + self.sea_level) as i32
+    }
+}
+
+impl WorldGenerator for NoiseGenerator
+{
+    fn generate(&self, pos: int3, blocks: &mut CBlockBuffer)
+    {
+        // go through horizontal plane
+        for rx in 0..CHUNK_SIZE as i32
+        {
+            for rz in 0..CHUNK_SIZE as i32
+            {
+                // relative height
+                let rh = self.height(rx + pos.x, rz + pos.z) - pos.y;
+
+                // fill all 0..32 or none 0..-n blocks
+                for ry in 0..rh.min(CHUNK_SIZE as i32)
+                {
+                    blocks.set_packed((rx, ry, rz), if ry == rh - 1 { self.top } else { self.fill });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use ezmath::*;
+
+use crate::common::chunk::{ CBlockBuffer, BlockBufferIndex, ChunkPos };
+use crate::common::block::PackedBlock;
+use crate::common::CHUNK_VOLUME;
+use crate::common::testing;
+use super::*;
+
+/// blocks of a chunk, as generated
+fn generate(gen: &impl WorldGenerator, pos: int3) -> CBlockBuffer
+{
+    let mut blocks = CBlockBuffer::new();
+    gen.generate(pos, &mut blocks);
+
+    blocks
+}
+
+/// chunk of nothing but stone
+fn solid() -> CBlockBuffer
+{
+    let mut blocks = CBlockBuffer::new();
+    for i in 0..CHUNK_VOLUME
+    {
+        blocks.set_packed(ChunkPos::local(i), PackedBlock::from_data(1, 0));
+    }
+
+    blocks
+}
+
+/// are two chunks' blocks all the same?
+fn same(a: &CBlockBuffer, b: &CBlockBuffer) -> bool
+{
+    (0..CHUNK_VOLUME).all(|i| a.get_packed(ChunkPos::local(i)) == b.get_packed(ChunkPos::local(i)))
+}
+
+#[test]
+fn test_flat_generator()
+{
+    let layers = (0..40).map(|i| PackedBlock::from_data(1 + i / 16, i % 16)).collect::<Vec<_>>();
+    let gen = FlatGenerator { layers: layers.clone() };
+
+    let ground = generate(&gen, int3::new(64, 0, -32));
+    for y in 0..32
+    {
+        assert_eq!(ground.get_packed(int3::new(0, y, 0)), layers[y as usize]);
+        assert_eq!(ground.get_packed(int3::new(31, y, 17)), layers[y as usize]);
+    }
+
+    // layers past the first chunk
+    let above = generate(&gen, int3::new(0, 32, 0));
+    assert_eq!(above.get_packed(int3::new(5, 7, 5)), layers[39]);
+    assert_eq!(above.get_packed(int3::new(5, 8, 5)), PackedBlock::default());
+
+    // nothing below y = 0
+    assert_eq!(generate(&gen, int3::new(0, -32, 0)).uniform(), Some(PackedBlock::default()));
+    assert_eq!(generate(&gen, int3::new(32, -64, 32)).uniform(), Some(PackedBlock::default()));
+}
+
+#[test]
+fn test_noise_generator()
+{
+    let (top, fill) = (PackedBlock::from_data(1, 0), PackedBlock::from_data(2, 0));
+    let pos = int3::new(-32, 0, 96);
+
+    // same seed and position, same chunk, whatever came before
+    let a = NoiseGenerator::new(7, top, fill);
+    let b = NoiseGenerator::new(7, top, fill);
+
+    generate(&b, int3::new(320, 0, 0));
+    assert!(same(&generate(&a, pos), &generate(&b, pos)));
+
+    // another seed, other hills
+    let c = NoiseGenerator::new(8, top, fill);
+    assert!((0..64).any(|x| a.height(x * 3, x * 5) != c.height(x * 3, x * 5)));
+
+    // surface, with fill under it, and solid deep down
+    let h = a.height(-32, 96);
+    let blocks = generate(&a, pos);
+
+    assert_eq!(blocks.get_packed(int3::new(0, h - 1, 0)), top);
+    assert_eq!(blocks.get_packed(int3::new(0, h - 2, 0)), fill);
+    assert_eq!(blocks.get_packed(int3::new(0, h, 0)), PackedBlock::default());
+    assert_eq!(generate(&a, int3::new(0, -64, 0)).uniform(), Some(fill));
+}
+
+#[test]
+fn test_void_generator()
+{
+    assert_eq!(generate(&VoidGenerator, int3::new(0, 0, 0)).uniform(), Some(PackedBlock::default()));
+}
+
+#[test]
+fn test_biome_config()
+{
+    let pal = testing::palette();
+    let dir = testing::temp_dir("biome-config");
+
+    // the game's own configuration loads
+    let ron = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/world/generator.ron");
+    assert!(RWorldGenerator::load(ron, 0, &pal).is_ok());
+
+    let load = |climate: f64, blend: f64, frequency: f64|
+    {
+        let path = dir.join("generator.ron");
+        let src = format!("Biomes(climate_frequency: {:?}, blend: {:?}, biomes: [(name: \"plains\", \
+            temperature: 0.0, humidity: 0.0, height: 10.0, amplitude: 5.0, frequency: {:?}, \
+            surface: \"grass\", subsurface: \"dirt\", depth: 3, filler: \"stone\")])", climate, blend, frequency);
+
+        std::fs::write(&path, src).unwrap();
+        RWorldGenerator::load(&path, 0, &pal)
+    };
+
+    assert!(load(300.0, 0.2, 15.0).is_ok());
+
+    // anything divided by must be positive
+    for (field, res) in vec![
+        ("climate_frequency", load(0.0, 0.2, 15.0)),
+        ("blend", load(300.0, -0.2, 15.0)),
+        ("plains.frequency", load(300.0, 0.2, 0.0)),
+    ]
+    {
+        match res
+        {
+            Err(GenError::Invalid { field: f, .. }) => assert_eq!(f, field),
+            _ => panic!("{} should be rejected", field),
+        }
+    }
+}
+
+#[test]
+fn test_caves_cross_chunks()
+{
+    let mut caves = CaveCarver::new(3);
+
+    caves.cell = 32;
+    caves.chance = 1.0;
+    caves.max_y = i32::MAX;
+
+    let carve = |pos: int3|
+    {
+        let mut blocks = solid();
+        caves.carve(pos, &mut blocks);
+
+        blocks
+    };
+    let air = |blocks: &CBlockBuffer, pos: int3| blocks.get_packed(pos) == PackedBlock::default();
+
+    // two neighbors, and a chunk straddling their shared face
+    let a = carve(int3::new(0, -32, 0));
+    let b = carve(int3::new(32, -32, 0));
+    let mid = carve(int3::new(16, -32, 0));
+
+    for i in 0..CHUNK_VOLUME
+    {
+        let p = ChunkPos::local(i);
+        let (chunk, x) = if p.x < 16 { (&a, p.x + 16) } else { (&b, p.x - 16) };
+
+        assert_eq!(mid.get_packed(p), chunk.get_packed(int3::new(x, p.y, p.z)), "tunnel is cut at {:?}", p);
+    }
+
+    // some tunnel goes through the face
+    assert!((0..CHUNK_VOLUME)
+        .map(ChunkPos::local)
+        .filter(|p| p.x == 0)
+        .any(|p| air(&a, int3::new(31, p.y, p.z)) && air(&b, p)));
+}
@@ -0,0 +1,13 @@
+use ezmath::*;
+
+use crate::common::chunk::CBlockBuffer;
+use super::WorldGenerator;
+
+/// generator for an empty world, where every chunk is air
+#[derive(Debug, Copy, Clone, Default)]
+pub struct VoidGenerator;
+
+impl WorldGenerator for VoidGenerator
+{
+    fn generate(&self, _: int3, _: &mut CBlockBuffer) { }
+}
@@ -0,0 +1,14 @@
+mod r_light;
+mod s_light;
+mod propagate;
+
+pub use self::
+{
+    r_light::*,
+    s_light::*,
+    propagate::*,
+};
+
+
+#[cfg(test)]
+mod tests;
\ No newline at end of file
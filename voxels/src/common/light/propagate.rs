@@ -0,0 +1,337 @@
+use std::collections::{ HashMap, HashSet, VecDeque };
+
+use ezmath::*;
+
+use crate::common::chunk::{ CBlockBuffer, CLightBuffer, LightChannel, ChunkPos, BlockBufferIndex };
+use crate::common::block::{ RBlockPalette, BlockFace, UnpackedBlock };
+use crate::common::{ CHUNK_SIZE, CHUNK_VOLUME };
+
+/// what the light engine needs to know about every block of
+/// a chunk. the low 4 bits are the block's emission, and the
+/// 5th bit is set if light passes through it
+pub struct LightMask(Box<[u8; CHUNK_VOLUME]>);
+
+/// light around a block, as needed for meshing. each value
+/// is packed like in a CLightBuffer
+#[derive(Debug, Copy, Clone, Default)]
+pub struct BlockLight
+{
+    /// light of the neighbour on each face, in `BlockFace`
+    /// order
+    pub faces: [u8; 6],
+    /// light of the block itself, seen by faces that don't
+    /// touch its boundary
+    pub center: u8,
+}
+
+/// pending light work of every chunk. light is spread one
+/// chunk at a time, and the work that crosses a chunk's
+/// border is queued for its neighbour
+#[derive(Debug, Default)]
+pub struct LightQueues
+{
+    remove: HashMap<int3, VecDeque<LightNode>>,
+    add: HashMap<int3, VecDeque<LightNode>>,
+
+    /// chunks whose light changed, or that border a
+    /// block whose light changed
+    touched: HashSet<int3>,
+}
+
+/// a block queued for light work
+#[derive(Debug, Copy, Clone)]
+pub struct LightNode
+{
+    /// index of the block within its chunk
+    index: u16,
+    /// channel of light being spread or removed
+    ch: LightChannel,
+    /// level being added, or that of the darkened neighbour
+    /// being removed
+    level: u8,
+    /// for removals, was the darkened neighbour right above?
+    down: bool,
+}
+
+impl LightMask
+{
+    /// gather the light properties of every block in a chunk
+    pub fn new(blocks: &CBlockBuffer, pal: &RBlockPalette) -> Self
+    {
+        let mask = |block: UnpackedBlock| block.emission() | if block.transmits_light() { 1 << 4 } else { 0 };
+
+        // every block is the same
+        if blocks.uniform().is_some()
+        {
+            return Self(Box::new([mask(blocks.get(int3::zeros(), pal)); CHUNK_VOLUME]));
+        }
+
+        let mut masks = Box::new([0; CHUNK_VOLUME]);
+        for (i, m) in masks.iter_mut().enumerate()
+        {
+            *m = mask(blocks.get(ChunkPos::local(i), pal));
+        }
+        Self(masks)
+    }
+
+    /// light level emitted by the block at a flat index
+    pub fn emission(&self, i: usize) -> u8
+    {
+        self.0[i] & 0b1111
+    }
+
+    /// does light pass through the block at a flat index?
+    pub fn transmits(&self, i: usize) -> bool
+    {
+        self.0[i] & (1 << 4) != 0
+    }
+}
+
+impl LightQueues
+{
+    /// level to remove to darken a block no matter what
+    pub const FORCE: u8 = CLightBuffer::MAX + 1;
+
+    /// darken a block given its global position, and every
+    /// block whose light came through it. `level` is the light
+    /// of the neighbour that was darkened: use `FORCE` to darken
+    /// the block itself, and 0 to spread its light again instead
+    pub fn remove(&mut self, pos: int3, ch: LightChannel, level: u8)
+    {
+        let (chunk, i) = split(pos);
+
+        self.remove
+            .entry(chunk)
+            .or_default()
+            .push_back(LightNode { index: i as u16, ch, level, down: false });
+    }
+
+    /// light a block given its global position to at least
+    /// `level`, and spread from it
+    pub fn add(&mut self, pos: int3, ch: LightChannel, level: u8)
+    {
+        let (chunk, i) = split(pos);
+
+        self.add
+            .entry(chunk)
+            .or_default()
+            .push_back(LightNode { index: i as u16, ch, level, down: false });
+    }
+
+    /// take the pending removals of any chunk that has some
+    pub fn next_remove(&mut self) -> Option<(int3, VecDeque<LightNode>)>
+    {
+        let chunk = *self.remove.keys().next()?;
+
+        self.remove.remove_entry(&chunk)
+    }
+
+    /// take the pending additions of any chunk that has some
+    pub fn next_add(&mut self) -> Option<(int3, VecDeque<LightNode>)>
+    {
+        let chunk = *self.add.keys().next()?;
+
+        self.add.remove_entry(&chunk)
+    }
+
+    /// take the position of every chunk whose light changed,
+    /// or whose neighbour's light changed on its border
+    pub fn touched(&mut self) -> impl Iterator<Item = int3> + '_
+    {
+        self.touched.drain()
+    }
+
+    /// run the pending removals of a chunk. blocks whose light
+    /// depended on a darkened neighbour are darkened in turn,
+    /// and independent light around them is queued to spread
+    /// back in
+    pub fn run_remove(&mut self, chunk: int3, mut nodes: VecDeque<LightNode>, mask: &LightMask, light: &mut CLightBuffer)
+    {
+        while let Some(n) = nodes.pop_front()
+        {
+            let i = n.index as usize;
+            let cur = light.channel(n.ch, i);
+
+            if cur == 0
+            {
+                continue;
+            }
+
+            // sky light going straight down doesn't dim, so it
+            // depends on an equal level right above it
+            let dependent = cur < n.level
+                || (n.ch == LightChannel::Sky && n.down && n.level == CLightBuffer::MAX && cur == CLightBuffer::MAX);
+
+            if !dependent
+            {
+                self.spread(chunk, i, n.ch, cur);
+                continue;
+            }
+
+            light.set_channel(n.ch, i, 0);
+            self.touch(chunk, i);
+
+            for f in 0..6usize
+            {
+                let face = BlockFace::from(f);
+                let (c, j) = step(chunk, i, face);
+                let node = LightNode { index: j as u16, ch: n.ch, level: cur, down: face == BlockFace::Down };
+
+                if c == chunk
+                {
+                    nodes.push_back(node);
+                }
+                else
+                {
+                    self.remove.entry(c).or_default().push_back(node);
+                }
+            }
+
+            // emitters light themselves back up
+            if n.ch == LightChannel::Block && mask.emission(i) > 0
+            {
+                self.add
+                    .entry(chunk)
+                    .or_default()
+                    .push_back(LightNode { index: i as u16, ch: n.ch, level: mask.emission(i), down: false });
+            }
+        }
+    }
+
+    /// run the pending additions of a chunk, flood filling
+    /// light through every block it can pass through
+    pub fn run_add(&mut self, chunk: int3, mut nodes: VecDeque<LightNode>, mask: &LightMask, light: &mut CLightBuffer)
+    {
+        while let Some(n) = nodes.pop_front()
+        {
+            let i = n.index as usize;
+
+            // opaque blocks only hold their own light
+            if !mask.transmits(i) && !(n.ch == LightChannel::Block && mask.emission(i) >= n.level)
+            {
+                continue;
+            }
+            if light.channel(n.ch, i) >= n.level
+            {
+                continue;
+            }
+
+            light.set_channel(n.ch, i, n.level);
+            self.touch(chunk, i);
+
+            for f in 0..6usize
+            {
+                let face = BlockFace::from(f);
+                let level = next_level(n.ch, n.level, face);
+
+                if level == 0
+                {
+                    continue;
+                }
+
+                let (c, j) = step(chunk, i, face);
+                let node = LightNode { index: j as u16, ch: n.ch, level, down: false };
+
+                if c == chunk
+                {
+                    nodes.push_back(node);
+                }
+                else
+                {
+                    self.add.entry(c).or_default().push_back(node);
+                }
+            }
+        }
+    }
+
+    /// queue the light of a block to spread to its neighbours
+    fn spread(&mut self, chunk: int3, i: usize, ch: LightChannel, level: u8)
+    {
+        for f in 0..6usize
+        {
+            let face = BlockFace::from(f);
+            let level = next_level(ch, level, face);
+
+            if level > 0
+            {
+                let (c, j) = step(chunk, i, face);
+
+                self.add
+                    .entry(c)
+                    .or_default()
+                    .push_back(LightNode { index: j as u16, ch, level, down: false });
+            }
+        }
+    }
+
+    /// mark a chunk as touched by a light change at a flat
+    /// index, along with the neighbours it borders there
+    fn touch(&mut self, chunk: int3, i: usize)
+    {
+        self.touched.insert(chunk);
+
+        for f in 0..6usize
+        {
+            let (c, _) = step(chunk, i, BlockFace::from(f));
+
+            if c != chunk
+            {
+                self.touched.insert(c);
+            }
+        }
+    }
+}
+
+/// world positions of the blocks of a chunk on its boundary
+/// with the given face
+pub fn boundary(chunk: int3, face: BlockFace) -> impl Iterator<Item = int3>
+{
+    const SIZE: i32 = CHUNK_SIZE as i32;
+
+    let (axis, positive) = face.axis();
+    let (u, v) = face.plane();
+
+    (0..SIZE)
+        .flat_map(|a| (0..SIZE).map(move |b| (a, b)))
+        .map(move |(a, b)|
+        {
+            let mut pos = chunk;
+
+            pos[axis] += if positive { SIZE - 1 } else { 0 };
+            pos[u] += a;
+            pos[v] += b;
+            pos
+        })
+}
+
+/// light level spread from a block to its neighbour on the
+/// given face
+fn next_level(ch: LightChannel, level: u8, face: BlockFace) -> u8
+{
+    if ch == LightChannel::Sky && face == BlockFace::Down && level == CLightBuffer::MAX
+    {
+        level
+    }
+    else
+    {
+        level.saturating_sub(1)
+    }
+}
+
+/// split a global block position into its chunk, and its
+/// flat index within that chunk
+fn split(mut pos: int3) -> (int3, usize)
+{
+    let mut chunk = pos;
+    ChunkPos::adjust(&mut chunk);
+
+    pos -= chunk;
+    (chunk, ChunkPos::index(pos))
+}
+
+/// step from the block at a flat index of a chunk towards a
+/// face, giving the chunk and flat index of the block there
+fn step(chunk: int3, i: usize, face: BlockFace) -> (int3, usize)
+{
+    split(chunk + ChunkPos::local(i) + face.normal())
+}
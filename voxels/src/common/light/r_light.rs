@@ -0,0 +1,34 @@
+use ezmath::*;
+
+/// resource that queues blocks whose light needs to be
+/// updated, because they were placed or removed. the light
+/// system relights them, and everything they affect, on
+/// its next run
+#[derive(Debug, Default)]
+pub struct RLightQueue
+{
+    changed: Vec<int3>,
+}
+
+impl RLightQueue
+{
+    /// queue a block, given its global position, whose
+    /// light needs to be updated. this should be called
+    /// every time a block is set after its chunk is lit
+    pub fn push(&mut self, pos: int3)
+    {
+        self.changed.push(pos);
+    }
+
+    /// is there no block queued?
+    pub fn is_empty(&self) -> bool
+    {
+        self.changed.is_empty()
+    }
+
+    /// take every queued block
+    pub fn drain(&mut self) -> impl Iterator<Item = int3> + '_
+    {
+        self.changed.drain(..)
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use ezgame::time::evt;
+use ezgame::ecs::*;
+use ezmath::*;
+
+use super::{ RLightQueue, LightQueues, LightMask, boundary };
+use crate::common::chunk::{ CChunk, CBlockBuffer, CLightBuffer, LightChannel, BlockBufferIndex, ChunkPos, RChunkCache, SChunkGen, TUnlit, TUpdated };
+use crate::common::block::{ RBlockPalette, BlockFace };
+use crate::common::{ CHUNK_SIZE, CHUNK_VOLUME };
+
+/// system that spreads sky light and block light through
+/// newly generated chunks, and relights around changed
+/// blocks
+pub struct SLight;
+
+impl System for SLight
+{
+    const EVENT: Event = evt::UPDATE;
+    const ORDER: Order = SChunkGen::ORDER + 1;
+
+    const FLUSH: bool = true;
+
+    fn prepare(res: &mut Resources)
+    {
+        res.insert(RLightQueue::default())
+    }
+
+    fn exe() -> Job
+    {
+        // begin...
+        sys("light_system")
+        // components...
+        .with_query(<Read<CChunk>>::query().filter(tag::<TUnlit>()))
+        .read_component::<CBlockBuffer>()
+        .write_component::<CLightBuffer>()
+        // resources...
+        .read_resource::<RChunkCache>()
+        .read_resource::<RBlockPalette>()
+        .write_resource::<RLightQueue>()
+        // system...
+        .build(|cmd, world, (r_cache, r_pal, r_queue), q_unlit|
+        {
+            const SIZE: i32 = CHUNK_SIZE as i32;
+
+            let unlit = q_unlit
+                .iter_entities(world)
+                .map(|(ent, chunk)| (ent, chunk.pos()))
+                .collect::<Vec<_>>();
+
+            if unlit.is_empty() && r_queue.is_empty()
+            {
+                return;
+            }
+
+            // chunks that are loaded but not generated yet have no
+            // blocks to light, and get lit once they do
+            let lit = |pos: int3| r_cache.generated_at(pos).is_some() && !unlit.iter().any(|(_, p)| *p == pos);
+            let up = int3::new(0, SIZE, 0);
+
+            let mut queues = LightQueues::default();
+            let mut respread = Vec::<int3>::new();
+
+            // newly generated chunks
+            for (ent, pos) in &unlit
+            {
+                // start from darkness, whatever spread into the
+                // chunk before it had its blocks
+                *world.get_component_mut::<CLightBuffer>(*ent).unwrap() = CLightBuffer::new();
+
+                let blocks = world.get_component::<CBlockBuffer>(*ent).unwrap();
+
+                // emitters
+                for i in 0..CHUNK_VOLUME
+                {
+                    let emission = blocks.get(ChunkPos::local(i), r_pal).emission();
+
+                    if emission > 0
+                    {
+                        queues.add(*pos + ChunkPos::local(i), LightChannel::Block, emission);
+                    }
+                }
+
+                // open sky above
+                if r_cache.generated_at(*pos + up).is_none()
+                {
+                    for top in boundary(*pos, BlockFace::Up)
+                    {
+                        queues.add(top, LightChannel::Sky, CLightBuffer::MAX);
+                    }
+                }
+
+                // the chunk below was lit as if under open sky
+                if lit(*pos - up)
+                {
+                    for bottom in boundary(*pos - up, BlockFace::Up)
+                    {
+                        queues.remove(bottom, LightChannel::Sky, LightQueues::FORCE);
+                    }
+                }
+
+                // pull in the light of lit neighbours
+                for f in 0..6usize
+                {
+                    let face = BlockFace::from(f);
+                    let neighbor = *pos + face.normal() * SIZE;
+
+                    if lit(neighbor)
+                    {
+                        respread.extend(boundary(neighbor, face.opposite()));
+                    }
+                }
+
+                cmd.remove_tag::<TUnlit>(*ent);
+            }
+
+            // changed blocks
+            for pos in r_queue.drain()
+            {
+                let mut chunk = pos;
+                ChunkPos::adjust(&mut chunk);
+
+                let ent = match r_cache.generated_at(chunk)
+                {
+                    Some(ent) => *ent,
+                    None => continue,
+                };
+
+                queues.remove(pos, LightChannel::Sky, LightQueues::FORCE);
+                queues.remove(pos, LightChannel::Block, LightQueues::FORCE);
+
+                for f in 0..6usize
+                {
+                    respread.push(pos + BlockFace::from(f).normal());
+                }
+
+                let emission = world
+                    .get_component::<CBlockBuffer>(ent)
+                    .unwrap()
+                    .get(pos - chunk, r_pal)
+                    .emission();
+
+                if emission > 0
+                {
+                    queues.add(pos, LightChannel::Block, emission);
+                }
+                if pos.y - chunk.y == SIZE - 1 && r_cache.generated_at(chunk + up).is_none()
+                {
+                    queues.add(pos, LightChannel::Sky, CLightBuffer::MAX);
+                }
+            }
+
+            // masks are built once per run, after blocks changed
+            let mut masks = HashMap::<int3, LightMask>::new();
+            let mut run = |queues: &mut LightQueues, add: bool|
+            {
+                loop
+                {
+                    let next = if add { queues.next_add() } else { queues.next_remove() };
+                    let (chunk, nodes) = match next
+                    {
+                        Some(next) => next,
+                        None => break,
+                    };
+
+                    // light escaping into chunks without blocks is lost
+                    let ent = match r_cache.generated_at(chunk)
+                    {
+                        Some(ent) => *ent,
+                        None => continue,
+                    };
+
+                    let mask = masks
+                        .entry(chunk)
+                        .or_insert_with(|| LightMask::new(&world.get_component::<CBlockBuffer>(ent).unwrap(), r_pal));
+                    let mut light = world
+                        .get_component_mut::<CLightBuffer>(ent)
+                        .unwrap();
+
+                    if add
+                    {
+                        queues.run_add(chunk, nodes, mask, &mut light);
+                    }
+                    else
+                    {
+                        queues.run_remove(chunk, nodes, mask, &mut light);
+                    }
+                }
+            };
+
+            // darken, spread the surviving light back in, then
+            // spread new light
+            run(&mut queues, false);
+            for pos in respread
+            {
+                queues.remove(pos, LightChannel::Sky, 0);
+                queues.remove(pos, LightChannel::Block, 0);
+            }
+            run(&mut queues, false);
+            run(&mut queues, true);
+
+            // remesh everything that changed
+            for chunk in queues.touched()
+            {
+                if let Some(ent) = r_cache.at(chunk)
+                {
+                    cmd.add_tag(*ent, TUpdated);
+                }
+            }
+        })
+    }
+}
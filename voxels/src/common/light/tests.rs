@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use ezgame::ecs::*;
+use ezmath::*;
+
+use crate::common::chunk::{ CChunk, CBlockBuffer, CLightBuffer, LightChannel, BlockBufferIndex, ChunkPos, RChunkCache, TUngenerated, TUnlit };
+use crate::common::block::{ RBlockPalette, BlockFace };
+use crate::common::testing::{ self, TestWorld };
+use crate::common::CHUNK_VOLUME;
+use super::*;
+
+/// a global position's chunk, and its position relative
+/// to that chunk
+fn split(pos: int3) -> (int3, int3)
+{
+    let mut chunk = pos;
+    ChunkPos::adjust(&mut chunk);
+
+    (chunk, pos - chunk)
+}
+
+/// a few chunks, lit without the ECS, the way `SLight` does
+struct Lit
+{
+    pal: RBlockPalette,
+    chunks: HashMap<int3, (CBlockBuffer, CLightBuffer)>,
+    queues: LightQueues,
+}
+
+impl Lit
+{
+    fn new(chunks: &[int3]) -> Self
+    {
+        Self
+        {
+            pal: testing::palette(),
+            chunks: chunks.iter().map(|c| (*c, (CBlockBuffer::new(), CLightBuffer::new()))).collect(),
+            queues: LightQueues::default(),
+        }
+    }
+
+    fn set(&mut self, pos: int3, block: &str)
+    {
+        let (chunk, local) = split(pos);
+        let block = self.pal.block(block).unwrap();
+
+        self.chunks.get_mut(&chunk).unwrap().0.set_packed(local, block);
+    }
+
+    fn light(&self, pos: int3, ch: LightChannel) -> u8
+    {
+        let (chunk, local) = split(pos);
+
+        self.chunks[&chunk].1.channel(ch, ChunkPos::index(local))
+    }
+
+    /// run every pending removal, then every pending addition
+    fn run(&mut self)
+    {
+        let masks = self.chunks
+            .iter()
+            .map(|(c, (blocks, _))| (*c, LightMask::new(blocks, &self.pal)))
+            .collect::<HashMap<_, _>>();
+
+        for add in &[false, true]
+        {
+            loop
+            {
+                let next = if *add { self.queues.next_add() } else { self.queues.next_remove() };
+                let (chunk, nodes) = match next
+                {
+                    Some(next) => next,
+                    None => break,
+                };
+                let light = match self.chunks.get_mut(&chunk)
+                {
+                    Some((_, light)) => light,
+                    None => continue,
+                };
+
+                if *add
+                {
+                    self.queues.run_add(chunk, nodes, &masks[&chunk], light);
+                }
+                else
+                {
+                    self.queues.run_remove(chunk, nodes, &masks[&chunk], light);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_light_block_falloff()
+{
+    let mut lit = Lit::new(&[int3::zeros()]);
+    let lamp = int3::new(16, 16, 16);
+
+    lit.set(lamp, "lamp");
+    lit.queues.add(lamp, LightChannel::Block, 15);
+    lit.run();
+
+    // one level per block, by manhattan distance
+    assert_eq!(lit.light(lamp, LightChannel::Block), 15);
+    assert_eq!(lit.light(int3::new(17, 16, 16), LightChannel::Block), 14);
+    assert_eq!(lit.light(int3::new(18, 17, 15), LightChannel::Block), 11);
+    assert_eq!(lit.light(int3::new(16, 16, 31), LightChannel::Block), 0);
+    assert_eq!(lit.light(int3::new(17, 16, 16), LightChannel::Sky), 0);
+}
+
+#[test]
+fn test_light_block_opaque()
+{
+    let mut lit = Lit::new(&[int3::zeros()]);
+    let lamp = int3::new(16, 16, 16);
+
+    lit.set(lamp, "lamp");
+    for f in 0..6usize
+    {
+        lit.set(lamp + BlockFace::from(f).normal(), "dirt");
+    }
+    lit.queues.add(lamp, LightChannel::Block, 15);
+    lit.run();
+
+    assert_eq!(lit.light(lamp, LightChannel::Block), 15);
+    assert_eq!(lit.light(int3::new(17, 16, 16), LightChannel::Block), 0);
+    assert_eq!(lit.light(int3::new(18, 16, 16), LightChannel::Block), 0);
+}
+
+#[test]
+fn test_light_block_chunks()
+{
+    let mut lit = Lit::new(&[int3::zeros(), int3::new(32, 0, 0)]);
+    let lamp = int3::new(30, 0, 0);
+
+    lit.set(lamp, "lamp");
+    lit.queues.add(lamp, LightChannel::Block, 15);
+    lit.run();
+
+    assert_eq!(lit.light(int3::new(31, 0, 0), LightChannel::Block), 14);
+    assert_eq!(lit.light(int3::new(33, 0, 0), LightChannel::Block), 12);
+    assert_eq!(lit.light(int3::new(33, 1, 1), LightChannel::Block), 10);
+
+    let touched = lit.queues.touched().collect::<Vec<_>>();
+    assert!(touched.contains(&int3::zeros()) && touched.contains(&int3::new(32, 0, 0)));
+}
+
+#[test]
+fn test_light_sky()
+{
+    let mut lit = Lit::new(&[int3::zeros()]);
+
+    // a roof over half the chunk
+    for x in 0..32
+    {
+        for z in 0..16
+        {
+            lit.set(int3::new(x, 10, z), "dirt");
+        }
+    }
+    for pos in boundary(int3::zeros(), BlockFace::Up)
+    {
+        lit.queues.add(pos, LightChannel::Sky, CLightBuffer::MAX);
+    }
+    lit.run();
+
+    // straight down doesn't dim, sideways does
+    assert_eq!(lit.light(int3::new(5, 0, 20), LightChannel::Sky), 15);
+    assert_eq!(lit.light(int3::new(5, 11, 5), LightChannel::Sky), 15);
+    assert_eq!(lit.light(int3::new(5, 10, 5), LightChannel::Sky), 0);
+    assert_eq!(lit.light(int3::new(5, 5, 15), LightChannel::Sky), 14);
+    assert_eq!(lit.light(int3::new(5, 5, 10), LightChannel::Sky), 9);
+}
+
+#[test]
+fn test_light_remove()
+{
+    let mut lit = Lit::new(&[int3::zeros()]);
+    let (a, b) = (int3::new(10, 16, 16), int3::new(20, 16, 16));
+
+    for lamp in &[a, b]
+    {
+        lit.set(*lamp, "lamp");
+        lit.queues.add(*lamp, LightChannel::Block, 15);
+    }
+    lit.run();
+    assert_eq!(lit.light(int3::new(12, 16, 16), LightChannel::Block), 13);
+
+    // the other lamp's light fills back in
+    lit.set(a, "air");
+    lit.queues.remove(a, LightChannel::Block, LightQueues::FORCE);
+    lit.run();
+
+    assert_eq!(lit.light(a, LightChannel::Block), 5);
+    assert_eq!(lit.light(int3::new(12, 16, 16), LightChannel::Block), 7);
+    assert_eq!(lit.light(int3::new(5, 16, 16), LightChannel::Block), 0);
+    assert_eq!(lit.light(b, LightChannel::Block), 15);
+
+    // and nothing is left once both are gone
+    lit.set(b, "air");
+    lit.queues.remove(b, LightChannel::Block, LightQueues::FORCE);
+    lit.run();
+
+    assert_eq!(lit.light(int3::new(20, 16, 17), LightChannel::Block), 0);
+    assert_eq!(lit.light(a, LightChannel::Block), 0);
+}
+
+/// light level of a chunk entity's block, given its local
+/// position
+fn light_at(world: &TestWorld, ent: Entity, local: int3, ch: LightChannel) -> u8
+{
+    world.registry
+        .get_component::<CLightBuffer>(ent)
+        .unwrap()
+        .channel(ch, ChunkPos::index(local))
+}
+
+#[test]
+fn test_light_system_ungenerated()
+{
+    let mut world = TestWorld::new();
+    let (below, above) = (int3::zeros(), int3::new(0, 32, 0));
+    let (lamp, dirt) =
+    {
+        let r_pal = world.resources.get::<RBlockPalette>().unwrap();
+
+        (r_pal.block("lamp").unwrap(), r_pal.block("dirt").unwrap())
+    };
+
+    // a lamp at the top of a generated chunk, under a chunk
+    // that's loaded but doesn't have its blocks yet
+    let mut blocks = CBlockBuffer::new();
+    blocks.set_packed(int3::new(16, 31, 16), lamp);
+
+    let b = world.chunk(below, blocks);
+    world.registry.add_tag(b, TUnlit).unwrap();
+
+    let a = world.registry.insert((TUngenerated,), vec![(CChunk::new(above), CBlockBuffer::new(), CLightBuffer::new())])[0];
+    world.resources.get_mut::<RChunkCache>().unwrap().store(above, a);
+
+    world.run(SLight::exe());
+
+    // nothing spreads into the empty chunk, which doesn't
+    // cover the one below either
+    assert_eq!(light_at(&world, a, int3::new(16, 0, 16), LightChannel::Block), 0);
+    assert_eq!(light_at(&world, b, int3::new(16, 30, 16), LightChannel::Block), 14);
+    assert_eq!(light_at(&world, b, int3::zeros(), LightChannel::Sky), 15);
+
+    // then it's generated full of dirt
+    {
+        let mut blocks = world.registry.get_component_mut::<CBlockBuffer>(a).unwrap();
+        for i in 0..CHUNK_VOLUME
+        {
+            blocks.set_packed(ChunkPos::local(i), dirt);
+        }
+    }
+    world.resources.get_mut::<RChunkCache>().unwrap().mark_generated(above);
+    world.registry.remove_tag::<TUngenerated>(a).unwrap();
+    world.registry.add_tag(a, TUnlit).unwrap();
+
+    world.run(SLight::exe());
+
+    // dark inside the dirt, and beneath it
+    for i in 0..CHUNK_VOLUME
+    {
+        assert_eq!(light_at(&world, a, ChunkPos::local(i), LightChannel::Block), 0);
+        assert_eq!(light_at(&world, a, ChunkPos::local(i), LightChannel::Sky), 0);
+    }
+    assert_eq!(light_at(&world, b, int3::zeros(), LightChannel::Sky), 0);
+    assert_eq!(light_at(&world, b, int3::new(16, 31, 16), LightChannel::Block), 15);
+}
@@ -5,13 +5,27 @@ pub use consts::*;
 pub mod transform;
 pub mod chunk;
 pub mod block;
+pub mod light;
+pub mod save;
+pub mod gen;
+pub mod ray;
+pub mod edit;
+pub mod schematic;
 pub mod debug;
 
+/// helpers shared by the tests of every module
+#[cfg(test)]
+mod testing;
+
 /// shared system bundle
 pub type Bundle =
 (
     transform::SLocalToWorld,
     chunk::SChunkLoad,
+    chunk::SChunkUnload,
+    save::SChunkRead,
     chunk::SChunkGen,
+    light::SLight,
+    save::SChunkWrite,
     debug::SDebugFps,
 );
\ No newline at end of file
@@ -0,0 +1,33 @@
+use ezmath::*;
+
+use crate::common::block::{ PackedBlock, BlockFace };
+
+/// what to do when a ray reaches a chunk that isn't loaded,
+/// or isn't generated yet
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RayUnloaded
+{
+    /// go through it, as if it were air
+    Pass,
+    /// stop, without hitting anything
+    Miss,
+    /// stop, hitting its first block, ie. for line-of-sight
+    Hit,
+}
+
+/// the block a ray hit
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RayHit
+{
+    /// global position of the block hit
+    pub pos: int3,
+    /// face of the block the ray went in through
+    pub face: BlockFace,
+    /// distance along the ray to where it hit
+    pub dist: f32,
+    /// global position of the last empty block before the
+    /// one hit, where a block would be placed against it
+    pub prev: int3,
+    /// block hit, or none if its chunk isn't loaded
+    pub block: Option<PackedBlock>,
+}
@@ -0,0 +1,11 @@
+mod ray;
+mod hit;
+
+pub use self::
+{
+    ray::*,
+    hit::*,
+};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,156 @@
+use ezgame::ecs::*;
+use ezmath::*;
+
+use crate::common::chunk::{ CBlockBuffer, BlockBufferIndex, RChunkCache, ChunkPos };
+use crate::common::block::{ PackedBlock, BlockFace };
+use super::{ RayHit, RayUnloaded };
+
+/// a ray cast through the blocks of the world, on global
+/// positions, one block at a time(Amanatides-Woo DDA)
+/// ```rust
+/// let ray = Ray::new(eye, look, 8.0);
+///
+/// if let Some(hit) = ray.cast(world, &r_cache)
+/// {
+///     // place a block against the face looked at
+///     blocks.set_block(hit.prev, stone);
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Ray
+{
+    /// start of the ray
+    pub origin: float3,
+    /// direction of the ray, normalized
+    pub dir: float3,
+    /// farthest the ray goes
+    pub max: f32,
+    /// what to do when reaching unloaded chunks
+    pub unloaded: RayUnloaded,
+}
+
+impl Ray
+{
+    /// create a new ray, which goes through unloaded chunks
+    pub fn new(origin: float3, dir: float3, max: f32) -> Self
+    {
+        Self
+        {
+            origin,
+            dir: dir.normalize(),
+            max,
+            unloaded: RayUnloaded::Pass,
+        }
+    }
+
+    /// set what the ray does when reaching unloaded chunks
+    pub fn unloaded(mut self, unloaded: RayUnloaded) -> Self
+    {
+        self.unloaded = unloaded;
+        self
+    }
+
+    /// cast this ray through the loaded chunks, until it hits
+    /// a block that isn't air
+    pub fn cast(&self, world: &SubRegistry, cache: &RChunkCache) -> Option<RayHit>
+    {
+        self.cast_with(world, cache, |_, block| block != PackedBlock::default())
+    }
+
+    /// cast this ray through the loaded chunks, until it hits
+    /// a block `hits` accepts, given its global position. blocks
+    /// are read like `WorldBlocks::get_block`, in data format
+    pub fn cast_with(&self, world: &SubRegistry, cache: &RChunkCache, hits: impl FnMut(int3, PackedBlock) -> bool) -> Option<RayHit>
+    {
+        self.walk(|pos|
+        {
+            let (chunk, local) = ChunkPos::split(pos);
+
+            let ent = cache.generated_at(chunk)?;
+            let blocks = world.get_component::<CBlockBuffer>(*ent)?;
+
+            Some(blocks.get_data(local))
+        }, hits)
+    }
+
+    /// walk this ray one block at a time, getting blocks from
+    /// `get`(none if unloaded), until it hits a block `hits`
+    /// accepts. the block the ray starts in is never hit
+    pub fn walk(&self, mut get: impl FnMut(int3) -> Option<PackedBlock>, mut hits: impl FnMut(int3, PackedBlock) -> bool) -> Option<RayHit>
+    {
+        // zero or invalid direction
+        if !self.dir.iter().all(|c| c.is_finite())
+        {
+            return None;
+        }
+
+        let mut pos = self.origin.map(|c| c.floor() as i32);
+
+        let mut step = int3::zeros();
+        let mut t_max = float3::repeat(std::f32::INFINITY);
+        let mut t_delta = float3::repeat(std::f32::INFINITY);
+
+        for a in 0..3
+        {
+            let d = self.dir[a];
+            let o = self.origin[a];
+
+            if d > 0.0
+            {
+                step[a] = 1;
+                t_max[a] = (pos[a] as f32 + 1.0 - o) / d;
+                t_delta[a] = 1.0 / d;
+            }
+            else if d < 0.0
+            {
+                step[a] = -1;
+                t_max[a] = (o - pos[a] as f32) / -d;
+                t_delta[a] = 1.0 / -d;
+            }
+        }
+
+        loop
+        {
+            // closest block boundary
+            let a = if t_max.x < t_max.y
+            {
+                if t_max.x < t_max.z { 0 } else { 2 }
+            }
+            else
+            {
+                if t_max.y < t_max.z { 1 } else { 2 }
+            };
+
+            let dist = t_max[a];
+            if dist > self.max
+            {
+                return None;
+            }
+
+            let prev = pos;
+
+            pos[a] += step[a];
+            t_max[a] += t_delta[a];
+
+            // entered through the face looking back at the ray
+            let mut normal = int3::zeros();
+            normal[a] = -step[a];
+
+            let face = BlockFace::from_normal(normal).unwrap();
+
+            match get(pos)
+            {
+                Some(block) => if hits(pos, block)
+                {
+                    return Some(RayHit { pos, face, dist, prev, block: Some(block) });
+                },
+                None => match self.unloaded
+                {
+                    RayUnloaded::Pass => {},
+                    RayUnloaded::Miss => return None,
+                    RayUnloaded::Hit => return Some(RayHit { pos, face, dist, prev, block: None }),
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use ezmath::*;
+
+use crate::common::block::{ PackedBlock, BlockFace };
+use super::*;
+
+/// a single stone block at `solid`, with everything within
+/// `loaded` blocks of the origin loaded
+fn world(solid: int3, loaded: i32) -> impl FnMut(int3) -> Option<PackedBlock>
+{
+    move |pos: int3|
+    {
+        if pos.iter().any(|c| c.abs() > loaded)
+        {
+            return None;
+        }
+        Some(if pos == solid { PackedBlock::from_data(1, 0) } else { PackedBlock::default() })
+    }
+}
+
+fn solid(_: int3, block: PackedBlock) -> bool
+{
+    block != PackedBlock::default()
+}
+
+#[test]
+fn test_ray_axis()
+{
+    let ray = Ray::new(float3::new(0.5, 0.5, 0.5), float3::new(1.0, 0.0, 0.0), 10.0);
+    let hit = ray.walk(world(int3::new(4, 0, 0), 100), solid).unwrap();
+
+    assert_eq!(hit.pos, int3::new(4, 0, 0));
+    assert_eq!(hit.prev, int3::new(3, 0, 0));
+    assert_eq!(hit.face, BlockFace::West);
+    assert!((hit.dist - 3.5).abs() < 1e-5);
+
+    // out of reach
+    let ray = Ray::new(float3::new(0.5, 0.5, 0.5), float3::new(1.0, 0.0, 0.0), 3.0);
+    assert!(ray.walk(world(int3::new(4, 0, 0), 100), solid).is_none());
+}
+
+#[test]
+fn test_ray_diagonal()
+{
+    // negative directions, across the origin's borders
+    let ray = Ray::new(float3::new(0.5, 0.5, 0.5), float3::new(-1.0, -1.0, -1.0), 20.0);
+    let hit = ray.walk(world(int3::new(-3, -3, -3), 100), solid).unwrap();
+
+    assert_eq!(hit.pos, int3::new(-3, -3, -3));
+    assert_eq!(hit.pos - hit.prev, -hit.face.normal());
+
+    // the starting block is never hit
+    let ray = Ray::new(float3::new(0.5, 0.5, 0.5), float3::new(0.0, 1.0, 0.0), 5.0);
+    assert!(ray.walk(world(int3::zeros(), 100), solid).is_none());
+
+    // no direction
+    let ray = Ray::new(float3::zeros(), float3::zeros(), 5.0);
+    assert!(ray.walk(world(int3::new(1, 0, 0), 100), solid).is_none());
+}
+
+#[test]
+fn test_ray_unloaded()
+{
+    let origin = float3::new(0.5, 0.5, 0.5);
+    let dir = float3::new(0.0, 0.0, 1.0);
+
+    // stone beyond the loaded blocks
+    let pass = Ray::new(origin, dir, 10.0).unloaded(RayUnloaded::Pass);
+    assert!(pass.walk(world(int3::new(0, 0, 5), 2), solid).is_none());
+
+    let miss = Ray::new(origin, dir, 10.0).unloaded(RayUnloaded::Miss);
+    assert!(miss.walk(world(int3::new(0, 0, 1), 0), solid).is_none());
+
+    let hit = Ray::new(origin, dir, 10.0).unloaded(RayUnloaded::Hit);
+    let hit = hit.walk(world(int3::new(0, 0, 5), 2), solid).unwrap();
+
+    assert_eq!(hit.pos, int3::new(0, 0, 3));
+    assert_eq!(hit.face, BlockFace::North);
+    assert_eq!(hit.block, None);
+}
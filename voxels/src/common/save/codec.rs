@@ -0,0 +1,132 @@
+use std::io::{ Read, Write };
+use std::convert::TryInto;
+
+use flate2::{ Compression, read::ZlibDecoder, write::ZlibEncoder };
+
+use crate::common::chunk::{ CBlockBuffer, BlockBufferIndex, ChunkPos };
+use crate::common::block::{ PackedBlock, PackedBlockFormat, BlockEntityTypes };
+use crate::common::CHUNK_VOLUME;
+
+/// version of the chunk encoding, bumped on every change
+const CHUNK_VERSION: u8 = 2;
+
+/// encode a chunk's blocks for a region file. the encoding is
+/// a version byte, then every packed block as a little endian
+/// u16 in flat index order, then the chunk's block entities,
+/// all compressed with zlib. address format blocks are written
+/// as the data format block of their entity's type, and each
+/// block entity as:
+///     - u16 -> flat index of its block
+///     - u32 -> length of its payload
+///     - its payload, see `BlockEntityTypes`
+///
+/// after a u16 count. version 1 chunks have no block entities
+pub fn encode_chunk(blocks: &CBlockBuffer, types: &BlockEntityTypes) -> Vec<u8>
+{
+    let mut raw = Vec::with_capacity(1 + CHUNK_VOLUME * 2);
+    let mut ents = Vec::new();
+
+    raw.push(CHUNK_VERSION);
+    for i in 0..CHUNK_VOLUME
+    {
+        let pos = ChunkPos::local(i);
+
+        raw.extend_from_slice(&blocks.get_data(pos).raw().to_le_bytes());
+        if let Some(ent) = blocks.get_entity(pos)
+        {
+            ents.push((i as u16, types.encode(ent)));
+        }
+    }
+
+    raw.extend_from_slice(&(ents.len() as u16).to_le_bytes());
+    for (i, payload) in ents
+    {
+        raw.extend_from_slice(&i.to_le_bytes());
+        raw.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        raw.extend(payload);
+    }
+
+    let mut enc = ZlibEncoder::new(Vec::new(), Compression::default());
+
+    // writing to a vec never fails
+    enc.write_all(&raw).unwrap();
+    enc.finish().unwrap()
+}
+
+/// decode a chunk's blocks from a region file, see `encode_chunk`.
+/// fails with a description if the data is damaged
+pub fn decode_chunk(data: &[u8], types: &BlockEntityTypes) -> Result<CBlockBuffer, String>
+{
+    const BLOCKS: usize = 1 + CHUNK_VOLUME * 2;
+
+    let mut raw = Vec::with_capacity(BLOCKS);
+
+    ZlibDecoder::new(data)
+        .read_to_end(&mut raw)
+        .map_err(|e| format!("couldn't decompress chunk: {}", e))?;
+
+    let version = match raw.first()
+    {
+        Some(v @ 1..=CHUNK_VERSION) => *v,
+        Some(v) => return Err(format!("unknown chunk version {}", v)),
+        None => return Err("chunk is empty".to_string()),
+    };
+    if raw.len() < BLOCKS || (version == 1 && raw.len() != BLOCKS)
+    {
+        return Err(format!("chunk is {} bytes, expected {}", raw.len(), BLOCKS));
+    }
+
+    let mut blocks = CBlockBuffer::new();
+    for (i, b) in raw[1..BLOCKS].chunks_exact(2).enumerate()
+    {
+        let block = PackedBlock::new(u16::from_le_bytes([b[0], b[1]]));
+
+        if block.format() == PackedBlockFormat::Addr
+        {
+            return Err(format!("chunk has an address format block at index {}", i));
+        }
+        blocks.set_packed(ChunkPos::local(i), block);
+    }
+    if version == 1
+    {
+        return Ok(blocks);
+    }
+
+    // block entities
+    let mut rest = &raw[BLOCKS..];
+
+    let count = u16::from_le_bytes(take(&mut rest, 2)?.try_into().unwrap());
+    for _ in 0..count
+    {
+        let i = u16::from_le_bytes(take(&mut rest, 2)?.try_into().unwrap()) as usize;
+        let len = u32::from_le_bytes(take(&mut rest, 4)?.try_into().unwrap()) as usize;
+        let payload = take(&mut rest, len)?;
+
+        if i >= CHUNK_VOLUME
+        {
+            return Err(format!("chunk has a block entity at index {}", i));
+        }
+
+        let pos = ChunkPos::local(i);
+        let block = blocks.get_packed(pos);
+        let ent = types.decode(block.id(), block.variant(), payload)?;
+
+        blocks
+            .set_entity(pos, ent)
+            .map_err(|_| "chunk has too many block entities".to_string())?;
+    }
+    Ok(blocks)
+}
+
+/// take `n` bytes off the front of a chunk's block entities
+fn take<'a>(rest: &mut &'a [u8], n: usize) -> Result<&'a [u8], String>
+{
+    if rest.len() < n
+    {
+        return Err("chunk's block entities end early".to_string());
+    }
+    let (head, tail) = rest.split_at(n);
+    *rest = tail;
+
+    Ok(head)
+}
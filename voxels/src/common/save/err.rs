@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+use std::fmt;
+
+/// errors that can occur while reading or writing a
+/// world's save directory
+#[derive(Debug)]
+pub enum SaveError
+{
+    /// a file of the world couldn't be read or written
+    Io { path: PathBuf, err: std::io::Error },
+    /// the world's metadata isn't valid RON, or is
+    /// missing fields
+    Parse { path: PathBuf, msg: String },
+    /// a region file or one of its chunks is damaged,
+    /// or of an unknown version
+    Corrupt { path: PathBuf, msg: String },
+}
+
+impl SaveError
+{
+    /// wrap an io error, given the file it happened on
+    pub(super) fn io(path: impl Into<PathBuf>) -> impl FnOnce(std::io::Error) -> Self
+    {
+        let path = path.into();
+
+        move |err| SaveError::Io { path, err }
+    }
+}
+
+impl fmt::Display for SaveError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            SaveError::Io { path, err } =>
+            {
+                write!(f, "couldn't access {}: {}", path.display(), err)
+            }
+            SaveError::Parse { path, msg } =>
+            {
+                write!(f, "couldn't parse {}: {}", path.display(), msg)
+            }
+            SaveError::Corrupt { path, msg } =>
+            {
+                write!(f, "corrupt save file {}: {}", path.display(), msg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SaveError
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)>
+    {
+        match self
+        {
+            SaveError::Io { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
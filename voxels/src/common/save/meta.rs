@@ -0,0 +1,62 @@
+use std::path::Path;
+use std::fs;
+
+use serde::{ Serialize, Deserialize };
+
+use crate::common::block::BlockNameTable;
+use super::SaveError;
+
+/// everything about a world that isn't stored in its
+/// chunks, saved as `world.ron` in its directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldMeta
+{
+    /// seed the world's terrain is generated from. new
+    /// worlds get a random one, which can be changed here
+    /// before any chunk is saved
+    pub seed: u32,
+    /// global position where players appear
+    pub spawn: [i32; 3],
+    /// text IDs of the numeric block IDs saved chunks use.
+    /// only ever appended to, so chunks saved by older
+    /// palettes stay readable
+    pub palette: BlockNameTable,
+}
+
+impl Default for WorldMeta
+{
+    fn default() -> Self
+    {
+        // new worlds get a different seed every time
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(12345, |d| d.subsec_nanos() ^ d.as_secs() as u32);
+
+        Self
+        {
+            seed,
+            spawn: [48, 12, 48],
+            palette: BlockNameTable::default(),
+        }
+    }
+}
+
+impl WorldMeta
+{
+    /// read the metadata from its file
+    pub fn read(path: &Path) -> Result<Self, SaveError>
+    {
+        let src = fs::read_to_string(path).map_err(SaveError::io(path))?;
+
+        ron::de::from_str(&src).map_err(|e| SaveError::Parse { path: path.to_path_buf(), msg: e.to_string() })
+    }
+
+    /// write the metadata to its file, replacing it
+    pub fn write(&self, path: &Path) -> Result<(), SaveError>
+    {
+        let src = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|e| SaveError::Parse { path: path.to_path_buf(), msg: e.to_string() })?;
+
+        fs::write(path, src).map_err(SaveError::io(path))
+    }
+}
@@ -0,0 +1,19 @@
+mod err;
+mod meta;
+mod codec;
+mod region;
+mod r_save;
+mod s_save;
+
+pub use self::
+{
+    err::*,
+    meta::*,
+    codec::*,
+    region::*,
+    r_save::*,
+    s_save::*,
+};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::path::{ Path, PathBuf };
+use std::fs;
+
+use ezmath::*;
+
+use crate::common::block::{ RBlockPalette, BlockRemap, BlockEntityTypes };
+use crate::common::chunk::CBlockBuffer;
+use super::{ WorldMeta, RegionFile, SaveError, encode_chunk, decode_chunk };
+
+/// resource for the world's save directory, which holds:
+///     - `world.ron` -> the world's metadata, see `WorldMeta`
+///     - `regions/`  -> the region files, named `r.x.y.z.vxr`
+///                      after their position, see `RegionFile`
+#[derive(Debug)]
+pub struct RWorldSave
+{
+    dir: PathBuf,
+    meta: WorldMeta,
+
+    /// open region files, by region position
+    regions: HashMap<int3, RegionFile>,
+
+    /// from the IDs saved chunks use, to the current palette
+    load_remap: BlockRemap,
+    /// from the current palette, to the IDs saved chunks use
+    save_remap: BlockRemap,
+    /// block entity payloads that can be loaded back
+    types: BlockEntityTypes,
+}
+
+impl RWorldSave
+{
+    /// open a world's save directory, creating a new world
+    /// if it doesn't exist. blocks the palette added since
+    /// the world was last saved are added to its name table.
+    /// block entity payload types should be registered on the
+    /// palette before, or their payloads only load as
+    /// `UnknownData`
+    pub fn open<P: AsRef<Path>>(dir: P, pal: &RBlockPalette) -> Result<Self, SaveError>
+    {
+        let dir = dir.as_ref().to_path_buf();
+        let regions = dir.join("regions");
+
+        fs::create_dir_all(&regions).map_err(SaveError::io(&regions))?;
+
+        let path = dir.join("world.ron");
+        let mut meta = if path.exists()
+        {
+            WorldMeta::read(&path)?
+        }
+        else
+        {
+            WorldMeta::default()
+        };
+
+        meta.palette.extend(pal);
+        meta.write(&path)?;
+
+        let load_remap = pal.remap(&meta.palette);
+        let save_remap = pal.remap_to(&meta.palette);
+
+        for name in load_remap.missing()
+        {
+            println!("[warning] block \"{}\" of the saved world is missing, it'll load as air", name);
+        }
+
+        Ok(Self
+        {
+            dir,
+            meta,
+            regions: HashMap::new(),
+            load_remap,
+            save_remap,
+            types: pal.entity_types().clone(),
+        })
+    }
+
+    /// get the world's metadata
+    pub fn meta(&self) -> &WorldMeta
+    {
+        &self.meta
+    }
+
+    /// get the world's metadata mutably. changes are only
+    /// written by `RWorldSave::save_meta`
+    pub fn meta_mut(&mut self) -> &mut WorldMeta
+    {
+        &mut self.meta
+    }
+
+    /// write the world's metadata back to its file
+    pub fn save_meta(&self) -> Result<(), SaveError>
+    {
+        self.meta.write(&self.dir.join("world.ron"))
+    }
+
+    /// load a chunk's blocks given its position, remapped
+    /// to the current palette. gives none if the chunk was
+    /// never saved
+    pub fn load_chunk(&mut self, pos: int3) -> Result<Option<CBlockBuffer>, SaveError>
+    {
+        let path = self.region_path(pos);
+        let data = match self.region(pos)?.read(pos)?
+        {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+
+        let mut blocks = decode_chunk(&data, &self.types).map_err(|msg| SaveError::Corrupt { path, msg })?;
+        blocks.remap(&self.load_remap);
+
+        Ok(Some(blocks))
+    }
+
+    /// save a chunk's blocks given its position, replacing
+    /// what was saved before
+    pub fn save_chunk(&mut self, pos: int3, blocks: &CBlockBuffer) -> Result<(), SaveError>
+    {
+        let data = if self.save_remap.is_identity()
+        {
+            encode_chunk(blocks, &self.types)
+        }
+        else
+        {
+            let mut blocks = blocks.clone();
+            blocks.remap(&self.save_remap);
+
+            encode_chunk(&blocks, &self.types)
+        };
+
+        self.region(pos)?.write(pos, &data)
+    }
+
+    /// get the open region file holding a chunk, opening
+    /// it if needed
+    fn region(&mut self, pos: int3) -> Result<&mut RegionFile, SaveError>
+    {
+        let region = RegionFile::region_of(pos);
+
+        if !self.regions.contains_key(&region)
+        {
+            let file = RegionFile::open(&self.region_path(pos))?;
+
+            self.regions.insert(region, file);
+        }
+        Ok(self.regions.get_mut(&region).unwrap())
+    }
+
+    /// path of the region file holding a chunk
+    fn region_path(&self, pos: int3) -> PathBuf
+    {
+        let r = RegionFile::region_of(pos);
+
+        self.dir
+            .join("regions")
+            .join(format!("r.{}.{}.{}.vxr", r.x, r.y, r.z))
+    }
+}
@@ -0,0 +1,269 @@
+use std::io::{ Read, Write, Seek, SeekFrom };
+use std::path::{ Path, PathBuf };
+use std::fs::{ File, OpenOptions };
+
+use ezmath::*;
+
+use crate::common::CHUNK_SIZE;
+use super::SaveError;
+
+/// a file storing the chunks of a REGION_SIZE^3 cube of the
+/// world. it starts with a header:
+///     - 4 bytes  -> magic, "VXRG"
+///     - 4 bytes  -> version, little endian u32
+///     - 8 bytes  -> per chunk, sector offset and byte length
+///                   as little endian u32's. 0, 0 if absent
+///
+/// and is followed by the chunks' encoded data, each starting
+/// on a SECTOR-byte boundary. chunks are rewritten in place
+/// when they still fit in their sectors, and moved to the
+/// first free sectors they fit in otherwise, which may be at
+/// the end of the file
+#[derive(Debug)]
+pub struct RegionFile
+{
+    path: PathBuf,
+    file: File,
+
+    /// sector offset and byte length of each chunk
+    table: Vec<(u32, u32)>,
+    /// which sectors hold the header or a chunk's data
+    used: Vec<bool>,
+}
+
+impl RegionFile
+{
+    /// size of a region, in chunks per axis
+    pub const REGION_SIZE: usize = 8;
+    /// unit in which space is allocated for chunks
+    pub const SECTOR: usize = 4096;
+
+    const MAGIC: &'static [u8; 4] = b"VXRG";
+    const VERSION: u32 = 1;
+
+    /// number of chunks in a region
+    const CHUNKS: usize = Self::REGION_SIZE * Self::REGION_SIZE * Self::REGION_SIZE;
+    /// size of the header, in bytes
+    const HEADER: usize = 8 + Self::CHUNKS * 8;
+    /// size of the header, in sectors
+    const HEADER_SECTORS: usize = (Self::HEADER + Self::SECTOR - 1) / Self::SECTOR;
+
+    /// open a region file, creating an empty one if it
+    /// doesn't exist
+    pub fn open(path: &Path) -> Result<Self, SaveError>
+    {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(path)
+            .map_err(SaveError::io(path))?;
+
+        let len = file
+            .metadata()
+            .map_err(SaveError::io(path))?
+            .len();
+
+        let mut region = Self
+        {
+            path: path.to_path_buf(),
+            file,
+            table: vec![(0, 0); Self::CHUNKS],
+            used: vec![true; Self::HEADER_SECTORS],
+        };
+
+        if len == 0
+        {
+            region.write_header()?;
+        }
+        else
+        {
+            region.read_header()?;
+        }
+        for i in 0..Self::CHUNKS
+        {
+            let (sector, len) = region.table[i];
+
+            region.mark(sector, len, true);
+        }
+        Ok(region)
+    }
+
+    /// get the position of the region that holds a chunk,
+    /// in regions
+    pub fn region_of(mut chunk: int3) -> int3
+    {
+        const SIZE: i32 = (CHUNK_SIZE * RegionFile::REGION_SIZE) as i32;
+
+        crate::common::chunk::ChunkPos::adjust(&mut chunk);
+
+        int3::new(chunk.x.div_euclid(SIZE), chunk.y.div_euclid(SIZE), chunk.z.div_euclid(SIZE))
+    }
+
+    /// read the encoded data of a chunk, given its position.
+    /// gives none if the chunk was never written
+    pub fn read(&mut self, chunk: int3) -> Result<Option<Vec<u8>>, SaveError>
+    {
+        let (sector, len) = self.table[Self::slot(chunk)];
+        if len == 0
+        {
+            return Ok(None);
+        }
+
+        let mut data = vec![0; len as usize];
+
+        self.file
+            .seek(SeekFrom::Start(sector as u64 * Self::SECTOR as u64))
+            .and_then(|_| self.file.read_exact(&mut data))
+            .map_err(SaveError::io(&self.path))?;
+
+        Ok(Some(data))
+    }
+
+    /// write the encoded data of a chunk, given its position,
+    /// replacing what was there
+    pub fn write(&mut self, chunk: int3, data: &[u8]) -> Result<(), SaveError>
+    {
+        let slot = Self::slot(chunk);
+        let (old, old_len) = self.table[slot];
+
+        // reuse the chunk's sectors, or take the first free
+        // ones that fit
+        self.mark(old, old_len, false);
+
+        let sector = if old != 0 && Self::sectors(data.len() as u32) <= Self::sectors(old_len)
+        {
+            old
+        }
+        else
+        {
+            self.find_free(Self::sectors(data.len() as u32))
+        };
+
+        self.mark(sector, data.len() as u32, true);
+        self.file
+            .seek(SeekFrom::Start(sector as u64 * Self::SECTOR as u64))
+            .and_then(|_| self.file.write_all(data))
+            .map_err(SaveError::io(&self.path))?;
+
+        self.table[slot] = (sector, data.len() as u32);
+        self.write_entry(slot)
+    }
+
+    /// number of sectors `len` bytes take
+    fn sectors(len: u32) -> usize
+    {
+        (len as usize + Self::SECTOR - 1) / Self::SECTOR
+    }
+
+    /// mark the sectors of a chunk's data as used or free
+    fn mark(&mut self, sector: u32, len: u32, used: bool)
+    {
+        let range = sector as usize..sector as usize + Self::sectors(len);
+
+        if self.used.len() < range.end
+        {
+            self.used.resize(range.end, false);
+        }
+        self.used[range].iter_mut().for_each(|u| *u = used);
+    }
+
+    /// first sector of a run of `count` free sectors, past the
+    /// end of the file if none is long enough
+    fn find_free(&self, count: usize) -> u32
+    {
+        let mut start = Self::HEADER_SECTORS;
+
+        for (i, used) in self.used.iter().enumerate().skip(Self::HEADER_SECTORS)
+        {
+            if *used
+            {
+                start = i + 1;
+            }
+            else if i + 1 - start >= count
+            {
+                break;
+            }
+        }
+        start as u32
+    }
+
+    /// index of a chunk in the header
+    fn slot(mut chunk: int3) -> usize
+    {
+        const SIZE: i32 = RegionFile::REGION_SIZE as i32;
+
+        crate::common::chunk::ChunkPos::adjust(&mut chunk);
+
+        let c = chunk / CHUNK_SIZE as i32;
+        let (x, y, z) = (c.x.rem_euclid(SIZE), c.y.rem_euclid(SIZE), c.z.rem_euclid(SIZE));
+
+        (x + y * SIZE + z * SIZE * SIZE) as usize
+    }
+
+    /// read and check the header
+    fn read_header(&mut self) -> Result<(), SaveError>
+    {
+        let mut header = vec![0; Self::HEADER];
+
+        self.file
+            .seek(SeekFrom::Start(0))
+            .and_then(|_| self.file.read_exact(&mut header))
+            .map_err(SaveError::io(&self.path))?;
+
+        if &header[0..4] != Self::MAGIC
+        {
+            return Err(SaveError::Corrupt { path: self.path.clone(), msg: "not a region file".to_string() });
+        }
+        let version = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        if version != Self::VERSION
+        {
+            return Err(SaveError::Corrupt { path: self.path.clone(), msg: format!("unknown region version {}", version) });
+        }
+
+        for (entry, b) in self.table.iter_mut().zip(header[8..].chunks_exact(8))
+        {
+            *entry =
+            (
+                u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+                u32::from_le_bytes([b[4], b[5], b[6], b[7]]),
+            );
+        }
+        Ok(())
+    }
+
+    /// write the whole header, for new files
+    fn write_header(&mut self) -> Result<(), SaveError>
+    {
+        let mut header = Vec::with_capacity(Self::HEADER_SECTORS * Self::SECTOR);
+
+        header.extend_from_slice(Self::MAGIC);
+        header.extend_from_slice(&Self::VERSION.to_le_bytes());
+        for (sector, len) in &self.table
+        {
+            header.extend_from_slice(&sector.to_le_bytes());
+            header.extend_from_slice(&len.to_le_bytes());
+        }
+        header.resize(Self::HEADER_SECTORS * Self::SECTOR, 0);
+
+        self.file
+            .seek(SeekFrom::Start(0))
+            .and_then(|_| self.file.write_all(&header))
+            .map_err(SaveError::io(&self.path))
+    }
+
+    /// write a single header entry, after its chunk moved
+    fn write_entry(&mut self, slot: usize) -> Result<(), SaveError>
+    {
+        let (sector, len) = self.table[slot];
+
+        let mut entry = [0; 8];
+        entry[..4].copy_from_slice(&sector.to_le_bytes());
+        entry[4..].copy_from_slice(&len.to_le_bytes());
+
+        self.file
+            .seek(SeekFrom::Start((8 + slot * 8) as u64))
+            .and_then(|_| self.file.write_all(&entry))
+            .map_err(SaveError::io(&self.path))
+    }
+}
@@ -0,0 +1,103 @@
+use ezgame::time::evt;
+use ezgame::ecs::*;
+
+use crate::common::chunk::{ CChunk, CBlockBuffer, RChunkCache, SChunkGen, TUngenerated, TUnlit, TUpdated, TUnsaved };
+use crate::common::light::SLight;
+use super::RWorldSave;
+
+/// system that loads ungenerated chunks from the world's
+/// save, before SChunkGen generates those that weren't
+pub struct SChunkRead;
+
+/// system that saves chunks whose blocks changed
+pub struct SChunkWrite;
+
+impl System for SChunkRead
+{
+    const EVENT: Event = evt::UPDATE;
+    const ORDER: Order = SChunkGen::ORDER - 1;
+
+    const FLUSH: bool = true;
+
+    fn exe() -> Job
+    {
+        // begin...
+        sys("chunk_read_system")
+        // components...
+        .with_query
+        (
+            <(Read<CChunk>, Write<CBlockBuffer>)>::query()
+                .filter(tag::<TUngenerated>())
+        )
+        // resources...
+        .write_resource::<RWorldSave>()
+        .write_resource::<RChunkCache>()
+        // system...
+        .build(|cmd, world, (r_save, r_cache), q_chunks|
+        {
+            for (ent, (chunk, mut blocks)) in q_chunks.iter_entities_mut(world)
+            {
+                match r_save.load_chunk(chunk.pos())
+                {
+                    Ok(Some(loaded)) => *blocks = loaded,
+                    // never saved, generate it
+                    Ok(None) => continue,
+                    Err(e) =>
+                    {
+                        println!("[error] couldn't load chunk, it'll be generated: {}", e);
+                        continue;
+                    }
+                }
+
+                r_cache.mark_generated(chunk.pos());
+
+                // remove and set tags
+                cmd.remove_tag::<TUngenerated>(ent);
+                cmd.add_tag(ent, TUnlit);
+                cmd.add_tag(ent, TUpdated);
+
+                // neighbors meshed their border as if this chunk
+                // was missing
+                for n in r_cache.generated_neighbors(chunk.pos())
+                {
+                    cmd.add_tag(n, TUpdated);
+                }
+            }
+        })
+    }
+}
+
+impl System for SChunkWrite
+{
+    const EVENT: Event = evt::UPDATE;
+    const ORDER: Order = SLight::ORDER + 1;
+
+    const FLUSH: bool = true;
+
+    fn exe() -> Job
+    {
+        // begin...
+        sys("chunk_write_system")
+        // components...
+        .with_query
+        (
+            <(Read<CChunk>, Read<CBlockBuffer>)>::query()
+                .filter(tag::<TUnsaved>())
+        )
+        // resources...
+        .write_resource::<RWorldSave>()
+        // system...
+        .build(|cmd, world, r_save, q_chunks|
+        {
+            for (ent, (chunk, blocks)) in q_chunks.iter_entities(world)
+            {
+                if let Err(e) = r_save.save_chunk(chunk.pos(), &blocks)
+                {
+                    println!("[error] couldn't save chunk: {}", e);
+                }
+
+                cmd.remove_tag::<TUnsaved>(ent);
+            }
+        })
+    }
+}
@@ -0,0 +1,224 @@
+use serde::{ Serialize, Deserialize };
+use ezgame::ecs::*;
+use ezmath::*;
+
+use crate::common::chunk::{ CBlockBuffer, BlockBufferIndex, ChunkPos, TUnsaved };
+use crate::common::block::{ PackedBlock, RBlockPalette, BlockEntity, BlockEntityTypes, UnknownData };
+use crate::common::testing::{ self, TestWorld };
+use crate::common::CHUNK_VOLUME;
+use super::*;
+
+/// block entity payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Sign
+{
+    lines: Vec<String>,
+}
+
+fn palette() -> RBlockPalette
+{
+    let mut pal = testing::palette();
+
+    pal.entity_types_mut().register::<Sign>("sign");
+    pal
+}
+
+fn assert_same(a: &CBlockBuffer, b: &CBlockBuffer)
+{
+    for i in 0..CHUNK_VOLUME
+    {
+        let pos = ChunkPos::local(i);
+
+        assert_eq!(a.get_packed(pos), b.get_packed(pos), "blocks differ at {:?}", pos);
+    }
+}
+
+/// buffer with `n` distinct blocks, scattered around
+fn varied(n: u16) -> CBlockBuffer
+{
+    let mut blocks = CBlockBuffer::new();
+
+    for i in 0..CHUNK_VOLUME
+    {
+        let id = (i * 7919) as u16 % n;
+
+        blocks.set_packed(ChunkPos::local(i), PackedBlock::from_data(id / 16, id % 16));
+    }
+    blocks
+}
+
+#[test]
+fn test_chunk_codec()
+{
+    let types = BlockEntityTypes::default();
+
+    let mut one = CBlockBuffer::new();
+    one.set_packed(int3::new(3, 1, 4), PackedBlock::from_data(5, 9));
+
+    // uniform, paletted, and direct storage
+    for blocks in &[CBlockBuffer::new(), one, varied(3), varied(40), varied(1000)]
+    {
+        let decoded = decode_chunk(&encode_chunk(blocks, &types), &types).unwrap();
+
+        assert_same(blocks, &decoded);
+    }
+
+    assert!(decode_chunk(&[1, 2, 3], &types).is_err());
+}
+
+#[test]
+fn test_region_file()
+{
+    let path = testing::temp_dir("region").join("r.0.0.0.vxr");
+
+    let a = int3::new(0, 0, 0);
+    let b = int3::new(32, 64, -32);
+    {
+        let mut region = RegionFile::open(&path).unwrap();
+
+        region.write(a, &[1; 10]).unwrap();
+        region.write(b, &[2; 5000]).unwrap();
+        // grows past its sector, and is moved
+        region.write(a, &[3; 9000]).unwrap();
+        // shrinks, and stays
+        region.write(b, &[4; 20]).unwrap();
+    }
+
+    let mut region = RegionFile::open(&path).unwrap();
+
+    assert_eq!(region.read(a).unwrap(), Some(vec![3; 9000]));
+    assert_eq!(region.read(b).unwrap(), Some(vec![4; 20]));
+    assert_eq!(region.read(int3::new(64, 0, 0)).unwrap(), None);
+}
+
+#[test]
+fn test_region_reuse()
+{
+    let path = testing::temp_dir("region-reuse").join("r.0.0.0.vxr");
+    let len = || std::fs::metadata(&path).unwrap().len();
+
+    let (a, b, c) = (int3::new(0, 0, 0), int3::new(32, 0, 0), int3::new(64, 0, 0));
+    {
+        let mut region = RegionFile::open(&path).unwrap();
+
+        region.write(a, &[1; 9000]).unwrap();
+        region.write(b, &[2; 100]).unwrap();
+        // grows past b, and leaves its sectors free
+        region.write(a, &[3; 13000]).unwrap();
+    }
+    let end = len();
+
+    // free sectors are found again after reopening
+    let mut region = RegionFile::open(&path).unwrap();
+
+    region.write(c, &[4; 12000]).unwrap();
+    assert_eq!(len(), end);
+
+    // a shrinks, and b grows into the sectors it left
+    region.write(a, &[5; 100]).unwrap();
+    region.write(b, &[6; 8000]).unwrap();
+    assert_eq!(len(), end);
+
+    assert_eq!(region.read(a).unwrap(), Some(vec![5; 100]));
+    assert_eq!(region.read(b).unwrap(), Some(vec![6; 8000]));
+    assert_eq!(region.read(c).unwrap(), Some(vec![4; 12000]));
+}
+
+#[test]
+fn test_world_save()
+{
+    let dir = testing::temp_dir("world");
+    let pal = palette();
+
+    let mut blocks = CBlockBuffer::new();
+    for i in 0..CHUNK_VOLUME / 2
+    {
+        let block = if i % 3 == 0 { "grass" } else { "dirt" };
+
+        blocks.set_packed(ChunkPos::local(i), pal.block(block).unwrap());
+    }
+
+    // chunks on both sides of a region border
+    let positions = [int3::new(0, 0, 0), int3::new(-32, 0, 0), int3::new(256, -32, 0)];
+    {
+        let mut save = RWorldSave::open(&dir, &pal).unwrap();
+
+        for pos in &positions
+        {
+            save.save_chunk(*pos, &blocks).unwrap();
+        }
+    }
+
+    let mut save = RWorldSave::open(&dir, &pal).unwrap();
+    for pos in &positions
+    {
+        assert_same(&blocks, &save.load_chunk(*pos).unwrap().unwrap());
+    }
+    assert!(save.load_chunk(int3::new(32, 0, 0)).unwrap().is_none());
+    assert_eq!(save.meta().palette, pal.name_table());
+}
+
+#[test]
+fn test_chunk_entities()
+{
+    let pal = palette();
+    let sign = Sign { lines: vec!["hello".to_string(), "world".to_string()] };
+    let log = pal.id("log").unwrap();
+
+    let mut blocks = varied(40);
+    let (a, b) = (int3::new(1, 2, 3), int3::new(31, 31, 31));
+
+    blocks.set_entity(a, BlockEntity::new(log, 2, sign.clone())).unwrap();
+    // not registered
+    blocks.set_entity(b, BlockEntity::new(log, 0, vec![4u32, 5, 6])).unwrap();
+
+    let decoded = decode_chunk(&encode_chunk(&blocks, pal.entity_types()), pal.entity_types()).unwrap();
+
+    assert_eq!(decoded.get_data(a), PackedBlock::from_data(log, 2));
+    assert_eq!(decoded.get_entity(a).unwrap().data::<Sign>(), Some(&sign));
+    assert_eq!(decoded.get_data(int3::new(0, 0, 0)), blocks.get_data(int3::new(0, 0, 0)));
+    assert_eq!(decoded.entities().len(), 2);
+
+    // unknown payloads are kept as they were
+    assert!(decoded.get_entity(b).unwrap().data::<UnknownData>().is_some());
+
+    let mut types = BlockEntityTypes::default();
+    types.register::<Vec<u32>>(std::any::type_name::<Vec<u32>>());
+
+    let again = decode_chunk(&encode_chunk(&decoded, pal.entity_types()), &types).unwrap();
+    assert_eq!(again.get_entity(b).unwrap().data::<Vec<u32>>(), Some(&vec![4, 5, 6]));
+}
+
+#[test]
+fn test_chunk_write()
+{
+    let dir = testing::temp_dir("write");
+    let pos = int3::new(32, 0, -32);
+
+    let mut world = TestWorld::new();
+    let (save, grass) =
+    {
+        let r_pal = world.resources.get::<RBlockPalette>().unwrap();
+
+        (RWorldSave::open(&dir, &r_pal).unwrap(), r_pal.block("grass").unwrap())
+    };
+    world.resources.insert(save);
+
+    // an edited chunk, then the write path
+    let mut blocks = CBlockBuffer::new();
+    blocks.set_packed(int3::new(1, 1, 2), grass);
+
+    let ent = world.chunk(pos, blocks);
+    world.registry.add_tag(ent, TUnsaved).unwrap();
+
+    world.run(SChunkWrite::exe());
+
+    // saved once
+    assert!(world.registry.get_tag::<TUnsaved>(ent).is_none());
+    drop(world);
+
+    let loaded = RWorldSave::open(&dir, &testing::palette()).unwrap().load_chunk(pos).unwrap().unwrap();
+
+    assert_eq!(loaded.get_packed(int3::new(1, 1, 2)), grass);
+    assert_eq!(loaded.get_packed(int3::new(0, 0, 0)), PackedBlock::default());
+}
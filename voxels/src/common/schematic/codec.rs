@@ -0,0 +1,129 @@
+use std::io::{ Read, Write };
+
+use flate2::{ Compression, read::ZlibDecoder, write::ZlibEncoder };
+use ezmath::*;
+
+use super::Schematic;
+use super::reader::Reader;
+
+/// magic bytes starting every schematic file
+const MAGIC: &[u8; 4] = b"VXSC";
+/// version of the schematic encoding, bumped on every change
+const VERSION: u8 = 1;
+
+/// encode a schematic. the encoding is the magic bytes and
+/// a version byte, followed by the zlib compressed body, with
+/// every number little endian:
+///     - 3 i32    -> size
+///     - u16      -> number of palette entries, each a u16
+///                   byte length and its UTF-8 text ID
+///     - size u16 -> blocks, see `Schematic::blocks`
+///     - u32      -> number of block entities, each 3 i32
+///                   position, a u32 byte length and its payload
+pub(super) fn encode(schem: &Schematic) -> Vec<u8>
+{
+    let mut body = Vec::new();
+
+    for c in schem.size.iter()
+    {
+        body.extend_from_slice(&c.to_le_bytes());
+    }
+
+    body.extend_from_slice(&(schem.palette.len() as u16).to_le_bytes());
+    for name in &schem.palette
+    {
+        body.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        body.extend_from_slice(name.as_bytes());
+    }
+
+    for block in &schem.blocks
+    {
+        body.extend_from_slice(&block.to_le_bytes());
+    }
+
+    body.extend_from_slice(&(schem.entities.len() as u32).to_le_bytes());
+    for (pos, payload) in &schem.entities
+    {
+        for c in pos.iter()
+        {
+            body.extend_from_slice(&c.to_le_bytes());
+        }
+        body.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        body.extend_from_slice(payload);
+    }
+
+    let mut out = Vec::with_capacity(body.len() / 2);
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+
+    let mut enc = ZlibEncoder::new(out, Compression::default());
+
+    // writing to a vec never fails
+    enc.write_all(&body).unwrap();
+    enc.finish().unwrap()
+}
+
+/// decode a schematic, see `encode`. fails with a description
+/// if the data is damaged
+pub(super) fn decode(data: &[u8]) -> Result<Schematic, String>
+{
+    if data.len() < 5 || &data[..4] != MAGIC
+    {
+        return Err("not a schematic".to_string());
+    }
+    if data[4] != VERSION
+    {
+        return Err(format!("unknown schematic version {}", data[4]));
+    }
+
+    let mut body = Vec::new();
+    ZlibDecoder::new(&data[5..])
+        .read_to_end(&mut body)
+        .map_err(|e| format!("couldn't decompress schematic: {}", e))?;
+
+    let mut r = Reader::new(&body, "schematic");
+
+    let size = int3::new(r.i32()?, r.i32()?, r.i32()?);
+    if size.iter().any(|c| *c < 0)
+    {
+        return Err(format!("schematic has a negative size {:?}", size));
+    }
+    let volume = size.x as u64 * size.y as u64 * size.z as u64;
+    if volume * 2 > body.len() as u64
+    {
+        return Err(format!("schematic of size {:?} is too big for its data", size));
+    }
+
+    let palette = (0..r.u16()?)
+        .map(|_|
+        {
+            let len = r.u16()? as usize;
+
+            String::from_utf8(r.bytes(len)?.to_vec()).map_err(|_| "palette entry isn't UTF-8".to_string())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let blocks = (0..volume)
+        .map(|_| r.u16())
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(bad) = blocks.iter().find(|b| **b != Schematic::VOID && (**b >> 4) as usize >= palette.len())
+    {
+        return Err(format!("block {:#x} is out of the palette", bad));
+    }
+
+    let entities = (0..r.u32()?)
+        .map(|_|
+        {
+            let pos = int3::new(r.i32()?, r.i32()?, r.i32()?);
+            let len = r.u32()? as usize;
+
+            Ok((pos, r.bytes(len)?.to_vec()))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    if !r.rest().is_empty()
+    {
+        return Err(format!("schematic has {} trailing bytes", r.rest().len()));
+    }
+    Ok(Schematic { size, palette, blocks, entities })
+}
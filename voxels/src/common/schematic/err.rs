@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+use std::fmt;
+
+/// errors that can occur while reading or writing a
+/// schematic file
+#[derive(Debug)]
+pub enum SchematicError
+{
+    /// the file couldn't be read or written
+    Io { path: PathBuf, err: std::io::Error },
+    /// the file isn't a schematic, is damaged, or is of
+    /// an unknown version
+    Corrupt { path: PathBuf, msg: String },
+}
+
+impl fmt::Display for SchematicError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            SchematicError::Io { path, err } =>
+            {
+                write!(f, "couldn't access {}: {}", path.display(), err)
+            }
+            SchematicError::Corrupt { path, msg } =>
+            {
+                write!(f, "corrupt schematic {}: {}", path.display(), msg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SchematicError
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)>
+    {
+        match self
+        {
+            SchematicError::Io { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
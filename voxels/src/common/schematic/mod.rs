@@ -0,0 +1,17 @@
+mod schematic;
+mod codec;
+mod ops;
+mod vox;
+mod reader;
+mod err;
+
+pub use self::
+{
+    schematic::*,
+    ops::*,
+    vox::*,
+    err::*,
+};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,64 @@
+use ezmath::*;
+
+use crate::common::chunk::WorldBlocks;
+use crate::common::block::{ PackedBlock, RBlockPalette };
+use crate::common::edit::BlockRegion;
+use super::Schematic;
+
+/// moving structures between the world and schematics
+/// ```rust
+/// let hut = blocks.export_schematic(region, &r_pal);
+/// hut.save("structures/hut.vxs")?;
+///
+/// let hut = Schematic::load("structures/hut.vxs")?;
+/// blocks.paste_schematic(&hut, int3::new(40, 12, 40), &r_pal);
+/// ```
+impl<'a> WorldBlocks<'a>
+{
+    /// export the blocks of a region as a schematic, with their
+    /// block entities. blocks that aren't loaded are void
+    pub fn export_schematic(&self, region: BlockRegion, pal: &RBlockPalette) -> Schematic
+    {
+        let mut schem = Schematic::from_clipboard(&self.copy(region), pal);
+
+        self.get_entities(region.min, region.max, |pos, ent|
+        {
+            schem.set_entity(pos - region.min, pal.entity_types().encode(ent));
+        });
+        schem
+    }
+
+    /// paste a schematic, with its min corner at `at`, then its
+    /// block entities over their blocks. void blocks are left
+    /// untouched. returns the number of blocks changed
+    pub fn paste_schematic(&mut self, schem: &Schematic, at: int3, pal: &RBlockPalette) -> usize
+    {
+        let changed = self.paste(&schem.to_clipboard(pal), at);
+
+        for (pos, payload) in schem.entities()
+        {
+            // block entities take the type of the block pasted
+            // under them
+            let block = match self.get_block(at + *pos)
+            {
+                Some(block) if block != PackedBlock::default() => block,
+                _ => continue,
+            };
+            let ent = match pal.entity_types().decode(block.id(), block.variant(), payload)
+            {
+                Ok(ent) => ent,
+                Err(e) =>
+                {
+                    println!("[warning] couldn't paste block entity at {:?}: {}", at + *pos, e);
+                    continue;
+                }
+            };
+
+            if self.set_entity(at + *pos, ent).is_err()
+            {
+                println!("[warning] couldn't paste block entity at {:?}, its chunk is full", at + *pos);
+            }
+        }
+        changed
+    }
+}
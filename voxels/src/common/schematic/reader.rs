@@ -0,0 +1,51 @@
+/// reads little endian numbers off the front of a slice, for
+/// the schematic and .vox decoders. running out of bytes fails
+/// with a description naming what's being read
+pub(super) struct Reader<'a>
+{
+    data: &'a [u8],
+    /// what's being read, ie. "schematic"
+    what: &'static str,
+}
+
+impl<'a> Reader<'a>
+{
+    /// read a slice, given a description of what it is
+    pub fn new(data: &'a [u8], what: &'static str) -> Self
+    {
+        Self { data, what }
+    }
+
+    /// get the bytes that weren't read yet
+    pub fn rest(&self) -> &'a [u8]
+    {
+        self.data
+    }
+
+    pub fn bytes(&mut self, n: usize) -> Result<&'a [u8], String>
+    {
+        if self.data.len() < n
+        {
+            return Err(format!("{} ends early", self.what));
+        }
+        let (head, tail) = self.data.split_at(n);
+        self.data = tail;
+
+        Ok(head)
+    }
+
+    pub fn u16(&mut self) -> Result<u16, String>
+    {
+        self.bytes(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, String>
+    {
+        self.bytes(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub fn i32(&mut self) -> Result<i32, String>
+    {
+        self.u32().map(|n| n as i32)
+    }
+}
@@ -0,0 +1,144 @@
+use std::path::Path;
+
+use ezmath::*;
+
+use crate::common::block::{ PackedBlock, PackedBlockFormat, RBlockPalette };
+use crate::common::edit::Clipboard;
+use super::{ SchematicError, codec };
+
+/// a structure of blocks, saved apart from any world so it
+/// can be moved between worlds and tools. blocks are stored
+/// against the schematic's own name palette, so they survive
+/// numeric ID changes in `RBlockPalette`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schematic
+{
+    /// number of blocks along each axis
+    pub(super) size: int3,
+    /// text ID of each block type used
+    pub(super) palette: Vec<String>,
+    /// per block, in x, y, then z order, its index in the
+    /// palette and its variant, packed as `index << 4 | variant`.
+    /// `Schematic::VOID` blocks are left untouched when pasted
+    pub(super) blocks: Vec<u16>,
+    /// block entity payloads, by relative position, see
+    /// `BlockEntityTypes::encode`
+    pub(super) entities: Vec<(int3, Vec<u8>)>,
+}
+
+impl Schematic
+{
+    /// block that isn't part of the structure
+    pub const VOID: u16 = u16::MAX;
+
+    /// create a schematic out of copied blocks. blocks that
+    /// weren't copied are void
+    pub fn from_clipboard(clip: &Clipboard, pal: &RBlockPalette) -> Self
+    {
+        let mut palette: Vec<String> = Vec::new();
+
+        let blocks = clip
+            .iter()
+            .map(|(_, block)| match block
+            {
+                Some(block) if block.format() == PackedBlockFormat::Data =>
+                {
+                    let name = pal.text_id(block.id()).unwrap_or("air");
+                    let index = match palette.iter().position(|n| n == name)
+                    {
+                        Some(i) => i,
+                        None =>
+                        {
+                            palette.push(name.to_string());
+                            palette.len() - 1
+                        }
+                    };
+                    (index as u16) << 4 | block.variant()
+                }
+                _ => Self::VOID,
+            })
+            .collect();
+
+        Self
+        {
+            size: clip.size(),
+            palette,
+            blocks,
+            entities: Vec::new(),
+        }
+    }
+
+    /// get the blocks of this schematic against a palette.
+    /// void blocks are none, and blocks the palette doesn't
+    /// have become air
+    pub fn to_clipboard(&self, pal: &RBlockPalette) -> Clipboard
+    {
+        let ids: Vec<u16> = self.palette
+            .iter()
+            .map(|name| pal.id(name).unwrap_or(0))
+            .collect();
+
+        let mut clip = Clipboard::new(self.size);
+        let positions: Vec<int3> = clip.iter().map(|(pos, _)| pos).collect();
+
+        for (pos, raw) in positions.into_iter().zip(&self.blocks)
+        {
+            if *raw == Self::VOID
+            {
+                continue;
+            }
+            let id = ids.get((*raw >> 4) as usize).copied().unwrap_or(0);
+
+            clip.set(pos, Some(if id == 0 { PackedBlock::default() } else { PackedBlock::from_data(id, *raw & 0xf) }));
+        }
+        clip
+    }
+
+    /// number of blocks along each axis
+    pub fn size(&self) -> int3
+    {
+        self.size
+    }
+
+    /// text ID of each block type this schematic uses
+    pub fn palette(&self) -> &[String]
+    {
+        &self.palette
+    }
+
+    /// block entity payloads, by relative position. these
+    /// are opaque to the schematic, and kept as-is
+    pub fn entities(&self) -> &[(int3, Vec<u8>)]
+    {
+        &self.entities
+    }
+
+    /// attach a block entity payload to a relative position,
+    /// replacing the one it had
+    pub fn set_entity(&mut self, pos: int3, payload: Vec<u8>)
+    {
+        self.entities.retain(|(p, _)| *p != pos);
+        self.entities.push((pos, payload));
+    }
+
+    /// read a schematic file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SchematicError>
+    {
+        let path = path.as_ref();
+
+        let data = std::fs::read(path)
+            .map_err(|err| SchematicError::Io { path: path.to_path_buf(), err })?;
+
+        codec::decode(&data).map_err(|msg| SchematicError::Corrupt { path: path.to_path_buf(), msg })
+    }
+
+    /// write this schematic to a file, replacing it if it
+    /// exists
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SchematicError>
+    {
+        let path = path.as_ref();
+
+        std::fs::write(path, codec::encode(self))
+            .map_err(|err| SchematicError::Io { path: path.to_path_buf(), err })
+    }
+}
@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use ezmath::*;
+
+use crate::common::chunk::{ CBlockBuffer, BlockBufferIndex };
+use crate::common::block::{ PackedBlock, RBlockPalette };
+use crate::common::edit::{ Clipboard, BlockRegion };
+use crate::common::testing::{ palette, sign, TestWorld };
+use super::*;
+use super::codec;
+
+/// small structure, with air and a void block
+fn structure(pal: &RBlockPalette) -> Clipboard
+{
+    let mut clip = Clipboard::new(int3::new(3, 2, 2));
+
+    for (pos, _) in Clipboard::new(clip.size()).iter()
+    {
+        let block = match pos.y
+        {
+            0 => pal.block("stone").unwrap(),
+            _ => PackedBlock::default(),
+        };
+        clip.set(pos, Some(block));
+    }
+    clip.set(int3::new(1, 1, 1), Some(PackedBlock::from_data(pal.id("log").unwrap(), 1)));
+    clip.set(int3::new(2, 1, 0), None);
+
+    clip
+}
+
+#[test]
+fn test_schematic_codec()
+{
+    let pal = palette();
+
+    let mut schem = Schematic::from_clipboard(&structure(&pal), &pal);
+    schem.set_entity(int3::new(1, 1, 1), vec![1, 2, 3]);
+
+    let decoded = codec::decode(&codec::encode(&schem)).unwrap();
+    assert_eq!(decoded, schem);
+
+    assert!(codec::decode(b"VXSC").is_err());
+    assert!(codec::decode(&codec::encode(&schem)[..12]).is_err());
+}
+
+#[test]
+fn test_schematic_palette()
+{
+    let pal = palette();
+    let clip = structure(&pal);
+
+    let schem = Schematic::from_clipboard(&clip, &pal);
+
+    // only the names used, in order of appearance
+    assert_eq!(schem.palette(), &["stone".to_string(), "air".to_string(), "log".to_string()][..]);
+    assert_eq!(schem.to_clipboard(&pal), clip);
+
+    // renamed blocks become air, void stays void
+    let mut renamed = schem.clone();
+    renamed.palette[0] = "granite".to_string();
+
+    let clip = renamed.to_clipboard(&pal);
+    assert_eq!(clip.get(int3::zeros()), Some(PackedBlock::default()));
+    assert_eq!(clip.get(int3::new(2, 1, 0)), None);
+}
+
+#[test]
+fn test_schematic_entities()
+{
+    let mut world = TestWorld::new();
+
+    let mut blocks = CBlockBuffer::new();
+    blocks.set_entity(int3::new(2, 1, 2), sign("hello")).unwrap();
+
+    let ent = world.chunk(int3::zeros(), blocks);
+
+    // export around the sign, then paste it further
+    let mut pal = palette();
+    pal.entity_types_mut().register::<String>("text");
+
+    let schem = world.edit(move |blocks|
+    {
+        let schem = blocks.export_schematic(BlockRegion::new(int3::new(1, 0, 1), int3::new(3, 2, 3)), &pal);
+        blocks.paste_schematic(&schem, int3::new(10, 4, 10), &pal);
+
+        schem
+    });
+    assert_eq!(schem.entities().len(), 1);
+    assert_eq!(schem.entities()[0].0, int3::new(1, 1, 1));
+
+    let blocks = world.registry.get_component::<CBlockBuffer>(ent).unwrap();
+    let pasted = blocks.get_entity(int3::new(11, 5, 11)).unwrap();
+
+    assert_eq!(pasted.data::<String>().map(|s| s.as_str()), Some("hello"));
+    assert_eq!(blocks.get_data(int3::new(11, 5, 11)), PackedBlock::from_data(3, 2));
+    assert_eq!(blocks.entities().len(), 2);
+}
+
+#[test]
+fn test_schematic_file()
+{
+    let pal = palette();
+    let path = std::env::temp_dir().join(format!("voxels-schematic-{}.vxs", std::process::id()));
+
+    let schem = Schematic::from_clipboard(&structure(&pal), &pal);
+    schem.save(&path).unwrap();
+
+    assert_eq!(Schematic::load(&path).unwrap(), schem);
+
+    std::fs::write(&path, b"nope").unwrap();
+    assert!(Schematic::load(&path).is_err());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+/// a .vox chunk, with its ID, content, and children
+fn chunk(id: &[u8; 4], content: &[u8], children: &[u8]) -> Vec<u8>
+{
+    let mut out = id.to_vec();
+
+    out.extend_from_slice(&(content.len() as u32).to_le_bytes());
+    out.extend_from_slice(&(children.len() as u32).to_le_bytes());
+    out.extend_from_slice(content);
+    out.extend_from_slice(children);
+    out
+}
+
+/// a .vox file, whose MAIN chunk has the given children
+fn vox(children: &[Vec<u8>]) -> Vec<u8>
+{
+    let mut out = b"VOX ".to_vec();
+
+    out.extend_from_slice(&150u32.to_le_bytes());
+    out.extend(chunk(b"MAIN", &[], &children.concat()));
+    out
+}
+
+fn size(x: u32, y: u32, z: u32) -> Vec<u8>
+{
+    chunk(b"SIZE", &[x.to_le_bytes(), y.to_le_bytes(), z.to_le_bytes()].concat(), &[])
+}
+
+fn xyzi(voxels: &[[u8; 4]]) -> Vec<u8>
+{
+    let mut content = (voxels.len() as u32).to_le_bytes().to_vec();
+    content.extend(voxels.concat());
+
+    chunk(b"XYZI", &content, &[])
+}
+
+/// palette where the given indices have a colour, and
+/// the rest are black
+fn rgba(colours: &[(u8, [u8; 4])]) -> Vec<u8>
+{
+    let mut content = vec![0; 256 * 4];
+    for (i, col) in colours
+    {
+        let at = (*i as usize - 1) * 4;
+
+        content[at..at + 4].copy_from_slice(col);
+    }
+    chunk(b"RGBA", &content, &[])
+}
+
+#[test]
+fn test_vox_parse()
+{
+    let data = vox(&[size(2, 3, 1), xyzi(&[[0, 0, 0, 1], [1, 2, 0, 2]]), rgba(&[(1, [128, 128, 128, 255]), (2, [0, 255, 0, 255])])]);
+    let file = VoxFile::parse(&data).unwrap();
+
+    assert_eq!(file.models, vec![VoxModel { size: int3::new(2, 3, 1), voxels: vec![([0, 0, 0], 1), ([1, 2, 0], 2)] }]);
+    assert_eq!(file.palette[0], [0; 4]);
+    assert_eq!(file.palette[1], [128, 128, 128, 255]);
+    assert_eq!(file.palette[2], [0, 255, 0, 255]);
+}
+
+#[test]
+fn test_vox_models()
+{
+    // several models, with chunks that aren't read in between
+    let data = vox(&[
+        chunk(b"PACK", &2u32.to_le_bytes(), &[]),
+        size(1, 1, 1),
+        xyzi(&[[0, 0, 0, 7]]),
+        chunk(b"nTRN", &[1, 2, 3, 4], &[]),
+        size(4, 4, 4),
+        xyzi(&[]),
+        chunk(b"MATL", &[0; 8], &[]),
+    ]);
+    let file = VoxFile::parse(&data).unwrap();
+
+    assert_eq!(file.models.len(), 2);
+    assert_eq!(file.models[0].voxels, vec![([0, 0, 0], 7)]);
+    assert_eq!(file.models[1].size, int3::new(4, 4, 4));
+    assert!(file.models[1].voxels.is_empty());
+
+    // no RGBA chunk, so the default palette
+    assert_eq!(file.palette[1], [0xff, 0xff, 0xff, 0xff]);
+    assert_eq!(file.palette[215], [0x00, 0x00, 0x33, 0xff]);
+    assert_eq!(file.palette[216], [0xee, 0x00, 0x00, 0xff]);
+    assert_eq!(file.palette[236], [0x00, 0x00, 0xee, 0xff]);
+    assert_eq!(file.palette[255], [0x11, 0x11, 0x11, 0xff]);
+}
+
+#[test]
+fn test_vox_errors()
+{
+    let data = vox(&[size(1, 1, 1), xyzi(&[[0, 0, 0, 1]])]);
+
+    assert!(VoxFile::parse(&data).is_ok());
+    assert!(VoxFile::parse(&data[..data.len() - 2]).is_err());
+    assert!(VoxFile::parse(&[b"VXSC".to_vec(), data[4..].to_vec()].concat()).is_err());
+
+    // voxels without a size, or out of it
+    assert!(VoxFile::parse(&vox(&[xyzi(&[[0, 0, 0, 1]])])).is_err());
+    assert!(VoxFile::parse(&vox(&[size(1, 1, 1), xyzi(&[[1, 0, 0, 1]])])).is_err());
+    assert!(VoxFile::parse(&vox(&[size(0, 1, 1), xyzi(&[])])).is_err());
+}
+
+#[test]
+fn test_vox_clipboard()
+{
+    let pal = palette();
+    let data = vox(&[size(2, 3, 1), xyzi(&[[0, 0, 0, 1], [1, 2, 0, 2]]), rgba(&[(1, [128, 128, 128, 255]), (2, [0, 255, 0, 255])])]);
+    let file = VoxFile::parse(&data).unwrap();
+
+    // z up becomes y up
+    let clip = file.to_clipboard(0, &pal, &HashMap::new()).unwrap();
+
+    assert_eq!(clip.size(), int3::new(2, 1, 3));
+    assert_eq!(clip.get(int3::new(0, 0, 2)), Some(pal.block("stone").unwrap()));
+    assert_eq!(clip.get(int3::new(1, 0, 0)), Some(pal.block("grass").unwrap()));
+    assert_eq!(clip.get(int3::new(0, 0, 0)), None);
+
+    // explicit mapping over nearest colour
+    let mapping = [(2, pal.block("sand").unwrap())].iter().copied().collect();
+    let clip = file.to_clipboard(0, &pal, &mapping).unwrap();
+
+    assert_eq!(clip.get(int3::new(0, 0, 2)), Some(pal.block("stone").unwrap()));
+    assert_eq!(clip.get(int3::new(1, 0, 0)), Some(pal.block("sand").unwrap()));
+
+    assert!(file.to_clipboard(1, &pal, &mapping).is_none());
+    assert_eq!(file.to_schematic(0, &pal, &mapping).unwrap().size(), int3::new(2, 1, 3));
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use ezmath::*;
+
+use crate::common::block::{ PackedBlock, RBlockPalette };
+use crate::common::edit::Clipboard;
+use super::{ Schematic, SchematicError };
+use super::reader::Reader;
+
+/// a MagicaVoxel model file(.vox). only the models and the
+/// palette are read: the scene graph, materials and layers
+/// are skipped, so models are imported one by one, untransformed
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoxFile
+{
+    /// models of the file, in order
+    pub models: Vec<VoxModel>,
+    /// colour(RGBA) of each palette index. index 0 is empty
+    pub palette: [[u8; 4]; 256],
+}
+
+/// a model of a .vox file
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoxModel
+{
+    /// number of voxels along each axis, z up
+    pub size: int3,
+    /// every solid voxel, as its position(z up) and its
+    /// palette index
+    pub voxels: Vec<([u8; 3], u8)>,
+}
+
+impl VoxFile
+{
+    /// read a .vox file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SchematicError>
+    {
+        let path = path.as_ref();
+
+        let data = std::fs::read(path)
+            .map_err(|err| SchematicError::Io { path: path.to_path_buf(), err })?;
+
+        Self::parse(&data).map_err(|msg| SchematicError::Corrupt { path: path.to_path_buf(), msg })
+    }
+
+    /// parse the contents of a .vox file, which are a header:
+    ///     - 4 bytes -> magic, "VOX "
+    ///     - i32     -> version
+    ///
+    /// followed by a MAIN chunk, whose children are the SIZE
+    /// and XYZI chunks of every model, in pairs, and an optional
+    /// RGBA palette chunk. every chunk is its 4 byte ID, its
+    /// content's and its children's byte lengths, as i32's, its
+    /// content, then its children. fails with a description
+    /// if the data is damaged
+    pub fn parse(data: &[u8]) -> Result<Self, String>
+    {
+        let mut r = Reader::new(data, ".vox file");
+
+        if r.bytes(4)? != b"VOX "
+        {
+            return Err("not a .vox file".to_string());
+        }
+        let _version = r.u32()?;
+
+        let (id, content, mut children) = r.chunk()?;
+        if id != b"MAIN"
+        {
+            return Err(format!("expected a MAIN chunk, found {}", String::from_utf8_lossy(id)));
+        }
+        if !content.is_empty()
+        {
+            return Err("MAIN chunk has content".to_string());
+        }
+
+        let mut models = Vec::new();
+        let mut size = None;
+        let mut palette = None;
+
+        while !children.rest().is_empty()
+        {
+            let (id, content, _) = children.chunk()?;
+            let mut c = Reader::new(content, ".vox file");
+
+            match id
+            {
+                b"SIZE" =>
+                {
+                    let s = int3::new(c.u32()? as i32, c.u32()? as i32, c.u32()? as i32);
+
+                    if s.iter().any(|c| *c <= 0 || *c > 256)
+                    {
+                        return Err(format!("model size {:?} is out of 1..=256", s));
+                    }
+                    size = Some(s);
+                }
+                b"XYZI" =>
+                {
+                    let size = size
+                        .take()
+                        .ok_or_else(|| "XYZI chunk without a SIZE chunk before it".to_string())?;
+
+                    let n = c.u32()? as usize;
+                    if n > c.rest().len() / 4
+                    {
+                        return Err(format!("XYZI chunk has {} voxels, but not the data for them", n));
+                    }
+
+                    let voxels = (0..n)
+                        .map(|_|
+                        {
+                            let v = c.bytes(4)?;
+                            let pos = [v[0], v[1], v[2]];
+
+                            if (0..3).any(|a| pos[a] as i32 >= size[a])
+                            {
+                                return Err(format!("voxel {:?} is out of its model of size {:?}", pos, size));
+                            }
+                            Ok((pos, v[3]))
+                        })
+                        .collect::<Result<_, String>>()?;
+
+                    models.push(VoxModel { size, voxels });
+                }
+                b"RGBA" =>
+                {
+                    // colour i is that of palette index i + 1
+                    let mut rgba = [[0; 4]; 256];
+                    for i in 0..255
+                    {
+                        let c = c.bytes(4)?;
+
+                        rgba[i + 1] = [c[0], c[1], c[2], c[3]];
+                    }
+                    palette = Some(rgba);
+                }
+                // scene graph, materials, etc.
+                _ => {},
+            }
+        }
+
+        Ok(Self
+        {
+            models,
+            palette: palette.unwrap_or_else(default_palette),
+        })
+    }
+
+    /// get the blocks of one of the models, y up. palette
+    /// indices are mapped to blocks through `mapping`, or to
+    /// the block whose colour is nearest theirs. empty voxels
+    /// are none
+    pub fn to_clipboard(&self, model: usize, pal: &RBlockPalette, mapping: &HashMap<u8, PackedBlock>) -> Option<Clipboard>
+    {
+        let model = self.models.get(model)?;
+
+        // z up to y up, rotating around x
+        let mut clip = Clipboard::new(int3::new(model.size.x, model.size.z, model.size.y));
+        let mut blocks = HashMap::new();
+
+        for (pos, index) in &model.voxels
+        {
+            let block = *blocks
+                .entry(*index)
+                .or_insert_with(|| mapping
+                    .get(index)
+                    .copied()
+                    .unwrap_or_else(|| nearest(self.palette[*index as usize], pal)));
+
+            let pos = int3::new(pos[0] as i32, pos[2] as i32, model.size.y - 1 - pos[1] as i32);
+
+            clip.set(pos, Some(block));
+        }
+        Some(clip)
+    }
+
+    /// get one of the models as a schematic, see
+    /// `VoxFile::to_clipboard`
+    pub fn to_schematic(&self, model: usize, pal: &RBlockPalette, mapping: &HashMap<u8, PackedBlock>) -> Option<Schematic>
+    {
+        self.to_clipboard(model, pal, mapping)
+            .map(|clip| Schematic::from_clipboard(&clip, pal))
+    }
+}
+
+/// get the block of a palette whose colour is nearest to a
+/// colour(RGBA). air is never picked, unless it's the only
+/// block
+fn nearest(col: [u8; 4], pal: &RBlockPalette) -> PackedBlock
+{
+    let col = float3::new(col[0] as f32, col[1] as f32, col[2] as f32) / 255.0;
+
+    pal.iter()
+        .filter(|(id, _)| *id != 0)
+        .map(|(id, block)|
+        {
+            let d = float3::new(block.col.x, block.col.y, block.col.z) - col;
+
+            (id, d.dot(&d))
+        })
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map_or(PackedBlock::default(), |(id, _)| PackedBlock::from_data(id, 0))
+}
+
+/// the palette of .vox files without an RGBA chunk: a 6^3
+/// colour cube, from white to just before black, then ramps
+/// of red, green, blue, and grey
+fn default_palette() -> [[u8; 4]; 256]
+{
+    const CUBE: [u8; 6] = [0xff, 0xcc, 0x99, 0x66, 0x33, 0x00];
+    const RAMP: [u8; 10] = [0xee, 0xdd, 0xbb, 0xaa, 0x88, 0x77, 0x55, 0x44, 0x22, 0x11];
+
+    let mut palette = [[0; 4]; 256];
+    let mut i = 1;
+
+    for r in &CUBE
+    {
+        for g in &CUBE
+        {
+            for b in &CUBE
+            {
+                // black is left out
+                if i < 216
+                {
+                    palette[i] = [*r, *g, *b, 0xff];
+                    i += 1;
+                }
+            }
+        }
+    }
+    for channel in 0..4
+    {
+        for v in &RAMP
+        {
+            palette[i] = match channel
+            {
+                3 => [*v, *v, *v, 0xff],
+                _ =>
+                {
+                    let mut c = [0, 0, 0, 0xff];
+                    c[channel] = *v;
+                    c
+                }
+            };
+            i += 1;
+        }
+    }
+    palette
+}
+
+impl<'a> Reader<'a>
+{
+    /// read a .vox chunk, as its ID, content, and children
+    fn chunk(&mut self) -> Result<(&'a [u8], &'a [u8], Reader<'a>), String>
+    {
+        let id = self.bytes(4)?;
+        let content = self.u32()? as usize;
+        let children = self.u32()? as usize;
+
+        Ok((id, self.bytes(content)?, Reader::new(self.bytes(children)?, ".vox file")))
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::{ Arc, Mutex };
+use std::path::PathBuf;
+
+use ezgame::ecs::*;
+use ezmath::*;
+
+use crate::common::block::{ RBlockPalette, BlockEntity };
+use crate::common::chunk::{ CChunk, CBlockBuffer, CLightBuffer, RChunkCache, WorldBlocks };
+use crate::common::light::RLightQueue;
+
+/// the game's block palette, as loaded at startup
+pub fn palette() -> RBlockPalette
+{
+    RBlockPalette::load(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/blocks")).unwrap()
+}
+
+/// fresh, empty directory for a test
+pub fn temp_dir(name: &str) -> PathBuf
+{
+    let dir = std::env::temp_dir().join(format!("voxels-{}-{}", name, std::process::id()));
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+/// a block entity holding text, for a block of ID 3 and
+/// variant 2
+pub fn sign(text: &str) -> BlockEntity
+{
+    BlockEntity::new(3, 2, text.to_string())
+}
+
+/// a registry to run systems on, with the resources every
+/// chunk system needs: the palette, the chunk cache and
+/// the light queue
+pub struct TestWorld
+{
+    pub registry: Registry,
+    pub resources: Resources,
+}
+
+impl TestWorld
+{
+    pub fn new() -> Self
+    {
+        let mut resources = Resources::default();
+
+        resources.insert(palette());
+        resources.insert(RChunkCache::new());
+        resources.insert(RLightQueue::default());
+
+        Self
+        {
+            registry: RegistryFactory::new().create_world(),
+            resources,
+        }
+    }
+
+    /// add a chunk that has its blocks, and store it in
+    /// the cache
+    pub fn chunk(&mut self, pos: int3, blocks: CBlockBuffer) -> Entity
+    {
+        let ent = self.registry.insert((), vec![(CChunk::new(pos), blocks, CLightBuffer::new())])[0];
+        let mut r_cache = self.resources.get_mut::<RChunkCache>().unwrap();
+
+        r_cache.store(pos, ent);
+        r_cache.mark_generated(pos);
+
+        ent
+    }
+
+    /// run a single system, then flush its commands
+    pub fn run(&mut self, job: Job)
+    {
+        Systems::builder()
+            .add_system(job)
+            .flush()
+            .build()
+            .execute(&mut self.registry, &mut self.resources);
+    }
+
+    /// edit the blocks of the world from within a system, then
+    /// flush its commands. returns what `f` returns
+    pub fn edit<T: Send + 'static>(&mut self, f: impl FnOnce(&mut WorldBlocks) -> T + Send + 'static) -> T
+    {
+        // systems may run more than once, `f` can't
+        let f = Mutex::new(Some(f));
+        let out = Arc::new(Mutex::new(None));
+        let res = out.clone();
+
+        self.run(sys("edit_system")
+            .write_component::<CBlockBuffer>()
+            .read_resource::<RChunkCache>()
+            .write_resource::<RLightQueue>()
+            .build(move |cmd, world, (r_cache, r_light), _|
+            {
+                if let Some(f) = f.lock().unwrap().take()
+                {
+                    *res.lock().unwrap() = Some(f(&mut WorldBlocks::new(world, cmd, &r_cache, &mut r_light)));
+                }
+            }));
+
+        let out = out.lock().unwrap().take();
+        out.expect("edit system didn't run")
+    }
+}
@@ -27,22 +27,34 @@ impl Game for TestGame
                 .height(600)
                 .title("voxels")
         );
-        app.resources().insert(common::block::RBlockPalette::load(""));
+        let palette = common::block::RBlockPalette::load(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/blocks"))
+            .unwrap_or_else(|e| panic!("[error] couldn't load block palette: {}", e));
+        let save = common::save::RWorldSave::open(concat!(env!("CARGO_MANIFEST_DIR"), "/saves/world"), &palette)
+            .unwrap_or_else(|e| panic!("[error] couldn't open world save: {}", e));
+        app.resources().insert
+        (
+            common::gen::RWorldGenerator::load(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/world/generator.ron"), save.meta().seed, &palette)
+                .unwrap_or_else(|e| panic!("[error] couldn't load world generator: {}", e))
+        );
+        let spawn = save.meta().spawn;
+        app.resources().insert(save);
+        app.resources().insert(palette);
         
         // insert standard camera into world
         let camera_components = 
         {
             use crate::common::transform::*;
+            use crate::common::chunk::TChunkLoader;
             use crate::client::camera::*;
 
             (
-                (TMainCamera,),
+                (TMainCamera, TChunkLoader),
                 vec!
                 [(
                     CCamera::new(45f32.to_radians(), 0.01, 1000.0, 1.0, 1.0),
                     CLocalToWorld::default(),
 
-                    CTranslation(ezmath::float3::new(48.0, 12.0, 48.0)),
+                    CTranslation(ezmath::float3::new(spawn[0] as f32, spawn[1] as f32, spawn[2] as f32)),
                     CRotation::default()
                 )]
             )